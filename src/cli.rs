@@ -0,0 +1,185 @@
+use crate::config::ConfigStore;
+use crate::data::structs::Server;
+use crate::export;
+use crate::secrets;
+
+use std::env;
+use std::fs as std_fs;
+use std::io::{self, Read};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+const PASSWORD_ENV: &str = "RS_POSTGRES_PASSWORD";
+
+pub const USAGE: &str = "Usage:
+  rs-postgres [--config <path>] [--debug]
+  rs-postgres [--config <path>] query --server <alias> [--db <name>] [-f <script.sql>] [--format csv|json|table]
+
+Without a command the GUI is started.
+
+query    Runs SQL from a file, or from stdin when -f is omitted or `-`, against a
+         saved server and prints the result (table by default). The master
+         password is read from RS_POSTGRES_PASSWORD or prompted for.";
+
+#[derive(Clone, Copy, Debug)]
+pub enum OutputFormat {
+    Csv,
+    Json,
+    Table,
+}
+
+#[derive(Debug)]
+pub struct QueryArgs {
+    pub server: String,
+    pub database: Option<String>,
+    pub file: Option<PathBuf>,
+    pub format: OutputFormat,
+}
+
+#[derive(Debug)]
+pub enum Command {
+    Query(QueryArgs),
+    Help,
+}
+
+/// Returns `None` when no command is given and the GUI should start.
+/// `--config` and `--debug` are global and handled by `main`.
+pub fn parse(args: &[String]) -> Option<Result<Command, String>> {
+    let mut rest = Vec::new();
+    let mut iter = args.iter().skip(1);
+
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--debug" => {}
+            "--config" => {
+                iter.next();
+            }
+            _ => rest.push(arg.as_str()),
+        }
+    }
+
+    let (command, options) = rest.split_first()?;
+
+    Some(match *command {
+        "query" => parse_query(options).map(Command::Query),
+        "help" | "--help" | "-h" => Ok(Command::Help),
+        command => Err(format!("Unknown command: {}", command)),
+    })
+}
+
+fn parse_query(options: &[&str]) -> Result<QueryArgs, String> {
+    let mut server = None;
+    let mut database = None;
+    let mut file = None;
+    let mut format = OutputFormat::Table;
+
+    let mut iter = options.iter();
+    while let Some(option) = iter.next() {
+        let mut value = || {
+            iter.next()
+                .map(|value| value.to_string())
+                .ok_or_else(|| format!("{} requires a value", option))
+        };
+
+        match *option {
+            "--server" | "-s" => server = Some(value()?),
+            "--db" | "-d" => database = Some(value()?),
+            "--file" | "-f" => file = Some(PathBuf::from(value()?)),
+            "--format" => {
+                format = match value()?.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    "table" => OutputFormat::Table,
+                    other => return Err(format!("Unknown format: {}", other)),
+                }
+            }
+            other => return Err(format!("Unknown option: {}", other)),
+        }
+    }
+
+    Ok(QueryArgs {
+        server: server.ok_or_else(|| String::from("--server is required"))?,
+        database,
+        file,
+        format,
+    })
+}
+
+pub fn run(command: Command, config_path: Option<PathBuf>) -> i32 {
+    let result = match command {
+        Command::Help => {
+            println!("{}", USAGE);
+            Ok(())
+        }
+        Command::Query(args) => query(args, config_path),
+    };
+
+    match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            1
+        }
+    }
+}
+
+/// Finds a saved server by alias and decrypts its password.
+fn unlock_server(config_path: Option<PathBuf>, alias: &str) -> Result<Server, String> {
+    let config = ConfigStore::new(config_path).read()?;
+
+    let mut server = config
+        .servers
+        .iter()
+        .find(|server| server.alias == alias)
+        .cloned()
+        .ok_or_else(|| format!("Server not found: {}", alias))?;
+
+    let vault = config.vault.as_ref().ok_or_else(|| {
+        String::from("No master password is set up yet. Start the GUI once to create it.")
+    })?;
+
+    let password = match env::var(PASSWORD_ENV) {
+        Ok(password) => password,
+        Err(_) => rpassword::prompt_password("Master password: ").map_err(|e| e.to_string())?,
+    };
+
+    let vault_key = vault.unlock(&password)?;
+    let keyring: Arc<dyn secrets::SecretStore> = Arc::new(secrets::KeyringStore);
+    server.password = secrets::open(config.settings.secrets_backend, &vault_key, &keyring)
+        .load(server.id, &server.password)?
+        .ok_or_else(|| format!("No password is stored for server {}", alias))?;
+
+    Ok(server)
+}
+
+fn query(args: QueryArgs, config_path: Option<PathBuf>) -> Result<(), String> {
+    let server = unlock_server(config_path, &args.server)?;
+
+    let code = match &args.file {
+        Some(path) if path.as_os_str() != "-" => {
+            std_fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?
+        }
+        _ => {
+            let mut code = String::new();
+            io::stdin()
+                .read_to_string(&mut code)
+                .map_err(|e| e.to_string())?;
+            code
+        }
+    };
+
+    let database_name = args.database.as_deref().unwrap_or(&server.service_database);
+    let database = server.connect(database_name);
+
+    let runtime = tokio::runtime::Runtime::new().map_err(|e| e.to_string())?;
+    let output = runtime
+        .block_on(database.execute_query(&code))
+        .map_err(|e| e.to_string())?;
+
+    let stdout = io::stdout().lock();
+    match args.format {
+        OutputFormat::Csv => export::write_csv(&output, stdout),
+        OutputFormat::Json => export::write_json(&output, stdout),
+        OutputFormat::Table => export::write_table(&output, stdout),
+    }
+}
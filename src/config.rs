@@ -0,0 +1,255 @@
+use crate::data::structs::{Config, Settings, Theme};
+use crate::utils;
+
+use log::info;
+use serde_json::Value;
+use std::fs as std_fs;
+use std::path::PathBuf;
+use uuid::Uuid;
+
+pub const CONFIG_VERSION: u32 = 1;
+
+const BACKUPS_COUNT: usize = 5;
+
+/// Location of the config file plus the state needed to back it up once
+/// per run before the first write.
+pub struct ConfigStore {
+    pub path: PathBuf,
+    backed_up: bool,
+}
+
+impl ConfigStore {
+    pub fn new(path: Option<PathBuf>) -> Self {
+        Self {
+            path: path.unwrap_or_else(default_path),
+            backed_up: false,
+        }
+    }
+
+    /// Session file next to the config, so separate profiles keep separate
+    /// sessions.
+    pub fn session_path(&self) -> PathBuf {
+        match self.path.file_stem().and_then(|stem| stem.to_str()) {
+            Some("config") | None => self.path.with_file_name("session.json"),
+            Some(stem) => self.path.with_file_name(format!("{}.session.json", stem)),
+        }
+    }
+
+    fn backup_path(&self, n: usize) -> PathBuf {
+        let file_name = self
+            .path
+            .file_name()
+            .map(|name| name.to_string_lossy().to_string())
+            .unwrap_or_default();
+
+        self.path.with_file_name(format!("{}.{}", file_name, n))
+    }
+
+    pub fn latest_backup(&self) -> Option<PathBuf> {
+        Some(self.backup_path(1)).filter(|path| path.exists())
+    }
+
+    pub fn load(&mut self) -> Result<Config, String> {
+        if let Some(dir) = self.path.parent()
+            && !dir.as_os_str().is_empty()
+        {
+            std_fs::create_dir_all(dir).map_err(|e| format!("{}: {}", dir.display(), e))?;
+        }
+
+        let (config, changed) = self.read_migrated()?;
+
+        if changed {
+            self.save(&config)?;
+        }
+
+        Ok(config)
+    }
+
+    /// Like `load`, but only migrates in memory and never touches the
+    /// disk, for readers such as the CLI running next to the GUI.
+    pub fn read(&self) -> Result<Config, String> {
+        self.read_migrated().map(|(config, _)| config)
+    }
+
+    /// The config brought up to date, and whether it has to be saved.
+    fn read_migrated(&self) -> Result<(Config, bool), String> {
+        if !self.path.exists() {
+            let config = Config {
+                version: CONFIG_VERSION,
+                ..Default::default()
+            };
+
+            return Ok((config, true));
+        }
+
+        let content = std_fs::read_to_string(&self.path)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+        let mut value: Value = serde_json::from_str(&content)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+
+        let mut changed = migrate(&mut value)?;
+
+        let mut config: Config = serde_json::from_value(value)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+
+        if !config.settings.theme.is_inited() {
+            config.settings.theme = Theme::Dark;
+            changed = true;
+        }
+
+        Ok((config, changed))
+    }
+
+    /// Writes the config through a temporary file, keeping the previous
+    /// versions as `config.json.1` (newest) to `config.json.5`.
+    pub fn save(&mut self, config: &Config) -> Result<(), String> {
+        if !self.backed_up && self.path.exists() {
+            self.rotate_backups()?;
+            self.backed_up = true;
+        }
+
+        let content = serde_json::to_string_pretty(config).map_err(|e| e.to_string())?;
+
+        utils::write_atomic(&self.path, &content)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))
+    }
+
+    fn rotate_backups(&self) -> Result<(), String> {
+        for n in (1..BACKUPS_COUNT).rev() {
+            let from = self.backup_path(n);
+            if from.exists() {
+                std_fs::rename(&from, self.backup_path(n + 1))
+                    .map_err(|e| format!("{}: {}", from.display(), e))?;
+            }
+        }
+
+        std_fs::copy(&self.path, self.backup_path(1))
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+
+        Ok(())
+    }
+
+    pub fn restore_latest_backup(&mut self) -> Result<(), String> {
+        let backup_path = self.backup_path(1);
+
+        std_fs::copy(&backup_path, &self.path)
+            .map_err(|e| format!("{}: {}", backup_path.display(), e))?;
+
+        Ok(())
+    }
+
+    /// Moves an unreadable config aside so a fresh one can be created.
+    pub fn reset(&mut self) -> Result<(), String> {
+        let broken_path = self.path.with_extension("json.broken");
+
+        std_fs::rename(&self.path, &broken_path)
+            .map_err(|e| format!("{}: {}", self.path.display(), e))?;
+
+        info!("Moved unreadable config to {}", broken_path.display());
+
+        Ok(())
+    }
+}
+
+pub fn default_path() -> PathBuf {
+    dirs::config_dir()
+        .unwrap_or_default()
+        .join("rs-postgres")
+        .join("config.json")
+}
+
+/// Brings an older config up to `CONFIG_VERSION`, one version at a time.
+/// Returns whether anything was changed.
+fn migrate(value: &mut Value) -> Result<bool, String> {
+    let Value::Object(config) = value else {
+        return Err(String::from("Config must be a JSON object"));
+    };
+
+    let version = config
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+
+    if version > CONFIG_VERSION {
+        return Err(format!(
+            "Config version {} is newer than supported version {}",
+            version, CONFIG_VERSION
+        ));
+    }
+
+    for from in version..CONFIG_VERSION {
+        info!("Migrating config from version {} to {}", from, from + 1);
+
+        match from {
+            0 => migrate_v0(config),
+            _ => unreachable!(),
+        }
+    }
+
+    config.insert(String::from("version"), Value::from(CONFIG_VERSION));
+
+    Ok(version != CONFIG_VERSION)
+}
+
+/// Unversioned configs: fill settings added over time and give every server
+/// a persistent ID.
+fn migrate_v0(config: &mut serde_json::Map<String, Value>) {
+    let default_settings = serde_json::to_value(Settings::default()).unwrap();
+
+    match config.get_mut("settings") {
+        Some(Value::Object(settings)) => {
+            if let Value::Object(default_settings) = default_settings {
+                for (key, default_value) in default_settings {
+                    settings.entry(key).or_insert(default_value);
+                }
+            }
+        }
+        _ => {
+            config.insert(String::from("settings"), default_settings);
+        }
+    }
+
+    config
+        .entry("servers")
+        .or_insert_with(|| Value::Array(Vec::new()));
+
+    if let Some(Value::Array(servers)) = config.get_mut("servers") {
+        for server in servers.iter_mut().filter_map(Value::as_object_mut) {
+            let has_id = server
+                .get("id")
+                .and_then(Value::as_str)
+                .and_then(|id| Uuid::parse_str(id).ok())
+                .is_some_and(|id| !id.is_nil());
+
+            if !has_id {
+                server.insert(String::from("id"), Value::from(Uuid::new_v4().to_string()));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_without_writing() {
+        let dir = std::env::temp_dir().join(format!("rs-postgres-test-{}", Uuid::new_v4()));
+        let store = ConfigStore::new(Some(dir.join("config.json")));
+
+        assert_eq!(store.read().unwrap().version, CONFIG_VERSION);
+        assert!(!dir.exists());
+
+        std_fs::create_dir_all(&dir).unwrap();
+        let legacy = r#"{"servers": [{"alias": "test", "ip": "127.0.0.1", "port": 5432, "user": "postgres", "password": "", "service_database": "postgres"}]}"#;
+        std_fs::write(&store.path, legacy).unwrap();
+
+        let config = store.read().unwrap();
+        assert_eq!(config.version, CONFIG_VERSION);
+        assert!(!config.servers[0].id.is_nil());
+        assert_eq!(std_fs::read_to_string(&store.path).unwrap(), legacy);
+        assert!(store.latest_backup().is_none());
+
+        std_fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -2,27 +2,124 @@ use serde::{Deserialize, Serialize};
 
 use indexmap::IndexMap;
 
+use std::collections::{HashMap, VecDeque};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+use uuid::Uuid;
 
 use egui::{Color32, Theme as EguiTheme};
 
 use crate::data::translates::Language;
+use crate::secrets::SecretsBackend;
+use crate::vault::Vault;
 
 #[derive(Serialize, Deserialize, Clone, Debug, Default)]
 pub struct Config {
+    #[serde(default)]
+    pub version: u32,
     pub servers: Vec<Server>,
+    #[serde(default)]
+    pub folders: Vec<String>,
+    #[serde(default)]
+    pub recent_files: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub password_hash: Option<String>,
+    #[serde(default)]
+    pub vault: Option<Vault>,
     pub settings: Settings,
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
 pub struct Server {
+    #[serde(default)]
+    pub id: Uuid,
     pub alias: String,
     pub ip: String,
     pub port: u16,
     pub user: String,
     pub password: String,
     pub service_database: String,
+    #[serde(default = "default_pool_size")]
+    pub pool_size: u32,
+    #[serde(default = "default_pool_idle_timeout")]
+    pub pool_idle_timeout: u64,
+    #[serde(default)]
+    pub folder: Option<String>,
+    #[serde(default)]
+    pub color: Option<ServerColor>,
+    #[serde(default)]
+    pub favorite: bool,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum ServerColor {
+    Red,
+    Orange,
+    Yellow,
+    Green,
+    Blue,
+    Purple,
+}
+
+impl ServerColor {
+    pub const ALL: [ServerColor; 6] = [
+        ServerColor::Red,
+        ServerColor::Orange,
+        ServerColor::Yellow,
+        ServerColor::Green,
+        ServerColor::Blue,
+        ServerColor::Purple,
+    ];
+
+    pub fn to_color32(self) -> Color32 {
+        match self {
+            ServerColor::Red => Color32::from_rgb(224, 80, 80),
+            ServerColor::Orange => Color32::from_rgb(232, 146, 60),
+            ServerColor::Yellow => Color32::from_rgb(220, 196, 60),
+            ServerColor::Green => Color32::from_rgb(92, 184, 92),
+            ServerColor::Blue => Color32::from_rgb(80, 140, 224),
+            ServerColor::Purple => Color32::from_rgb(160, 100, 210),
+        }
+    }
+}
+
+/// Returns the parent of a `/`-separated folder path, `None` for top-level folders.
+pub fn folder_parent(path: &str) -> Option<String> {
+    path.rsplit_once('/').map(|(parent, _)| parent.to_string())
+}
+
+pub fn folder_name(path: &str) -> &str {
+    path.rsplit_once('/').map_or(path, |(_, name)| name)
+}
+
+pub fn default_pool_size() -> u32 {
+    4
+}
+
+pub fn default_pool_idle_timeout() -> u64 {
+    300
+}
+
+impl Server {
+    pub fn page_title(&self, database: &str) -> String {
+        format!("{} ({}:{})", database, self.ip, self.port)
+    }
+
+    pub fn connect(&self, database: &str) -> crate::database::Database {
+        let options = sqlx::postgres::PgConnectOptions::new()
+            .host(&self.ip)
+            .port(self.port)
+            .username(&self.user)
+            .password(&self.password)
+            .database(database);
+
+        crate::database::Database::new(
+            options,
+            self.pool_size,
+            Duration::from_secs(self.pool_idle_timeout),
+        )
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -30,6 +127,17 @@ pub struct Settings {
     pub scale_factor: f32,
     pub theme: Theme,
     pub language: Language,
+    #[serde(default = "default_restore_session")]
+    pub restore_session: bool,
+    #[serde(default)]
+    pub secrets_backend: SecretsBackend,
+    /// Minutes without input before the app locks; 0 disables auto-lock.
+    #[serde(default)]
+    pub auto_lock_minutes: u32,
+}
+
+pub fn default_restore_session() -> bool {
+    true
 }
 
 #[derive(Serialize, Deserialize, Clone, Debug)]
@@ -50,10 +158,7 @@ impl Theme {
     }
 
     pub fn is_inited(&self) -> bool {
-        match self {
-            Theme::NotInited => false,
-            _ => true,
-        }
+        !matches!(self, Theme::NotInited)
     }
 
     pub fn text_input_color(&self) -> Color32 {
@@ -71,6 +176,9 @@ impl Default for Settings {
             scale_factor: 1.125,
             theme: Theme::NotInited,
             language: Language::English,
+            restore_session: default_restore_session(),
+            secrets_backend: SecretsBackend::default(),
+            auto_lock_minutes: 0,
         }
     }
 }
@@ -83,6 +191,8 @@ pub struct AddServerWindow {
     pub user_field: String,
     pub password_field: String,
     pub service_database_field: String,
+    pub pool_size_field: String,
+    pub pool_idle_timeout_field: String,
 }
 
 impl Default for AddServerWindow {
@@ -95,6 +205,8 @@ impl Default for AddServerWindow {
             user_field: String::new(),
             password_field: String::new(),
             service_database_field: String::from("postgres"),
+            pool_size_field: default_pool_size().to_string(),
+            pool_idle_timeout_field: default_pool_idle_timeout().to_string(),
         }
     }
 }
@@ -114,10 +226,368 @@ pub struct EditServerWindow {
     pub user_field: String,
     pub password_field: String,
     pub service_database_field: String,
+    pub pool_size_field: String,
+    pub pool_idle_timeout_field: String,
     pub server: Option<Server>,
     pub original_server: Option<Server>,
 }
 
+#[derive(Default)]
+pub struct FolderWindow {
+    pub show: bool,
+    pub name_field: String,
+    pub parent: Option<String>,
+    pub original_path: Option<String>,
+}
+
+#[derive(Default)]
+pub struct UnsavedChangesWindow {
+    pub show: bool,
+    pub page_index: Option<usize>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BackendAction {
+    Cancel,
+    Terminate,
+}
+
+#[derive(Default)]
+pub struct BackendActionWindow {
+    pub show: bool,
+    pub action: Option<BackendAction>,
+    pub pids: Vec<i32>,
+    pub page_index: Option<usize>,
+}
+
+/// Asks for sample values of the `$n` parameters of a normalised
+/// statement before running EXPLAIN on it.
+#[derive(Default)]
+pub struct ExplainWindow {
+    pub show: bool,
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: Option<crate::database::Database>,
+    pub query: String,
+    pub parameters: Vec<String>,
+    /// Whether the statement looks like it doesn't write. Only a hint, as
+    /// ANALYZE always runs in a read-only transaction that is rolled back.
+    pub read_only: bool,
+    pub analyze: bool,
+}
+
+#[derive(Clone, Debug)]
+pub enum CommandStatus {
+    Running,
+    Success,
+    Error(String),
+}
+
+/// What to reload once a script from `ScriptWindow` has been applied.
+#[derive(Clone, Debug, Default)]
+pub enum AfterScript {
+    #[default]
+    Nothing,
+    ReloadRoles(Uuid),
+    ReloadPrivileges(usize),
+    ReloadDatabases(Uuid),
+    /// Server, old and new name of the database.
+    RenameDatabase(Uuid, String, String),
+    ReloadTable(usize),
+    ReloadIndexes(usize),
+    ReloadSchemaDiff(usize),
+    ReloadDataCompare(usize),
+}
+
+/// Shows generated SQL for review and runs it on request.
+#[derive(Default)]
+pub struct ScriptWindow {
+    pub show: bool,
+    pub title: String,
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: Option<crate::database::Database>,
+    pub sql: String,
+    /// Shown instead of `sql` when it contains a password.
+    pub masked_sql: Option<String>,
+    pub status: Option<Arc<Mutex<CommandStatus>>>,
+    pub after: AfterScript,
+    /// Closed before the script runs, for statements such as `DROP DATABASE`
+    /// that fail while the app still holds connections to it.
+    pub disconnect: Option<crate::database::Database>,
+}
+
+/// Lists offered by the create and alter database dialogs.
+#[derive(Clone, Default)]
+pub struct DatabaseOptions {
+    pub owners: Vec<String>,
+    pub templates: Vec<String>,
+    pub tablespaces: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct CreateDatabaseWindow {
+    pub show: bool,
+    pub server_id: Uuid,
+    pub name: String,
+    pub owner: String,
+    pub encoding: String,
+    pub locale: String,
+    pub template: String,
+    pub tablespace: String,
+    pub options: Arc<Mutex<Option<Result<DatabaseOptions, String>>>>,
+}
+
+#[derive(Default)]
+pub struct AlterDatabaseWindow {
+    pub show: bool,
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub original_owner: String,
+    pub name: String,
+    pub owner: String,
+    pub options: Arc<Mutex<Option<Result<DatabaseOptions, String>>>>,
+    /// Source of `original_owner`.
+    pub properties: Arc<Mutex<Option<Result<DatabaseProperties, String>>>>,
+}
+
+#[derive(Default)]
+pub struct DropDatabaseWindow {
+    pub show: bool,
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub force: bool,
+}
+
+#[derive(Default)]
+pub struct DatabasePropertiesWindow {
+    pub show: bool,
+    pub database_name: String,
+    pub properties: Arc<Mutex<Option<Result<DatabaseProperties, String>>>>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BackupTool {
+    #[default]
+    Dump,
+    Restore,
+    Psql,
+}
+
+impl BackupTool {
+    pub fn program(&self) -> &'static str {
+        match self {
+            BackupTool::Dump => "pg_dump",
+            BackupTool::Restore => "pg_restore",
+            BackupTool::Psql => "psql",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum DumpFormat {
+    #[default]
+    Custom,
+    Directory,
+    Tar,
+    Plain,
+}
+
+impl DumpFormat {
+    pub const ALL: [DumpFormat; 4] = [DumpFormat::Custom, DumpFormat::Directory, DumpFormat::Tar, DumpFormat::Plain];
+
+    /// Value of `pg_dump --format`.
+    pub fn flag(&self) -> &'static str {
+        match self {
+            DumpFormat::Custom => "c",
+            DumpFormat::Directory => "d",
+            DumpFormat::Tar => "t",
+            DumpFormat::Plain => "p",
+        }
+    }
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            DumpFormat::Custom => "Custom",
+            DumpFormat::Directory => "Directory",
+            DumpFormat::Tar => "Tar",
+            DumpFormat::Plain => "Plain SQL",
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DumpFormat::Custom => "dump",
+            DumpFormat::Directory => "",
+            DumpFormat::Tar => "tar",
+            DumpFormat::Plain => "sql",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub enum BackupResult {
+    Success,
+    Failed(Option<i32>),
+    Cancelled,
+    Error(String),
+}
+
+/// Output and outcome of a running `pg_dump`, `pg_restore` or `psql`.
+pub struct BackupProcess {
+    pub log: VecDeque<String>,
+    pub cancel: bool,
+    pub started_at: Instant,
+    pub finished: Option<(BackupResult, Duration)>,
+}
+
+#[derive(Default)]
+pub struct BackupWindow {
+    pub show: bool,
+    pub tool: BackupTool,
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub path: String,
+    pub format: DumpFormat,
+    /// Comma separated patterns passed as `--schema`.
+    pub schemas: String,
+    /// Comma separated patterns passed as `--table`.
+    pub tables: String,
+    pub data_only: bool,
+    pub schema_only: bool,
+    pub clean: bool,
+    pub single_transaction: bool,
+    pub compression: u32,
+    pub jobs: u32,
+    pub process: Option<Arc<Mutex<BackupProcess>>>,
+}
+
+/// A message the server sent while a statement ran, such as `RAISE NOTICE`
+/// or the output of `VACUUM VERBOSE`.
+#[derive(Clone, Debug)]
+pub struct ServerNotice {
+    pub severity: &'static str,
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum MaintenanceCommand {
+    #[default]
+    Vacuum,
+    Analyze,
+    Reindex,
+    Cluster,
+}
+
+impl MaintenanceCommand {
+    pub const ALL: [MaintenanceCommand; 4] = [
+        MaintenanceCommand::Vacuum,
+        MaintenanceCommand::Analyze,
+        MaintenanceCommand::Reindex,
+        MaintenanceCommand::Cluster,
+    ];
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            MaintenanceCommand::Vacuum => "VACUUM",
+            MaintenanceCommand::Analyze => "ANALYZE",
+            MaintenanceCommand::Reindex => "REINDEX",
+            MaintenanceCommand::Cluster => "CLUSTER",
+        }
+    }
+}
+
+/// A row of one of the `pg_stat_progress_*` views.
+#[derive(Clone, Debug)]
+pub struct MaintenanceProgress {
+    pub relation: String,
+    pub phase: String,
+    pub done: i64,
+    pub total: i64,
+}
+
+pub struct MaintenanceProcess {
+    pub notices: Vec<ServerNotice>,
+    pub progress: Option<MaintenanceProgress>,
+    pub pid: Option<i32>,
+    pub started_at: Instant,
+    pub finished: Option<(Result<(), String>, Duration)>,
+}
+
+#[derive(Default)]
+pub struct MaintenanceWindow {
+    pub show: bool,
+    pub database_name: String,
+    pub database: Option<crate::database::Database>,
+    /// A pool of its own for following and cancelling the command, so that
+    /// neither waits for a free connection of `database`.
+    pub monitor: Option<crate::database::Database>,
+    /// `None` runs the command on the whole database.
+    pub table: Option<String>,
+    /// Schema qualified name of `table`.
+    pub relation: Arc<Mutex<Option<Result<String, String>>>>,
+    pub command: MaintenanceCommand,
+    pub full: bool,
+    pub freeze: bool,
+    pub analyze: bool,
+    pub verbose: bool,
+    pub concurrently: bool,
+    /// Index for `CLUSTER`; empty reuses the one the table was clustered on.
+    pub index: String,
+    pub process: Option<Arc<Mutex<MaintenanceProcess>>>,
+}
+
+#[derive(Default)]
+pub struct CreateIndexWindow {
+    pub show: bool,
+    pub page_index: usize,
+    pub schema: String,
+    pub table: String,
+    pub available_columns: Vec<String>,
+    pub name: String,
+    pub unique: bool,
+    pub method: String,
+    /// Comma-separated columns or expressions, as SQL.
+    pub columns: String,
+    pub include: String,
+    pub predicate: String,
+    pub concurrently: bool,
+}
+
+#[derive(Default)]
+pub struct RoleWindow {
+    pub show: bool,
+    pub server_id: Uuid,
+    /// `None` when creating a role.
+    pub original: Option<RoleInfo>,
+    pub role: RoleInfo,
+    pub password: String,
+    pub available_roles: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ErrorWindow {
+    pub show: bool,
+    pub message: Option<String>,
+}
+
+impl ErrorWindow {
+    pub fn with_message(message: impl ToString) -> Self {
+        log::error!("{}", message.to_string());
+
+        Self {
+            show: true,
+            message: Some(message.to_string()),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct RecoveryKeyWindow {
+    pub show: bool,
+    pub recovery_key: String,
+}
+
 #[derive(Default)]
 pub struct SQLResponseCopyWindow {
     pub show: bool,
@@ -130,6 +600,9 @@ pub struct SettingsWindow {
     pub scale_factor: f32,
     pub theme: Theme,
     pub language: Option<Language>,
+    pub restore_session: Option<bool>,
+    pub secrets_backend: Option<SecretsBackend>,
+    pub auto_lock_minutes: Option<u32>,
 }
 
 impl Default for SettingsWindow {
@@ -139,6 +612,9 @@ impl Default for SettingsWindow {
             scale_factor: 0.0,
             theme: Theme::NotInited,
             language: None,
+            restore_session: None,
+            secrets_backend: None,
+            auto_lock_minutes: None,
         }
     }
 }
@@ -146,7 +622,11 @@ impl Default for SettingsWindow {
 pub struct LoginWindow {
     pub show: bool,
     pub clear_storage: bool,
+    pub recovery: bool,
     pub password: String,
+    pub recovery_key: String,
+    pub new_password: String,
+    pub generate_recovery_key: bool,
     pub error: Option<String>,
 }
 
@@ -155,12 +635,17 @@ impl Default for LoginWindow {
         Self {
             show: true,
             clear_storage: false,
+            recovery: false,
             password: String::new(),
+            recovery_key: String::new(),
+            new_password: String::new(),
+            generate_recovery_key: true,
             error: None,
         }
     }
 }
 
+#[derive(Default)]
 pub struct ChangePasswordWindow {
     pub show: bool,
     pub old_password: String,
@@ -169,18 +654,6 @@ pub struct ChangePasswordWindow {
     pub error: Option<String>,
 }
 
-impl Default for ChangePasswordWindow {
-    fn default() -> Self {
-        Self {
-            show: false,
-            old_password: String::new(),
-            new_password: String::new(),
-            confirm_password: String::new(),
-            error: None,
-        }
-    }
-}
-
 pub struct Icons<'a> {
     pub warning_light: egui::Image<'a>,
     pub warning_dark: egui::Image<'a>,
@@ -191,7 +664,58 @@ pub struct Icons<'a> {
 pub struct LoadedDatabase {
     pub name: String,
     pub database: crate::database::Database,
-    pub tables: Vec<String>,
+    pub tables: Arc<Mutex<Option<TablesState>>>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DatabaseProperties {
+    pub owner: String,
+    pub encoding: String,
+    pub collation: String,
+    pub ctype: String,
+    pub tablespace: String,
+    pub size: i64,
+    pub connections: i64,
+    /// -1 means no limit.
+    pub connection_limit: i32,
+    pub allow_connections: bool,
+    pub is_template: bool,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct RoleInfo {
+    pub name: String,
+    pub superuser: bool,
+    pub inherit: bool,
+    pub create_role: bool,
+    pub create_db: bool,
+    pub login: bool,
+    pub replication: bool,
+    /// -1 means no limit.
+    pub connection_limit: i32,
+    pub valid_until: Option<String>,
+    pub member_of: Vec<String>,
+}
+
+impl RoleInfo {
+    /// Built-in roles such as `pg_monitor`.
+    pub fn is_predefined(&self) -> bool {
+        self.name.starts_with("pg_")
+    }
+}
+
+#[derive(Clone)]
+pub enum RolesState {
+    Loading,
+    Loaded(Vec<RoleInfo>),
+    Error(String),
+}
+
+#[derive(Clone)]
+pub enum TablesState {
+    Loading,
+    Loaded(Vec<String>),
+    Error(String),
 }
 
 #[derive(Clone)]
@@ -211,11 +735,51 @@ pub struct SQLQueryExecutionSuccess {
     pub page_index: u32,
 }
 
+/// An error returned by `execute_query`. Apart from `message`, fields are
+/// only set when the server reported them.
+#[derive(Clone, Debug, Default)]
+pub struct QueryError {
+    pub message: String,
+    pub severity: Option<&'static str>,
+    /// SQLSTATE code.
+    pub code: Option<String>,
+    pub detail: Option<String>,
+    pub hint: Option<String>,
+    pub context: Option<String>,
+    pub schema: Option<String>,
+    pub table: Option<String>,
+    pub column: Option<String>,
+    pub data_type: Option<String>,
+    pub constraint: Option<String>,
+    /// Zero-based character offset of the error in the query.
+    pub position: Option<usize>,
+}
+
+impl std::fmt::Display for QueryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if let Some(severity) = self.severity {
+            write!(f, "{}:  ", severity)?;
+        }
+        if let Some(code) = &self.code {
+            write!(f, "{}: ", code)?;
+        }
+        write!(f, "{}", self.message)?;
+
+        for (label, value) in [("DETAIL", &self.detail), ("HINT", &self.hint), ("CONTEXT", &self.context)] {
+            if let Some(value) = value {
+                write!(f, "\n{}:  {}", label, value)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Clone, Debug)]
 pub enum SQLQueryExecutionStatusType {
     Running,
     Success(SQLQueryExecutionSuccess),
-    Error(String),
+    Error(QueryError),
 }
 
 #[derive(Clone, Debug)]
@@ -231,9 +795,9 @@ pub enum ValueType {
     Unknown(String),
 }
 
-impl ValueType {
-    pub fn to_string(&self) -> String {
-        match self {
+impl std::fmt::Display for ValueType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let text = match self {
             ValueType::Null => "None".to_string(),
             ValueType::Text(text) => text.clone(),
             ValueType::Int(int) => int.to_string(),
@@ -269,24 +833,790 @@ impl ValueType {
                 .collect::<Vec<String>>()
                 .join(", "),
             ValueType::Unknown(unknown) => unknown.clone(),
-        }
+        };
+
+        write!(f, "{}", text)
     }
 }
 
 #[derive(Clone)]
 pub struct SQLQueryPage {
+    pub server_id: Uuid,
+    pub database_name: String,
     pub database: crate::database::Database,
     pub code: String,
     pub code_file_path: Option<String>,
     pub sql_query_execution_status: Option<Arc<Mutex<SQLQueryExecutionStatusType>>>,
     pub output_is_empty: bool,
     pub update_page_index: Option<u32>,
+    pub cursor: Option<usize>,
+    pub restore_cursor: bool,
+    pub saved_code: String,
+    pub file_modified: Option<SystemTime>,
+    pub file_changed: bool,
+    /// Notices sent by the server during the last run.
+    pub notices: Arc<Mutex<Vec<ServerNotice>>>,
+    pub show_messages: bool,
+    /// Runs go through a read-only transaction that is rolled back, as
+    /// EXPLAIN ANALYZE pages opened from query statistics do.
+    pub read_only: bool,
+}
+
+impl SQLQueryPage {
+    pub fn new(
+        server_id: Uuid,
+        database_name: impl ToString,
+        database: crate::database::Database,
+        code: impl ToString,
+    ) -> Self {
+        Self {
+            server_id,
+            database_name: database_name.to_string(),
+            database,
+            code: code.to_string(),
+            code_file_path: None,
+            sql_query_execution_status: None,
+            output_is_empty: true,
+            update_page_index: None,
+            cursor: None,
+            restore_cursor: false,
+            saved_code: String::new(),
+            file_modified: None,
+            file_changed: false,
+            notices: Arc::default(),
+            show_messages: false,
+            read_only: false,
+        }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.code_file_path.is_some() && self.code != self.saved_code
+    }
+}
+
+/// One row of `pg_stat_activity`, with the backends it waits for.
+#[derive(Clone, Debug)]
+pub struct ActivitySession {
+    pub pid: i32,
+    pub user: String,
+    pub database: String,
+    pub application: String,
+    pub client_address: String,
+    pub backend_type: String,
+    pub state: String,
+    pub wait_event: String,
+    pub query: String,
+    /// Seconds since the current query (or the last one, when idle) started.
+    pub duration: Option<f64>,
+    pub blocked_by: Vec<i32>,
+    /// Ungranted locks from `pg_locks`, e.g. "RowExclusiveLock on relation public.users".
+    pub waiting_for: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ActivityColumn {
+    #[default]
+    Pid,
+    User,
+    Database,
+    Application,
+    ClientAddress,
+    State,
+    WaitEvent,
+    Duration,
+    Query,
+}
+
+#[derive(Clone, Default)]
+pub struct ActivitySnapshot {
+    pub loading: bool,
+    pub sessions: Vec<ActivitySession>,
+    pub error: Option<String>,
+    pub refreshed_at: Option<Instant>,
+}
+
+#[derive(Clone)]
+pub struct ActivityPage {
+    pub server_id: Uuid,
+    pub database: crate::database::Database,
+    pub snapshot: Arc<Mutex<ActivitySnapshot>>,
+    pub auto_refresh: bool,
+    pub refresh_interval: u32,
+    pub filter: String,
+    pub hide_idle: bool,
+    pub show_background: bool,
+    pub sort_column: ActivityColumn,
+    pub sort_descending: bool,
+    pub selected: Vec<i32>,
+    pub show_lock_tree: bool,
+}
+
+impl ActivityPage {
+    pub fn new(server_id: Uuid, database: crate::database::Database) -> Self {
+        Self {
+            server_id,
+            database,
+            snapshot: Arc::new(Mutex::new(ActivitySnapshot::default())),
+            auto_refresh: true,
+            refresh_interval: 5,
+            filter: String::new(),
+            hide_idle: false,
+            show_background: false,
+            sort_column: ActivityColumn::default(),
+            sort_descending: false,
+            selected: Vec::new(),
+            show_lock_tree: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ReplicaStats {
+    pub name: String,
+    pub client_address: String,
+    pub state: String,
+    pub replay_lag: Option<f64>,
+    pub lag_bytes: Option<f64>,
+}
+
+/// Cumulative counters read at one moment; rates are derived from two
+/// consecutive samples.
+#[derive(Clone, Debug, Default)]
+pub struct ServerStats {
+    pub xact_commit: i64,
+    pub xact_rollback: i64,
+    pub blks_read: i64,
+    pub blks_hit: i64,
+    pub tuples_read: i64,
+    pub tuples_written: i64,
+    pub deadlocks: i64,
+    pub temp_files: i64,
+    pub temp_bytes: i64,
+    pub connections_active: i64,
+    pub connections_idle: i64,
+    pub connections_idle_in_transaction: i64,
+    pub connections_other: i64,
+    pub checkpoints: i64,
+    pub buffers_written: i64,
+    /// `None` before PostgreSQL 14, which has no `pg_stat_wal`.
+    pub wal_bytes: Option<f64>,
+    pub in_recovery: bool,
+    /// Replay delay on a standby, or the largest replica replay lag on a
+    /// primary, in seconds.
+    pub replication_lag: Option<f64>,
+    pub replicas: Vec<ReplicaStats>,
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DashboardPoint {
+    /// Seconds since the dashboard was opened.
+    pub time: f64,
+    pub transactions: f64,
+    pub cache_hit_ratio: f64,
+    pub tuples_read: f64,
+    pub tuples_written: f64,
+    pub deadlocks: f64,
+    pub temp_bytes: f64,
+    pub wal_bytes: Option<f64>,
+    pub buffers_written: f64,
+    pub connections_active: f64,
+    pub connections_idle: f64,
+    pub connections_idle_in_transaction: f64,
+    pub connections_other: f64,
+    pub replication_lag: Option<f64>,
+}
+
+/// Shared with the sampling task, which reads its settings before every
+/// sample and stops once the page is closed.
+pub struct DashboardState {
+    pub database: crate::database::Database,
+    pub database_name: Option<String>,
+    pub interval: u32,
+    pub paused: bool,
+    pub started_at: Instant,
+    pub last_sample: Option<(Instant, ServerStats)>,
+    pub points: VecDeque<DashboardPoint>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct DashboardPage {
+    pub server_id: Uuid,
+    pub state: Arc<Mutex<DashboardState>>,
+}
+
+/// One normalised statement from `pg_stat_statements`. Times are in
+/// milliseconds.
+#[derive(Clone, Debug)]
+pub struct StatementStats {
+    pub user: String,
+    pub database: String,
+    pub query: String,
+    pub calls: i64,
+    pub total_time: f64,
+    pub mean_time: f64,
+    pub stddev_time: f64,
+    pub rows: i64,
+    pub shared_blks_hit: i64,
+    pub shared_blks_read: i64,
+    pub temp_blks: i64,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum StatementsColumn {
+    Calls,
+    #[default]
+    TotalTime,
+    MeanTime,
+    StddevTime,
+    Rows,
+    SharedBlksHit,
+    SharedBlksRead,
+    TempBlks,
+    Query,
+}
+
+#[derive(Clone, Default)]
+pub struct StatementsSnapshot {
+    pub loading: bool,
+    pub loaded: bool,
+    pub extension_installed: bool,
+    pub statements: Vec<StatementStats>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct StatementsPage {
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: crate::database::Database,
+    pub snapshot: Arc<Mutex<StatementsSnapshot>>,
+    pub filter: String,
+    pub all_databases: bool,
+    pub sort_column: StatementsColumn,
+    pub sort_descending: bool,
+    pub confirm_reset: bool,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum PrivilegeObjectKind {
+    Database,
+    Schema,
+    #[default]
+    Table,
+}
+
+impl PrivilegeObjectKind {
+    pub const ALL: [PrivilegeObjectKind; 3] = [
+        PrivilegeObjectKind::Database,
+        PrivilegeObjectKind::Schema,
+        PrivilegeObjectKind::Table,
+    ];
+
+    pub fn privileges(&self) -> &'static [&'static str] {
+        match self {
+            PrivilegeObjectKind::Database => &["CONNECT", "CREATE", "TEMPORARY"],
+            PrivilegeObjectKind::Schema => &["USAGE", "CREATE"],
+            PrivilegeObjectKind::Table => &[
+                "SELECT",
+                "INSERT",
+                "UPDATE",
+                "DELETE",
+                "TRUNCATE",
+                "REFERENCES",
+                "TRIGGER",
+            ],
+        }
+    }
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            PrivilegeObjectKind::Database => "DATABASE",
+            PrivilegeObjectKind::Schema => "SCHEMA",
+            PrivilegeObjectKind::Table => "TABLE",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct PrivilegeObject {
+    /// Quoted, schema-qualified name ready for use in SQL.
+    pub name: String,
+    /// Privileges granted to the role directly.
+    pub granted: Vec<String>,
+    /// Privileges the role has, including through membership and ownership.
+    pub effective: Vec<String>,
+    /// Edited copy of `granted`.
+    pub selected: Vec<String>,
+}
+
+#[derive(Clone, Default)]
+pub struct PrivilegesSnapshot {
+    pub loading: bool,
+    pub loaded: bool,
+    pub roles: Vec<String>,
+    /// Taken by the page once loaded, so edits aren't overwritten.
+    pub objects: Option<Vec<PrivilegeObject>>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct PrivilegesPage {
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: crate::database::Database,
+    pub kind: PrivilegeObjectKind,
+    pub role: String,
+    pub snapshot: Arc<Mutex<PrivilegesSnapshot>>,
+    pub objects: Vec<PrivilegeObject>,
+    pub filter: String,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableColumn {
+    pub name: String,
+    pub data_type: String,
+    pub not_null: bool,
+    /// SQL expression, empty for none.
+    pub default: String,
+    /// Name in the database, `None` for a column that is not created yet.
+    pub original_name: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum TableConstraintKind {
+    #[default]
+    PrimaryKey,
+    Unique,
+    Check,
+    ForeignKey,
+}
+
+impl TableConstraintKind {
+    pub const ALL: [TableConstraintKind; 4] = [
+        TableConstraintKind::PrimaryKey,
+        TableConstraintKind::Unique,
+        TableConstraintKind::Check,
+        TableConstraintKind::ForeignKey,
+    ];
+
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            TableConstraintKind::PrimaryKey => "PRIMARY KEY",
+            TableConstraintKind::Unique => "UNIQUE",
+            TableConstraintKind::Check => "CHECK",
+            TableConstraintKind::ForeignKey => "FOREIGN KEY",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableConstraint {
+    pub name: String,
+    pub kind: TableConstraintKind,
+    /// Comma-separated column names.
+    pub columns: String,
+    /// Expression of a check constraint.
+    pub expression: String,
+    /// Referenced table of a foreign key, as SQL.
+    pub ref_table: String,
+    pub ref_columns: String,
+    pub on_delete: String,
+    pub on_update: String,
+    pub original_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TableIndex {
+    pub name: String,
+    /// Comma-separated key columns or expressions, with any collation,
+    /// operator class and ordering, as SQL.
+    pub columns: String,
+    /// Comma-separated `INCLUDE` columns, as SQL, empty for none.
+    pub include: String,
+    pub unique: bool,
+    pub method: String,
+    /// `WHERE` clause of a partial index, empty for none.
+    pub predicate: String,
+    pub original_name: Option<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct TableDefinition {
+    pub schema: String,
+    pub name: String,
+    pub columns: Vec<TableColumn>,
+    pub constraints: Vec<TableConstraint>,
+    pub indexes: Vec<TableIndex>,
+}
+
+#[derive(Clone, Default)]
+pub struct TableDesignerSnapshot {
+    pub loading: bool,
+    /// Taken by the page once loaded, so edits aren't overwritten.
+    pub definition: Option<TableDefinition>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct TableDesignerPage {
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: crate::database::Database,
+    /// The table as it is in the database, `None` for a new table.
+    pub original: Option<TableDefinition>,
+    pub table: TableDefinition,
+    pub snapshot: Arc<Mutex<TableDesignerSnapshot>>,
+}
+
+/// An index of a table with its usage statistics and advisor hints.
+#[derive(Clone, Debug)]
+pub struct IndexStats {
+    pub name: String,
+    pub definition: String,
+    pub size: i64,
+    pub scans: i64,
+    pub tuples_read: i64,
+    pub tuples_fetched: i64,
+    /// Estimated wasted bytes, only known for btree indexes on analyzed columns.
+    pub bloat: Option<i64>,
+    pub unique: bool,
+    pub primary: bool,
+    pub valid: bool,
+    /// Constraint the index enforces.
+    pub constraint: Option<String>,
+    /// An identical index that should be kept instead of this one.
+    pub duplicate_of: Option<String>,
+    /// A btree index whose leading columns are the columns of this one.
+    pub covered_by: Option<String>,
+}
+
+impl IndexStats {
+    pub fn is_unused(&self) -> bool {
+        self.scans == 0 && !self.unique
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct IndexesSnapshot {
+    pub loading: bool,
+    pub loaded: bool,
+    pub schema: String,
+    pub columns: Vec<String>,
+    pub indexes: Vec<IndexStats>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct IndexesPage {
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: crate::database::Database,
+    pub table: String,
+    pub snapshot: Arc<Mutex<IndexesSnapshot>>,
+    /// Drop and reindex without blocking writes.
+    pub concurrently: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ErColumn {
+    pub name: String,
+    pub data_type: String,
+    pub primary_key: bool,
+    pub foreign_key: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ErTable {
+    pub name: String,
+    pub columns: Vec<ErColumn>,
+}
+
+/// A foreign key from `table` to `referenced_table`, both in the same schema.
+#[derive(Clone, Debug, Default)]
+pub struct ErRelation {
+    pub name: String,
+    pub table: String,
+    pub columns: Vec<String>,
+    pub referenced_table: String,
+    pub referenced_columns: Vec<String>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct ErModel {
+    pub tables: Vec<ErTable>,
+    pub relations: Vec<ErRelation>,
+}
+
+#[derive(Clone, Default)]
+pub struct ErDiagramSnapshot {
+    pub loading: bool,
+    pub loaded: bool,
+    pub schemas: Vec<String>,
+    /// Taken by the page once loaded, so moved tables keep their place.
+    pub model: Option<ErModel>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiagramFormat {
+    Svg,
+    Png,
+    Dot,
+}
+
+impl DiagramFormat {
+    pub const ALL: [DiagramFormat; 3] = [DiagramFormat::Svg, DiagramFormat::Png, DiagramFormat::Dot];
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DiagramFormat::Svg => "svg",
+            DiagramFormat::Png => "png",
+            DiagramFormat::Dot => "dot",
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ErDiagramPage {
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub database: crate::database::Database,
+    pub schema: String,
+    pub snapshot: Arc<Mutex<ErDiagramSnapshot>>,
+    pub model: ErModel,
+    /// Top-left corners of the table boxes, in diagram coordinates.
+    pub positions: HashMap<String, egui::Pos2>,
+    pub pan: egui::Vec2,
+    pub zoom: f32,
+    /// Table whose neighbourhood is shown, `None` for the whole schema.
+    pub focus: Option<String>,
+    /// Number of foreign key hops around `focus` that are shown.
+    pub depth: usize,
+    /// Table being dragged, `None` while panning.
+    pub dragging: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum SchemaObjectKind {
+    #[default]
+    Schema,
+    Sequence,
+    Table,
+    Column,
+    Constraint,
+    Index,
+    Function,
+    View,
+}
+
+impl SchemaObjectKind {
+    pub fn keyword(&self) -> &'static str {
+        match self {
+            SchemaObjectKind::Schema => "SCHEMA",
+            SchemaObjectKind::Sequence => "SEQUENCE",
+            SchemaObjectKind::Table => "TABLE",
+            SchemaObjectKind::Column => "COLUMN",
+            SchemaObjectKind::Constraint => "CONSTRAINT",
+            SchemaObjectKind::Index => "INDEX",
+            SchemaObjectKind::Function => "FUNCTION",
+            SchemaObjectKind::View => "VIEW",
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaColumn {
+    pub data_type: String,
+    pub not_null: bool,
+    pub default: Option<String>,
+    /// Expression of a stored generated column.
+    pub generated: Option<String>,
+    /// `ALWAYS` or `BY DEFAULT` for identity columns.
+    pub identity: Option<String>,
+}
+
+impl SchemaColumn {
+    /// The column as written after its name in `CREATE TABLE`.
+    pub fn definition(&self) -> String {
+        let mut definition = self.data_type.clone();
+        if let Some(generated) = &self.generated {
+            definition.push_str(&format!(" GENERATED ALWAYS AS ({}) STORED", generated));
+        }
+        if let Some(identity) = &self.identity {
+            definition.push_str(&format!(" GENERATED {} AS IDENTITY", identity));
+        }
+        if let Some(default) = &self.default {
+            definition.push_str(&format!(" DEFAULT {}", default));
+        }
+        if self.not_null {
+            definition.push_str(" NOT NULL");
+        }
+        definition
+    }
+}
+
+/// A catalog object compared by the schema diff. Names are quoted, and
+/// schema-qualified except for columns and constraints, which belong to `table`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaObject {
+    pub kind: SchemaObjectKind,
+    pub table: Option<String>,
+    /// Functions include their argument types, to tell overloads apart.
+    pub name: String,
+    pub definition: String,
+    pub column: Option<SchemaColumn>,
+    /// Table a foreign key references, named like `table`.
+    pub references: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum DiffStatus {
+    /// Only in the source, created on the target.
+    Added,
+    /// Only in the target, dropped from it.
+    Removed,
+    Changed,
+}
+
+#[derive(Clone, Debug)]
+pub struct SchemaDifference {
+    pub kind: SchemaObjectKind,
+    pub table: Option<String>,
+    pub name: String,
+    pub status: DiffStatus,
+    pub source: Option<SchemaObject>,
+    pub target: Option<SchemaObject>,
+    /// Included in the migration script.
+    pub selected: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct SchemaDiffSnapshot {
+    pub loading: bool,
+    /// Taken by the page once compared, so selections aren't overwritten.
+    pub differences: Option<Vec<SchemaDifference>>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct SchemaDiffPage {
+    /// Server and name of the database the target is brought in line with.
+    pub source: Option<(Uuid, String)>,
+    pub target: Option<(Uuid, String)>,
+    pub snapshot: Arc<Mutex<SchemaDiffSnapshot>>,
+    pub differences: Vec<SchemaDifference>,
+    /// Whether `differences` holds the result of a comparison.
+    pub compared: bool,
+    /// Difference shown side by side.
+    pub selected: Option<usize>,
+    pub filter: String,
+}
+
+/// A row that differs between the tables, with its values as text in the
+/// order of `DataComparison::columns`.
+#[derive(Clone, Debug)]
+pub struct DataDifference {
+    pub status: DiffStatus,
+    pub source: Option<Vec<Option<String>>>,
+    pub target: Option<Vec<Option<String>>>,
+    /// Included in the sync script.
+    pub selected: bool,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct DataComparison {
+    /// Quoted qualified name of the table in the target.
+    pub table: String,
+    /// Columns of both tables, primary key columns first.
+    pub columns: Vec<String>,
+    pub key_length: usize,
+    /// Inserts into the target need `OVERRIDING SYSTEM VALUE`.
+    pub identity_always: bool,
+    /// Columns found in only one of the tables, left out of the comparison.
+    pub skipped_columns: Vec<String>,
+    pub differences: Vec<DataDifference>,
+    /// More differences were found than are kept in `differences`.
+    pub truncated: bool,
+}
+
+#[derive(Clone, Default)]
+pub struct DataCompareSnapshot {
+    pub loading: bool,
+    /// Asks the running comparison to stop.
+    pub cancel: bool,
+    pub rows: u64,
+    /// Added, removed and changed rows found so far.
+    pub counts: [u64; 3],
+    /// Taken by the page once compared, so selections aren't overwritten.
+    pub comparison: Option<DataComparison>,
+    pub error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct DataComparePage {
+    /// Server and name of the database the target is synced with.
+    pub source: Option<(Uuid, String)>,
+    pub target: Option<(Uuid, String)>,
+    /// Table name, optionally qualified with its schema.
+    pub table: String,
+    pub snapshot: Arc<Mutex<DataCompareSnapshot>>,
+    pub comparison: Option<DataComparison>,
+    /// Difference whose values are shown column by column.
+    pub selected: Option<usize>,
+}
+
+impl Page {
+    pub fn is_dirty(&self) -> bool {
+        match &self.page_type {
+            PageType::SQLQuery(sqlquery_page) => sqlquery_page.is_dirty(),
+            _ => false,
+        }
+    }
 }
 
 #[derive(Clone)]
 pub enum PageType {
     Welcome,
     SQLQuery(SQLQueryPage),
+    Activity(ActivityPage),
+    Dashboard(DashboardPage),
+    Statements(StatementsPage),
+    Privileges(PrivilegesPage),
+    TableDesigner(TableDesignerPage),
+    Indexes(IndexesPage),
+    ErDiagram(ErDiagramPage),
+    SchemaDiff(SchemaDiffPage),
+    DataCompare(DataComparePage),
+}
+
+impl PageType {
+    /// Server, database name and pool of pages bound to one database.
+    pub fn binding_mut(&mut self) -> Option<(Uuid, &mut String, &mut crate::database::Database)> {
+        match self {
+            PageType::SQLQuery(page) => Some((page.server_id, &mut page.database_name, &mut page.database)),
+            PageType::Statements(page) => Some((page.server_id, &mut page.database_name, &mut page.database)),
+            PageType::Privileges(page) => Some((page.server_id, &mut page.database_name, &mut page.database)),
+            PageType::TableDesigner(page) => Some((page.server_id, &mut page.database_name, &mut page.database)),
+            PageType::Indexes(page) => Some((page.server_id, &mut page.database_name, &mut page.database)),
+            PageType::ErDiagram(page) => Some((page.server_id, &mut page.database_name, &mut page.database)),
+            PageType::Welcome
+            | PageType::Activity(_)
+            | PageType::Dashboard(_)
+            | PageType::SchemaDiff(_)
+            | PageType::DataCompare(_) => None,
+        }
+    }
+
+    /// Databases picked on the sides of compare pages, which connect when
+    /// the comparison runs.
+    pub fn compared_databases_mut(&mut self) -> Vec<&mut (Uuid, String)> {
+        match self {
+            PageType::SchemaDiff(page) => page.source.iter_mut().chain(page.target.iter_mut()).collect(),
+            PageType::DataCompare(page) => page.source.iter_mut().chain(page.target.iter_mut()).collect(),
+            _ => Vec::new(),
+        }
+    }
 }
 
 #[derive(Clone)]
@@ -321,9 +1651,108 @@ impl Default for Pages {
     }
 }
 
+#[derive(Serialize, Deserialize, Clone, Debug, Default, PartialEq)]
+pub struct Session {
+    pub current_page_index: u16,
+    pub pages: Vec<SessionPage>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct SessionPage {
+    pub server_id: Uuid,
+    pub database_name: String,
+    pub code: String,
+    pub code_file_path: Option<String>,
+    pub cursor: Option<usize>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
 #[derive(Clone)]
 pub enum Action {
     ClosePage(usize),
+    MoveServer(Uuid, Uuid),
+    MoveServerToFolder(Uuid, Option<String>),
+    AddRecentFile(String),
+    OpenFile(String),
+    OpenStatementsPage(Uuid, String),
+    OpenPrivilegesPage(Uuid, String),
+    /// Server, database and table, `None` for a new table.
+    OpenTableDesigner(Uuid, String, Option<String>),
+    OpenIndexesPage(Uuid, String, String),
+    OpenErDiagram(Uuid, String),
+    OpenSchemaDiff(Uuid, String),
+    /// Server, database and table.
+    OpenDataCompare(Uuid, String, String),
+    /// Server, database and table, `None` for the whole database.
+    OpenMaintenance(Uuid, String, Option<String>),
+}
+
+/// Server list written by "Export servers". A plain export keeps servers
+/// without passwords in `servers`; an encrypted one holds another
+/// `ServersExport` in `data`, sealed with a key derived from the passphrase.
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct ServersExport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault: Option<Vault>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<String>,
+    #[serde(default)]
+    pub servers: Vec<Server>,
+    #[serde(default)]
+    pub folders: Vec<String>,
+}
+
+#[derive(Clone)]
+pub enum ServersFileDialogAction {
+    ExportWithoutPasswords,
+    Export,
+    Import,
+}
+
+#[derive(Default)]
+pub struct ExportServersWindow {
+    pub show: bool,
+    pub selected: Vec<Uuid>,
+    pub encrypt: bool,
+    pub include_passwords: bool,
+    pub passphrase: String,
+    pub confirm_passphrase: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ImportResolution {
+    #[default]
+    Skip,
+    Overwrite,
+    Rename,
+}
+
+impl ImportResolution {
+    pub const ALL: [ImportResolution; 3] = [
+        ImportResolution::Skip,
+        ImportResolution::Overwrite,
+        ImportResolution::Rename,
+    ];
+}
+
+pub struct ImportServer {
+    pub server: Server,
+    pub selected: bool,
+    /// Existing server with the same ID or alias.
+    pub conflict: Option<Uuid>,
+    pub resolution: ImportResolution,
+}
+
+#[derive(Default)]
+pub struct ImportServersWindow {
+    pub show: bool,
+    /// Encrypted file waiting for its passphrase.
+    pub encrypted: Option<ServersExport>,
+    pub passphrase: String,
+    pub servers: Vec<ImportServer>,
+    pub folders: Vec<String>,
+    pub error: Option<String>,
 }
 
 #[derive(Clone)]
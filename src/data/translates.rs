@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::data::structs::ImportResolution;
+use crate::secrets::SecretsBackend;
+
 #[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(rename_all = "snake_case")]
 pub enum Language {
@@ -556,4 +559,1678 @@ impl Translator {
         }
     }
 
+    pub fn pool_size(&self) -> String {
+        match self.language {
+            Language::English => "Pool size".to_string(),
+            Language::Russian => "Размер пула".to_string(),
+        }
+    }
+
+    pub fn pool_idle_timeout(&self) -> String {
+        match self.language {
+            Language::English => "Idle timeout (s)".to_string(),
+            Language::Russian => "Таймаут простоя (с)".to_string(),
+        }
+    }
+
+    pub fn incorrect_pool_size_value(&self) -> String {
+        format!("- {}", match self.language {
+            Language::English => "Incorrect pool size value".to_string(),
+            Language::Russian => "Некорректный размер пула".to_string(),
+        })
+    }
+
+    pub fn incorrect_pool_idle_timeout_value(&self) -> String {
+        format!("- {}", match self.language {
+            Language::English => "Incorrect idle timeout value".to_string(),
+            Language::Russian => "Некорректный таймаут простоя".to_string(),
+        })
+    }
+
+    pub fn search(&self) -> String {
+        match self.language {
+            Language::English => "Search...".to_string(),
+            Language::Russian => "Поиск...".to_string(),
+        }
+    }
+
+    pub fn add_folder(&self) -> String {
+        match self.language {
+            Language::English => "Add folder".to_string(),
+            Language::Russian => "Добавить папку".to_string(),
+        }
+    }
+
+    pub fn rename(&self) -> String {
+        match self.language {
+            Language::English => "Rename".to_string(),
+            Language::Russian => "Переименовать".to_string(),
+        }
+    }
+
+    pub fn add_to_favorites(&self) -> String {
+        match self.language {
+            Language::English => "Add to favorites".to_string(),
+            Language::Russian => "Добавить в избранное".to_string(),
+        }
+    }
+
+    pub fn remove_from_favorites(&self) -> String {
+        match self.language {
+            Language::English => "Remove from favorites".to_string(),
+            Language::Russian => "Убрать из избранного".to_string(),
+        }
+    }
+
+    pub fn move_to_folder(&self) -> String {
+        match self.language {
+            Language::English => "Move to folder".to_string(),
+            Language::Russian => "Переместить в папку".to_string(),
+        }
+    }
+
+    pub fn root_folder(&self) -> String {
+        match self.language {
+            Language::English => "(Root)".to_string(),
+            Language::Russian => "(Корень)".to_string(),
+        }
+    }
+
+    pub fn color(&self) -> String {
+        match self.language {
+            Language::English => "Color".to_string(),
+            Language::Russian => "Цвет".to_string(),
+        }
+    }
+
+    pub fn no_color(&self) -> String {
+        match self.language {
+            Language::English => "No color".to_string(),
+            Language::Russian => "Без цвета".to_string(),
+        }
+    }
+
+    pub fn name_must_not_contain_slash(&self) -> String {
+        format!("- {}", match self.language {
+            Language::English => "Name must not contain \"/\"".to_string(),
+            Language::Russian => "Имя не должно содержать \"/\"".to_string(),
+        })
+    }
+
+    pub fn restore_session(&self) -> String {
+        match self.language {
+            Language::English => "Restore session on startup".to_string(),
+            Language::Russian => "Восстанавливать сессию при запуске".to_string(),
+        }
+    }
+
+    pub fn save_as(&self) -> String {
+        match self.language {
+            Language::English => "Save as".to_string(),
+            Language::Russian => "Сохранить как".to_string(),
+        }
+    }
+
+    pub fn open_recent(&self) -> String {
+        match self.language {
+            Language::English => "Open recent".to_string(),
+            Language::Russian => "Недавние файлы".to_string(),
+        }
+    }
+
+    pub fn unsaved_changes(&self) -> String {
+        match self.language {
+            Language::English => "Unsaved changes".to_string(),
+            Language::Russian => "Несохранённые изменения".to_string(),
+        }
+    }
+
+    pub fn unsaved_changes_confirmation(&self) -> String {
+        match self.language {
+            Language::English => "These files have unsaved changes. Save them before closing?".to_string(),
+            Language::Russian => "В этих файлах есть несохранённые изменения. Сохранить их перед закрытием?".to_string(),
+        }
+    }
+
+    pub fn dont_save(&self) -> String {
+        match self.language {
+            Language::English => "Don't save".to_string(),
+            Language::Russian => "Не сохранять".to_string(),
+        }
+    }
+
+    pub fn cancel(&self) -> String {
+        match self.language {
+            Language::English => "Cancel".to_string(),
+            Language::Russian => "Отмена".to_string(),
+        }
+    }
+
+    pub fn file_changed_on_disk(&self) -> String {
+        match self.language {
+            Language::English => "The file has been changed on disk.".to_string(),
+            Language::Russian => "Файл был изменён на диске.".to_string(),
+        }
+    }
+
+    pub fn keep_mine(&self) -> String {
+        match self.language {
+            Language::English => "Keep my version".to_string(),
+            Language::Russian => "Оставить мою версию".to_string(),
+        }
+    }
+
+    pub fn passwords_storage(&self) -> String {
+        match self.language {
+            Language::English => "Passwords storage".to_string(),
+            Language::Russian => "Хранилище паролей".to_string(),
+        }
+    }
+
+    pub fn encrypted_config(&self) -> String {
+        match self.language {
+            Language::English => "Encrypted config".to_string(),
+            Language::Russian => "Зашифрованный конфиг".to_string(),
+        }
+    }
+
+    pub fn system_keyring(&self) -> String {
+        match self.language {
+            Language::English => "System keyring".to_string(),
+            Language::Russian => "Системная связка ключей".to_string(),
+        }
+    }
+
+    pub fn secrets_backend_name(&self, secrets_backend: SecretsBackend) -> String {
+        match secrets_backend {
+            SecretsBackend::Vault => self.encrypted_config(),
+            SecretsBackend::Keyring => self.system_keyring(),
+        }
+    }
+
+    pub fn export_servers_without_passwords(&self) -> String {
+        match self.language {
+            Language::English => "Export servers without passwords".to_string(),
+            Language::Russian => "Экспорт серверов без паролей".to_string(),
+        }
+    }
+
+    pub fn recover_access(&self) -> String {
+        match self.language {
+            Language::English => "Recover access".to_string(),
+            Language::Russian => "Восстановление доступа".to_string(),
+        }
+    }
+
+    pub fn recovery_key(&self) -> String {
+        match self.language {
+            Language::English => "Recovery key".to_string(),
+            Language::Russian => "Ключ восстановления".to_string(),
+        }
+    }
+
+    pub fn reset_password(&self) -> String {
+        match self.language {
+            Language::English => "Reset password".to_string(),
+            Language::Russian => "Сбросить пароль".to_string(),
+        }
+    }
+
+    pub fn generate_recovery_key(&self) -> String {
+        match self.language {
+            Language::English => "Generate a recovery key".to_string(),
+            Language::Russian => "Создать ключ восстановления".to_string(),
+        }
+    }
+
+    pub fn generate_new_recovery_key(&self) -> String {
+        match self.language {
+            Language::English => "Generate new key".to_string(),
+            Language::Russian => "Создать новый ключ".to_string(),
+        }
+    }
+
+    pub fn forgot_password(&self) -> String {
+        match self.language {
+            Language::English => "Forgot password?".to_string(),
+            Language::Russian => "Забыли пароль?".to_string(),
+        }
+    }
+
+    pub fn incorrect_recovery_key(&self) -> String {
+        match self.language {
+            Language::English => "Incorrect recovery key".to_string(),
+            Language::Russian => "Неверный ключ восстановления".to_string(),
+        }
+    }
+
+    pub fn recovery_key_description(&self) -> String {
+        match self.language {
+            Language::English => "Store this key somewhere safe. It restores access if the master password is lost and is shown only once.".to_string(),
+            Language::Russian => "Сохраните этот ключ в надёжном месте. Он восстановит доступ при утере мастер-пароля и показывается только один раз.".to_string(),
+        }
+    }
+
+    pub fn auto_lock(&self) -> String {
+        match self.language {
+            Language::English => "Auto-lock after, minutes (0 = never)".to_string(),
+            Language::Russian => "Автоблокировка через, минут (0 = никогда)".to_string(),
+        }
+    }
+
+    pub fn lock(&self) -> String {
+        match self.language {
+            Language::English => "Lock".to_string(),
+            Language::Russian => "Заблокировать".to_string(),
+        }
+    }
+
+    pub fn export(&self) -> String {
+        match self.language {
+            Language::English => "Export".to_string(),
+            Language::Russian => "Экспорт".to_string(),
+        }
+    }
+
+    pub fn import(&self) -> String {
+        match self.language {
+            Language::English => "Import".to_string(),
+            Language::Russian => "Импорт".to_string(),
+        }
+    }
+
+    pub fn export_servers(&self) -> String {
+        match self.language {
+            Language::English => "Export servers".to_string(),
+            Language::Russian => "Экспорт серверов".to_string(),
+        }
+    }
+
+    pub fn import_servers(&self) -> String {
+        match self.language {
+            Language::English => "Import servers".to_string(),
+            Language::Russian => "Импорт серверов".to_string(),
+        }
+    }
+
+    pub fn encrypt_with_passphrase(&self) -> String {
+        match self.language {
+            Language::English => "Encrypt with a passphrase".to_string(),
+            Language::Russian => "Зашифровать паролем".to_string(),
+        }
+    }
+
+    pub fn include_passwords(&self) -> String {
+        match self.language {
+            Language::English => "Include passwords".to_string(),
+            Language::Russian => "Включить пароли".to_string(),
+        }
+    }
+
+    pub fn passphrase(&self) -> String {
+        match self.language {
+            Language::English => "Passphrase".to_string(),
+            Language::Russian => "Пароль файла".to_string(),
+        }
+    }
+
+    pub fn plain_export_description(&self) -> String {
+        match self.language {
+            Language::English => "Passwords are not exported. The file can be shared or committed to a repository.".to_string(),
+            Language::Russian => "Пароли не экспортируются. Файл можно передавать или хранить в репозитории.".to_string(),
+        }
+    }
+
+    pub fn decrypt(&self) -> String {
+        match self.language {
+            Language::English => "Decrypt".to_string(),
+            Language::Russian => "Расшифровать".to_string(),
+        }
+    }
+
+    pub fn incorrect_passphrase(&self) -> String {
+        match self.language {
+            Language::English => "Incorrect passphrase".to_string(),
+            Language::Russian => "Неверный пароль файла".to_string(),
+        }
+    }
+
+    pub fn new_server(&self) -> String {
+        match self.language {
+            Language::English => "New server".to_string(),
+            Language::Russian => "Новый сервер".to_string(),
+        }
+    }
+
+    pub fn skip(&self) -> String {
+        match self.language {
+            Language::English => "Skip".to_string(),
+            Language::Russian => "Пропустить".to_string(),
+        }
+    }
+
+    pub fn overwrite(&self) -> String {
+        match self.language {
+            Language::English => "Overwrite".to_string(),
+            Language::Russian => "Перезаписать".to_string(),
+        }
+    }
+
+    pub fn import_resolution(&self, resolution: ImportResolution) -> String {
+        match resolution {
+            ImportResolution::Skip => self.skip(),
+            ImportResolution::Overwrite => self.overwrite(),
+            ImportResolution::Rename => self.rename(),
+        }
+    }
+
+    pub fn config_error(&self) -> String {
+        match self.language {
+            Language::English => "The config file could not be loaded".to_string(),
+            Language::Russian => "Не удалось загрузить файл конфигурации".to_string(),
+        }
+    }
+
+    pub fn retry(&self) -> String {
+        match self.language {
+            Language::English => "Retry".to_string(),
+            Language::Russian => "Повторить".to_string(),
+        }
+    }
+
+    pub fn restore_backup(&self) -> String {
+        match self.language {
+            Language::English => "Restore latest backup".to_string(),
+            Language::Russian => "Восстановить последнюю копию".to_string(),
+        }
+    }
+
+    pub fn reset_config(&self) -> String {
+        match self.language {
+            Language::English => "Start with a new config (the old file is kept as .broken)".to_string(),
+            Language::Russian => "Начать с новой конфигурацией (старый файл сохранится как .broken)".to_string(),
+        }
+    }
+
+    pub fn quit(&self) -> String {
+        match self.language {
+            Language::English => "Quit".to_string(),
+            Language::Russian => "Выйти".to_string(),
+        }
+    }
+
+    pub fn activity(&self) -> String {
+        match self.language {
+            Language::English => "Activity".to_string(),
+            Language::Russian => "Активность".to_string(),
+        }
+    }
+
+    pub fn refresh(&self) -> String {
+        match self.language {
+            Language::English => "Refresh".to_string(),
+            Language::Russian => "Обновить".to_string(),
+        }
+    }
+
+    pub fn auto_refresh(&self) -> String {
+        match self.language {
+            Language::English => "Auto-refresh, s".to_string(),
+            Language::Russian => "Автообновление, с".to_string(),
+        }
+    }
+
+    pub fn hide_idle(&self) -> String {
+        match self.language {
+            Language::English => "Hide idle".to_string(),
+            Language::Russian => "Скрыть бездействующие".to_string(),
+        }
+    }
+
+    pub fn background_processes(&self) -> String {
+        match self.language {
+            Language::English => "Background processes".to_string(),
+            Language::Russian => "Фоновые процессы".to_string(),
+        }
+    }
+
+    pub fn sessions(&self) -> String {
+        match self.language {
+            Language::English => "Sessions".to_string(),
+            Language::Russian => "Сеансы".to_string(),
+        }
+    }
+
+    pub fn lock_waits(&self) -> String {
+        match self.language {
+            Language::English => "Lock waits".to_string(),
+            Language::Russian => "Ожидания блокировок".to_string(),
+        }
+    }
+
+    pub fn no_lock_waits(&self) -> String {
+        match self.language {
+            Language::English => "No sessions are waiting for locks".to_string(),
+            Language::Russian => "Нет сеансов, ожидающих блокировки".to_string(),
+        }
+    }
+
+    pub fn cancel_query(&self) -> String {
+        match self.language {
+            Language::English => "Cancel query".to_string(),
+            Language::Russian => "Отменить запрос".to_string(),
+        }
+    }
+
+    pub fn terminate(&self) -> String {
+        match self.language {
+            Language::English => "Terminate".to_string(),
+            Language::Russian => "Завершить".to_string(),
+        }
+    }
+
+    pub fn cancel_backends_confirmation(&self, pids: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Cancel the running queries of backends {}?", pids.to_string()),
+            Language::Russian => format!("Отменить выполняющиеся запросы процессов {}?", pids.to_string()),
+        }
+    }
+
+    pub fn terminate_backends_confirmation(&self, pids: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Terminate backends {}? Their open transactions will be rolled back and the clients disconnected.", pids.to_string()),
+            Language::Russian => format!("Завершить процессы {}? Их открытые транзакции будут отменены, а клиенты отключены.", pids.to_string()),
+        }
+    }
+
+    pub fn backends_not_signalled(&self, pids: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("These backends could not be signalled: {}", pids.to_string()),
+            Language::Russian => format!("Не удалось отправить сигнал процессам: {}", pids.to_string()),
+        }
+    }
+
+    pub fn database(&self) -> String {
+        match self.language {
+            Language::English => "Database".to_string(),
+            Language::Russian => "База данных".to_string(),
+        }
+    }
+
+    pub fn application(&self) -> String {
+        match self.language {
+            Language::English => "Application".to_string(),
+            Language::Russian => "Приложение".to_string(),
+        }
+    }
+
+    pub fn client(&self) -> String {
+        match self.language {
+            Language::English => "Client".to_string(),
+            Language::Russian => "Клиент".to_string(),
+        }
+    }
+
+    pub fn state(&self) -> String {
+        match self.language {
+            Language::English => "State".to_string(),
+            Language::Russian => "Состояние".to_string(),
+        }
+    }
+
+    pub fn wait_event(&self) -> String {
+        match self.language {
+            Language::English => "Wait event".to_string(),
+            Language::Russian => "Ожидание".to_string(),
+        }
+    }
+
+    pub fn duration(&self) -> String {
+        match self.language {
+            Language::English => "Duration".to_string(),
+            Language::Russian => "Длительность".to_string(),
+        }
+    }
+
+    pub fn query(&self) -> String {
+        match self.language {
+            Language::English => "Query".to_string(),
+            Language::Russian => "Запрос".to_string(),
+        }
+    }
+
+    pub fn blocked_by(&self) -> String {
+        match self.language {
+            Language::English => "Blocked by".to_string(),
+            Language::Russian => "Заблокирован".to_string(),
+        }
+    }
+
+    pub fn waiting_for(&self) -> String {
+        match self.language {
+            Language::English => "Waiting for".to_string(),
+            Language::Russian => "Ожидает".to_string(),
+        }
+    }
+
+    pub fn dashboard(&self) -> String {
+        match self.language {
+            Language::English => "Dashboard".to_string(),
+            Language::Russian => "Панель мониторинга".to_string(),
+        }
+    }
+
+    pub fn all_databases(&self) -> String {
+        match self.language {
+            Language::English => "All databases".to_string(),
+            Language::Russian => "Все базы данных".to_string(),
+        }
+    }
+
+    pub fn interval(&self) -> String {
+        match self.language {
+            Language::English => "Interval, s".to_string(),
+            Language::Russian => "Интервал, с".to_string(),
+        }
+    }
+
+    pub fn pause(&self) -> String {
+        match self.language {
+            Language::English => "Pause".to_string(),
+            Language::Russian => "Пауза".to_string(),
+        }
+    }
+
+    pub fn collecting_samples(&self) -> String {
+        match self.language {
+            Language::English => "Collecting samples...".to_string(),
+            Language::Russian => "Сбор данных...".to_string(),
+        }
+    }
+
+    pub fn role(&self) -> String {
+        match self.language {
+            Language::English => "Role".to_string(),
+            Language::Russian => "Роль".to_string(),
+        }
+    }
+
+    pub fn primary(&self) -> String {
+        match self.language {
+            Language::English => "Primary".to_string(),
+            Language::Russian => "Ведущий".to_string(),
+        }
+    }
+
+    pub fn standby(&self) -> String {
+        match self.language {
+            Language::English => "Standby".to_string(),
+            Language::Russian => "Резервный".to_string(),
+        }
+    }
+
+    pub fn connections(&self) -> String {
+        match self.language {
+            Language::English => "Connections".to_string(),
+            Language::Russian => "Подключения".to_string(),
+        }
+    }
+
+    pub fn deadlocks(&self) -> String {
+        match self.language {
+            Language::English => "Deadlocks".to_string(),
+            Language::Russian => "Взаимоблокировки".to_string(),
+        }
+    }
+
+    pub fn temp_files(&self) -> String {
+        match self.language {
+            Language::English => "Temporary files".to_string(),
+            Language::Russian => "Временные файлы".to_string(),
+        }
+    }
+
+    pub fn checkpoints(&self) -> String {
+        match self.language {
+            Language::English => "Checkpoints".to_string(),
+            Language::Russian => "Контрольные точки".to_string(),
+        }
+    }
+
+    pub fn replicas(&self) -> String {
+        match self.language {
+            Language::English => "Replicas".to_string(),
+            Language::Russian => "Реплики".to_string(),
+        }
+    }
+
+    pub fn replication_lag(&self) -> String {
+        match self.language {
+            Language::English => "Replication lag".to_string(),
+            Language::Russian => "Отставание репликации".to_string(),
+        }
+    }
+
+    pub fn transactions_per_second(&self) -> String {
+        match self.language {
+            Language::English => "Transactions/s".to_string(),
+            Language::Russian => "Транзакций/с".to_string(),
+        }
+    }
+
+    pub fn cache_hit_ratio(&self) -> String {
+        match self.language {
+            Language::English => "Cache hit ratio".to_string(),
+            Language::Russian => "Попадания в кэш".to_string(),
+        }
+    }
+
+    pub fn tuples_per_second(&self) -> String {
+        match self.language {
+            Language::English => "Tuples/s".to_string(),
+            Language::Russian => "Строк/с".to_string(),
+        }
+    }
+
+    pub fn read(&self) -> String {
+        match self.language {
+            Language::English => "Read".to_string(),
+            Language::Russian => "Чтение".to_string(),
+        }
+    }
+
+    pub fn written(&self) -> String {
+        match self.language {
+            Language::English => "Written".to_string(),
+            Language::Russian => "Запись".to_string(),
+        }
+    }
+
+    pub fn other(&self) -> String {
+        match self.language {
+            Language::English => "Other".to_string(),
+            Language::Russian => "Прочие".to_string(),
+        }
+    }
+
+    pub fn buffers_written(&self) -> String {
+        match self.language {
+            Language::English => "Buffers written".to_string(),
+            Language::Russian => "Записано буферов".to_string(),
+        }
+    }
+
+    pub fn query_statistics(&self) -> String {
+        match self.language {
+            Language::English => "Query statistics".to_string(),
+            Language::Russian => "Статистика запросов".to_string(),
+        }
+    }
+
+    pub fn reset_statistics(&self) -> String {
+        match self.language {
+            Language::English => "Reset statistics".to_string(),
+            Language::Russian => "Сбросить статистику".to_string(),
+        }
+    }
+
+    pub fn reset_statistics_confirmation(&self) -> String {
+        match self.language {
+            Language::English => "Reset all collected statement statistics?".to_string(),
+            Language::Russian => "Сбросить всю собранную статистику запросов?".to_string(),
+        }
+    }
+
+    pub fn pg_stat_statements_missing(&self) -> String {
+        match self.language {
+            Language::English => "The pg_stat_statements extension is not installed in this database. The server must also load it via shared_preload_libraries.".to_string(),
+            Language::Russian => "Расширение pg_stat_statements не установлено в этой базе данных. Сервер также должен загружать его через shared_preload_libraries.".to_string(),
+        }
+    }
+
+    pub fn create_extension(&self) -> String {
+        match self.language {
+            Language::English => "Create extension".to_string(),
+            Language::Russian => "Создать расширение".to_string(),
+        }
+    }
+
+    pub fn explain(&self) -> String {
+        match self.language {
+            Language::English => "Explain".to_string(),
+            Language::Russian => "Explain".to_string(),
+        }
+    }
+
+    pub fn calls(&self) -> String {
+        match self.language {
+            Language::English => "Calls".to_string(),
+            Language::Russian => "Вызовы".to_string(),
+        }
+    }
+
+    pub fn total_time(&self) -> String {
+        match self.language {
+            Language::English => "Total, ms".to_string(),
+            Language::Russian => "Всего, мс".to_string(),
+        }
+    }
+
+    pub fn mean_time(&self) -> String {
+        match self.language {
+            Language::English => "Mean, ms".to_string(),
+            Language::Russian => "Среднее, мс".to_string(),
+        }
+    }
+
+    pub fn stddev_time(&self) -> String {
+        match self.language {
+            Language::English => "Stddev, ms".to_string(),
+            Language::Russian => "Откл., мс".to_string(),
+        }
+    }
+
+    pub fn rows_column(&self) -> String {
+        match self.language {
+            Language::English => "Rows".to_string(),
+            Language::Russian => "Строки".to_string(),
+        }
+    }
+
+    pub fn shared_hit(&self) -> String {
+        match self.language {
+            Language::English => "Shared hit".to_string(),
+            Language::Russian => "Попадания".to_string(),
+        }
+    }
+
+    pub fn shared_read(&self) -> String {
+        match self.language {
+            Language::English => "Shared read".to_string(),
+            Language::Russian => "Чтения".to_string(),
+        }
+    }
+
+    pub fn temp_blocks(&self) -> String {
+        match self.language {
+            Language::English => "Temp blocks".to_string(),
+            Language::Russian => "Временные блоки".to_string(),
+        }
+    }
+
+    pub fn parameters_description(&self) -> String {
+        match self.language {
+            Language::English => "Enter SQL literals for the statement parameters, e.g. 42 or 'text'. Empty values are NULL.".to_string(),
+            Language::Russian => "Введите SQL-литералы для параметров запроса, например 42 или 'text'. Пустые значения — NULL.".to_string(),
+        }
+    }
+
+    pub fn explain_analyze(&self) -> String {
+        match self.language {
+            Language::English => "ANALYZE (executes the statement)".to_string(),
+            Language::Russian => "ANALYZE (выполняет запрос)".to_string(),
+        }
+    }
+
+    pub fn apply(&self) -> String {
+        match self.language {
+            Language::English => "Apply".to_string(),
+            Language::Russian => "Применить".to_string(),
+        }
+    }
+
+    pub fn open_in_sql_editor(&self) -> String {
+        match self.language {
+            Language::English => "Open in SQL editor".to_string(),
+            Language::Russian => "Открыть в SQL-редакторе".to_string(),
+        }
+    }
+
+    pub fn roles(&self) -> String {
+        match self.language {
+            Language::English => "Roles".to_string(),
+            Language::Russian => "Роли".to_string(),
+        }
+    }
+
+    pub fn create_role(&self) -> String {
+        match self.language {
+            Language::English => "Create role".to_string(),
+            Language::Russian => "Создать роль".to_string(),
+        }
+    }
+
+    pub fn edit_role(&self) -> String {
+        match self.language {
+            Language::English => "Edit role".to_string(),
+            Language::Russian => "Изменить роль".to_string(),
+        }
+    }
+
+    pub fn delete_role(&self) -> String {
+        match self.language {
+            Language::English => "Delete role".to_string(),
+            Language::Russian => "Удалить роль".to_string(),
+        }
+    }
+
+    pub fn member_of(&self) -> String {
+        match self.language {
+            Language::English => "Member of".to_string(),
+            Language::Russian => "Входит в".to_string(),
+        }
+    }
+
+    pub fn unchanged(&self) -> String {
+        match self.language {
+            Language::English => "Unchanged".to_string(),
+            Language::Russian => "Без изменений".to_string(),
+        }
+    }
+
+    pub fn valid_until(&self) -> String {
+        match self.language {
+            Language::English => "Valid until".to_string(),
+            Language::Russian => "Действует до".to_string(),
+        }
+    }
+
+    pub fn connection_limit(&self) -> String {
+        match self.language {
+            Language::English => "Connection limit".to_string(),
+            Language::Russian => "Лимит подключений".to_string(),
+        }
+    }
+
+    pub fn review(&self) -> String {
+        match self.language {
+            Language::English => "Review".to_string(),
+            Language::Russian => "Просмотреть".to_string(),
+        }
+    }
+
+    pub fn privileges(&self) -> String {
+        match self.language {
+            Language::English => "Privileges".to_string(),
+            Language::Russian => "Привилегии".to_string(),
+        }
+    }
+
+    pub fn discard(&self) -> String {
+        match self.language {
+            Language::English => "Discard".to_string(),
+            Language::Russian => "Отменить".to_string(),
+        }
+    }
+
+    pub fn effective_privilege(&self) -> String {
+        match self.language {
+            Language::English => "Held through role membership or ownership".to_string(),
+            Language::Russian => "Получена через членство в роли или владение".to_string(),
+        }
+    }
+
+    pub fn create_database(&self) -> String {
+        match self.language {
+            Language::English => "Create database".to_string(),
+            Language::Russian => "Создать базу данных".to_string(),
+        }
+    }
+
+    pub fn default_value(&self) -> String {
+        match self.language {
+            Language::English => "Default".to_string(),
+            Language::Russian => "По умолчанию".to_string(),
+        }
+    }
+
+    pub fn owner(&self) -> String {
+        match self.language {
+            Language::English => "Owner".to_string(),
+            Language::Russian => "Владелец".to_string(),
+        }
+    }
+
+    pub fn template(&self) -> String {
+        match self.language {
+            Language::English => "Template".to_string(),
+            Language::Russian => "Шаблон".to_string(),
+        }
+    }
+
+    pub fn encoding(&self) -> String {
+        match self.language {
+            Language::English => "Encoding".to_string(),
+            Language::Russian => "Кодировка".to_string(),
+        }
+    }
+
+    pub fn locale(&self) -> String {
+        match self.language {
+            Language::English => "Locale".to_string(),
+            Language::Russian => "Локаль".to_string(),
+        }
+    }
+
+    pub fn tablespace(&self) -> String {
+        match self.language {
+            Language::English => "Tablespace".to_string(),
+            Language::Russian => "Табличное пространство".to_string(),
+        }
+    }
+
+    pub fn alter_database(&self) -> String {
+        match self.language {
+            Language::English => "Rename or change owner".to_string(),
+            Language::Russian => "Переименовать или сменить владельца".to_string(),
+        }
+    }
+
+    pub fn alter_database_note(&self) -> String {
+        match self.language {
+            Language::English => "Renaming closes open connections to the database; it fails while other sessions use it.".to_string(),
+            Language::Russian => "Переименование закрывает открытые подключения к базе; оно не выполнится, пока её используют другие сеансы.".to_string(),
+        }
+    }
+
+    pub fn drop_database(&self) -> String {
+        match self.language {
+            Language::English => "Drop database".to_string(),
+            Language::Russian => "Удалить базу данных".to_string(),
+        }
+    }
+
+    pub fn drop_database_confirmation(&self, name: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Drop database {} and all of its data?", name.to_string()),
+            Language::Russian => format!("Удалить базу данных {} со всеми данными?", name.to_string()),
+        }
+    }
+
+    pub fn drop_database_force(&self) -> String {
+        match self.language {
+            Language::English => "Terminate other connections (FORCE)".to_string(),
+            Language::Russian => "Завершить другие подключения (FORCE)".to_string(),
+        }
+    }
+
+    pub fn drop_database_force_hint(&self) -> String {
+        match self.language {
+            Language::English => "Requires PostgreSQL 13 or later".to_string(),
+            Language::Russian => "Требуется PostgreSQL 13 или новее".to_string(),
+        }
+    }
+
+    pub fn size(&self) -> String {
+        match self.language {
+            Language::English => "Size".to_string(),
+            Language::Russian => "Размер".to_string(),
+        }
+    }
+
+    pub fn unlimited(&self) -> String {
+        match self.language {
+            Language::English => "Unlimited".to_string(),
+            Language::Russian => "Без ограничений".to_string(),
+        }
+    }
+
+    pub fn collation(&self) -> String {
+        match self.language {
+            Language::English => "Collation".to_string(),
+            Language::Russian => "Правило сортировки".to_string(),
+        }
+    }
+
+    pub fn ctype(&self) -> String {
+        match self.language {
+            Language::English => "Character type".to_string(),
+            Language::Russian => "Тип символов".to_string(),
+        }
+    }
+
+    pub fn allow_connections(&self) -> String {
+        match self.language {
+            Language::English => "Allow connections".to_string(),
+            Language::Russian => "Подключения разрешены".to_string(),
+        }
+    }
+
+    pub fn is_template(&self) -> String {
+        match self.language {
+            Language::English => "Template database".to_string(),
+            Language::Russian => "Шаблонная база".to_string(),
+        }
+    }
+
+    pub fn properties(&self) -> String {
+        match self.language {
+            Language::English => "Properties".to_string(),
+            Language::Russian => "Свойства".to_string(),
+        }
+    }
+
+    pub fn new_table(&self) -> String {
+        match self.language {
+            Language::English => "New table".to_string(),
+            Language::Russian => "Новая таблица".to_string(),
+        }
+    }
+
+    pub fn schema(&self) -> String {
+        match self.language {
+            Language::English => "Schema".to_string(),
+            Language::Russian => "Схема".to_string(),
+        }
+    }
+
+    pub fn columns(&self) -> String {
+        match self.language {
+            Language::English => "Columns".to_string(),
+            Language::Russian => "Столбцы".to_string(),
+        }
+    }
+
+    pub fn data_type(&self) -> String {
+        match self.language {
+            Language::English => "Type".to_string(),
+            Language::Russian => "Тип".to_string(),
+        }
+    }
+
+    pub fn column_order_note(&self) -> String {
+        match self.language {
+            Language::English => "Existing columns keep their order in the database; new columns are added after them.".to_string(),
+            Language::Russian => "Существующие столбцы сохраняют свой порядок в базе; новые столбцы добавляются после них.".to_string(),
+        }
+    }
+
+    pub fn add_column(&self) -> String {
+        match self.language {
+            Language::English => "Add column".to_string(),
+            Language::Russian => "Добавить столбец".to_string(),
+        }
+    }
+
+    pub fn constraints(&self) -> String {
+        match self.language {
+            Language::English => "Constraints".to_string(),
+            Language::Russian => "Ограничения".to_string(),
+        }
+    }
+
+    pub fn expression(&self) -> String {
+        match self.language {
+            Language::English => "Expression".to_string(),
+            Language::Russian => "Выражение".to_string(),
+        }
+    }
+
+    pub fn table(&self) -> String {
+        match self.language {
+            Language::English => "Table".to_string(),
+            Language::Russian => "Таблица".to_string(),
+        }
+    }
+
+    pub fn add_constraint(&self) -> String {
+        match self.language {
+            Language::English => "Add constraint".to_string(),
+            Language::Russian => "Добавить ограничение".to_string(),
+        }
+    }
+
+    pub fn indexes(&self) -> String {
+        match self.language {
+            Language::English => "Indexes".to_string(),
+            Language::Russian => "Индексы".to_string(),
+        }
+    }
+
+    pub fn add_index(&self) -> String {
+        match self.language {
+            Language::English => "Add index".to_string(),
+            Language::Russian => "Добавить индекс".to_string(),
+        }
+    }
+
+    pub fn design_table(&self) -> String {
+        match self.language {
+            Language::English => "Design table".to_string(),
+            Language::Russian => "Редактировать структуру".to_string(),
+        }
+    }
+
+    pub fn create_table(&self) -> String {
+        match self.language {
+            Language::English => "Create table".to_string(),
+            Language::Russian => "Создать таблицу".to_string(),
+        }
+    }
+
+    pub fn indexes_of(&self, table: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Indexes of {}", table.to_string()),
+            Language::Russian => format!("Индексы {}", table.to_string()),
+        }
+    }
+
+    pub fn create_index(&self) -> String {
+        match self.language {
+            Language::English => "Create index".to_string(),
+            Language::Russian => "Создать индекс".to_string(),
+        }
+    }
+
+    pub fn concurrently_hint(&self) -> String {
+        match self.language {
+            Language::English => "Avoids blocking writes; cannot run inside a transaction".to_string(),
+            Language::Russian => "Не блокирует запись; не выполняется внутри транзакции".to_string(),
+        }
+    }
+
+    pub fn scans(&self) -> String {
+        match self.language {
+            Language::English => "Scans".to_string(),
+            Language::Russian => "Сканирований".to_string(),
+        }
+    }
+
+    pub fn tuples_read(&self) -> String {
+        match self.language {
+            Language::English => "Tuples read".to_string(),
+            Language::Russian => "Прочитано записей".to_string(),
+        }
+    }
+
+    pub fn tuples_fetched(&self) -> String {
+        match self.language {
+            Language::English => "Tuples fetched".to_string(),
+            Language::Russian => "Выбрано записей".to_string(),
+        }
+    }
+
+    pub fn bloat_estimate(&self) -> String {
+        match self.language {
+            Language::English => "Bloat (estimate)".to_string(),
+            Language::Russian => "Раздувание (оценка)".to_string(),
+        }
+    }
+
+    pub fn hints(&self) -> String {
+        match self.language {
+            Language::English => "Hints".to_string(),
+            Language::Russian => "Подсказки".to_string(),
+        }
+    }
+
+    pub fn definition(&self) -> String {
+        match self.language {
+            Language::English => "Definition".to_string(),
+            Language::Russian => "Определение".to_string(),
+        }
+    }
+
+    pub fn reindex(&self) -> String {
+        match self.language {
+            Language::English => "Reindex".to_string(),
+            Language::Russian => "Перестроить".to_string(),
+        }
+    }
+
+    pub fn drop_index(&self) -> String {
+        match self.language {
+            Language::English => "Drop index".to_string(),
+            Language::Russian => "Удалить индекс".to_string(),
+        }
+    }
+
+    pub fn invalid_index(&self) -> String {
+        match self.language {
+            Language::English => "Invalid".to_string(),
+            Language::Russian => "Недействителен".to_string(),
+        }
+    }
+
+    pub fn duplicate_of(&self, name: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Duplicate of {}", name.to_string()),
+            Language::Russian => format!("Дубликат {}", name.to_string()),
+        }
+    }
+
+    pub fn covered_by(&self, name: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Covered by {}", name.to_string()),
+            Language::Russian => format!("Покрывается {}", name.to_string()),
+        }
+    }
+
+    pub fn unused_index(&self) -> String {
+        match self.language {
+            Language::English => "Never scanned".to_string(),
+            Language::Russian => "Не использовался".to_string(),
+        }
+    }
+
+    pub fn method(&self) -> String {
+        match self.language {
+            Language::English => "Method".to_string(),
+            Language::Russian => "Метод".to_string(),
+        }
+    }
+
+    pub fn er_diagram(&self) -> String {
+        match self.language {
+            Language::English => "ER diagram".to_string(),
+            Language::Russian => "ER-диаграмма".to_string(),
+        }
+    }
+
+    pub fn all_tables(&self) -> String {
+        match self.language {
+            Language::English => "All tables".to_string(),
+            Language::Russian => "Все таблицы".to_string(),
+        }
+    }
+
+    pub fn depth(&self) -> String {
+        match self.language {
+            Language::English => "Depth".to_string(),
+            Language::Russian => "Глубина".to_string(),
+        }
+    }
+
+    pub fn auto_layout(&self) -> String {
+        match self.language {
+            Language::English => "Auto layout".to_string(),
+            Language::Russian => "Авторасстановка".to_string(),
+        }
+    }
+
+    pub fn fit_to_view(&self) -> String {
+        match self.language {
+            Language::English => "Fit to view".to_string(),
+            Language::Russian => "Вписать в окно".to_string(),
+        }
+    }
+
+    pub fn schema_compare(&self) -> String {
+        match self.language {
+            Language::English => "Schema compare".to_string(),
+            Language::Russian => "Сравнение схем".to_string(),
+        }
+    }
+
+    pub fn compare_schema(&self) -> String {
+        match self.language {
+            Language::English => "Compare schema".to_string(),
+            Language::Russian => "Сравнить схему".to_string(),
+        }
+    }
+
+    pub fn source(&self) -> String {
+        match self.language {
+            Language::English => "Source".to_string(),
+            Language::Russian => "Источник".to_string(),
+        }
+    }
+
+    pub fn target(&self) -> String {
+        match self.language {
+            Language::English => "Target".to_string(),
+            Language::Russian => "Цель".to_string(),
+        }
+    }
+
+    pub fn compare(&self) -> String {
+        match self.language {
+            Language::English => "Compare".to_string(),
+            Language::Russian => "Сравнить".to_string(),
+        }
+    }
+
+    pub fn added(&self) -> String {
+        match self.language {
+            Language::English => "Added".to_string(),
+            Language::Russian => "Добавлено".to_string(),
+        }
+    }
+
+    pub fn removed(&self) -> String {
+        match self.language {
+            Language::English => "Removed".to_string(),
+            Language::Russian => "Удалено".to_string(),
+        }
+    }
+
+    pub fn changed(&self) -> String {
+        match self.language {
+            Language::English => "Changed".to_string(),
+            Language::Russian => "Изменено".to_string(),
+        }
+    }
+
+    pub fn select_all(&self) -> String {
+        match self.language {
+            Language::English => "Select all".to_string(),
+            Language::Russian => "Выбрать все".to_string(),
+        }
+    }
+
+    pub fn select_none(&self) -> String {
+        match self.language {
+            Language::English => "Select none".to_string(),
+            Language::Russian => "Снять выбор".to_string(),
+        }
+    }
+
+    pub fn migration_script(&self) -> String {
+        match self.language {
+            Language::English => "Migration script".to_string(),
+            Language::Russian => "Скрипт миграции".to_string(),
+        }
+    }
+
+    pub fn no_differences(&self) -> String {
+        match self.language {
+            Language::English => "No differences".to_string(),
+            Language::Russian => "Различий нет".to_string(),
+        }
+    }
+
+    pub fn database_not_loaded(&self) -> String {
+        match self.language {
+            Language::English => "Database is not loaded".to_string(),
+            Language::Russian => "База данных не загружена".to_string(),
+        }
+    }
+
+    pub fn data_compare(&self) -> String {
+        match self.language {
+            Language::English => "Data compare".to_string(),
+            Language::Russian => "Сравнение данных".to_string(),
+        }
+    }
+
+    pub fn compare_data(&self) -> String {
+        match self.language {
+            Language::English => "Compare data".to_string(),
+            Language::Russian => "Сравнить данные".to_string(),
+        }
+    }
+
+    pub fn column(&self) -> String {
+        match self.language {
+            Language::English => "Column".to_string(),
+            Language::Russian => "Столбец".to_string(),
+        }
+    }
+
+    pub fn sync_script(&self) -> String {
+        match self.language {
+            Language::English => "Sync script".to_string(),
+            Language::Russian => "Скрипт синхронизации".to_string(),
+        }
+    }
+
+    pub fn rows_compared(&self, rows: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("{} rows compared", rows.to_string()),
+            Language::Russian => format!("Сравнено строк: {}", rows.to_string()),
+        }
+    }
+
+    pub fn differences_truncated(&self, count: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Only the first {} differences are listed", count.to_string()),
+            Language::Russian => format!("Показаны только первые {} различий", count.to_string()),
+        }
+    }
+
+    pub fn skipped_columns(&self, columns: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Not compared, missing in one of the tables: {}", columns.to_string()),
+            Language::Russian => format!("Не сравниваются, нет в одной из таблиц: {}", columns.to_string()),
+        }
+    }
+
+    pub fn backup_database(&self) -> String {
+        match self.language {
+            Language::English => "Backup".to_string(),
+            Language::Russian => "Резервная копия".to_string(),
+        }
+    }
+
+    pub fn restore_database(&self) -> String {
+        match self.language {
+            Language::English => "Restore".to_string(),
+            Language::Russian => "Восстановление".to_string(),
+        }
+    }
+
+    pub fn run_sql_file(&self) -> String {
+        match self.language {
+            Language::English => "Run SQL file".to_string(),
+            Language::Russian => "Выполнить SQL-файл".to_string(),
+        }
+    }
+
+    pub fn browse(&self) -> String {
+        match self.language {
+            Language::English => "Browse".to_string(),
+            Language::Russian => "Обзор".to_string(),
+        }
+    }
+
+    pub fn format(&self) -> String {
+        match self.language {
+            Language::English => "Format".to_string(),
+            Language::Russian => "Формат".to_string(),
+        }
+    }
+
+    pub fn schemas(&self) -> String {
+        match self.language {
+            Language::English => "Schemas".to_string(),
+            Language::Russian => "Схемы".to_string(),
+        }
+    }
+
+    pub fn comma_separated(&self) -> String {
+        match self.language {
+            Language::English => "Comma separated".to_string(),
+            Language::Russian => "Через запятую".to_string(),
+        }
+    }
+
+    pub fn compression(&self) -> String {
+        match self.language {
+            Language::English => "Compression".to_string(),
+            Language::Russian => "Сжатие".to_string(),
+        }
+    }
+
+    pub fn jobs(&self) -> String {
+        match self.language {
+            Language::English => "Jobs".to_string(),
+            Language::Russian => "Потоки".to_string(),
+        }
+    }
+
+    pub fn data_only(&self) -> String {
+        match self.language {
+            Language::English => "Data only".to_string(),
+            Language::Russian => "Только данные".to_string(),
+        }
+    }
+
+    pub fn schema_only(&self) -> String {
+        match self.language {
+            Language::English => "Schema only".to_string(),
+            Language::Russian => "Только схема".to_string(),
+        }
+    }
+
+    pub fn clean_before_restore(&self) -> String {
+        match self.language {
+            Language::English => "Drop objects before creating".to_string(),
+            Language::Russian => "Удалять объекты перед созданием".to_string(),
+        }
+    }
+
+    pub fn single_transaction(&self) -> String {
+        match self.language {
+            Language::English => "Single transaction".to_string(),
+            Language::Russian => "Одна транзакция".to_string(),
+        }
+    }
+
+    pub fn finished_successfully(&self, seconds: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Finished successfully in {} s", seconds.to_string()),
+            Language::Russian => format!("Успешно завершено за {} с", seconds.to_string()),
+        }
+    }
+
+    pub fn backup_failed(&self, code: impl ToString) -> String {
+        match self.language {
+            Language::English => format!("Failed with exit code {}", code.to_string()),
+            Language::Russian => format!("Завершено с ошибкой, код {}", code.to_string()),
+        }
+    }
+
+    pub fn backup_cancelled(&self) -> String {
+        match self.language {
+            Language::English => "Cancelled".to_string(),
+            Language::Russian => "Отменено".to_string(),
+        }
+    }
+
+    pub fn start(&self) -> String {
+        match self.language {
+            Language::English => "Start".to_string(),
+            Language::Russian => "Запустить".to_string(),
+        }
+    }
+
+    pub fn maintenance(&self) -> String {
+        match self.language {
+            Language::English => "Maintenance".to_string(),
+            Language::Russian => "Обслуживание".to_string(),
+        }
+    }
+
+    pub fn command(&self) -> String {
+        match self.language {
+            Language::English => "Command".to_string(),
+            Language::Russian => "Команда".to_string(),
+        }
+    }
+
+    pub fn index(&self) -> String {
+        match self.language {
+            Language::English => "Index".to_string(),
+            Language::Russian => "Индекс".to_string(),
+        }
+    }
+
+    pub fn cluster_index_hint(&self) -> String {
+        match self.language {
+            Language::English => "Index the table was clustered on before".to_string(),
+            Language::Russian => "Индекс предыдущей кластеризации".to_string(),
+        }
+    }
+
+    pub fn data_output(&self) -> String {
+        match self.language {
+            Language::English => "Data output".to_string(),
+            Language::Russian => "Результат".to_string(),
+        }
+    }
+
+    pub fn messages(&self) -> String {
+        match self.language {
+            Language::English => "Messages".to_string(),
+            Language::Russian => "Сообщения".to_string(),
+        }
+    }
+
+    pub fn severity(&self) -> String {
+        match self.language {
+            Language::English => "Severity".to_string(),
+            Language::Russian => "Уровень".to_string(),
+        }
+    }
+
+    pub fn detail(&self) -> String {
+        match self.language {
+            Language::English => "Detail".to_string(),
+            Language::Russian => "Подробности".to_string(),
+        }
+    }
+
+    pub fn hint(&self) -> String {
+        match self.language {
+            Language::English => "Hint".to_string(),
+            Language::Russian => "Подсказка".to_string(),
+        }
+    }
+
+    pub fn error_context(&self) -> String {
+        match self.language {
+            Language::English => "Context".to_string(),
+            Language::Russian => "Контекст".to_string(),
+        }
+    }
+
+    pub fn constraint(&self) -> String {
+        match self.language {
+            Language::English => "Constraint".to_string(),
+            Language::Russian => "Ограничение".to_string(),
+        }
+    }
+
+    pub fn position(&self) -> String {
+        match self.language {
+            Language::English => "Position".to_string(),
+            Language::Russian => "Позиция".to_string(),
+        }
+    }
+
+    pub fn line_column(&self, line: usize, column: usize) -> String {
+        match self.language {
+            Language::English => format!("Line {}, column {}", line, column),
+            Language::Russian => format!("Строка {}, столбец {}", line, column),
+        }
+    }
+
+    pub fn password_missing(&self) -> String {
+        match self.language {
+            Language::English => "No password is stored for this server".to_string(),
+            Language::Russian => "Для этого сервера не сохранён пароль".to_string(),
+        }
+    }
+
+    pub fn explain_analyze_writes(&self) -> String {
+        match self.language {
+            Language::English => "The statement may modify data. Writes will fail and nothing is kept".to_string(),
+            Language::Russian => "Запрос может изменить данные. Запись завершится ошибкой, изменения не сохранятся".to_string(),
+        }
+    }
+
+    pub fn read_only_rolled_back(&self) -> String {
+        match self.language {
+            Language::English => "Runs in a read-only transaction that is rolled back".to_string(),
+            Language::Russian => "Выполняется в транзакции только для чтения с откатом".to_string(),
+        }
+    }
+
+    pub fn service_database_rename_note(&self) -> String {
+        match self.language {
+            Language::English => "This is the server's service database, which the app connects to; rename it after choosing another one in the server settings.".to_string(),
+            Language::Russian => "Это служебная база сервера, к которой подключается приложение; переименуйте её, выбрав другую в настройках сервера.".to_string(),
+        }
+    }
+
 }
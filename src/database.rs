@@ -1,30 +1,199 @@
-use sqlx::{Column, PgPool, Row, TypeInfo};
+use sqlx::{Column, Executor, PgPool, Row, TypeInfo};
 use sqlx_postgres::PgPoolOptions;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use sqlx::postgres::types::{PgInterval, PgMoney};
+use sqlx::postgres::{PgConnectOptions, PgErrorPosition, PgSeverity};
 
 use chrono::{DateTime, NaiveDate, NaiveTime, Utc};
 
-use crate::data::structs::ValueType;
+use crate::data::structs::{
+    ActivitySession, DatabaseOptions, DatabaseProperties, ErColumn, ErModel, ErRelation, ErTable, IndexStats, MaintenanceProgress, PrivilegeObject, PrivilegeObjectKind, ReplicaStats, RoleInfo, SchemaColumn, SchemaObject, SchemaObjectKind, QueryError, ServerNotice, ServerStats,
+    StatementStats, TableColumn, TableConstraint, TableConstraintKind, TableDefinition, TableIndex,
+    ValueType,
+};
 
 use indexmap::IndexMap;
 
+/// sqlx doesn't expose server notices, it only forwards them to `log`
+/// under this target, with the severity folded into the log level.
+const NOTICE_TARGET: &str = "sqlx::postgres::notice";
+
+pub type NoticeSink = Arc<dyn Fn(ServerNotice) + Send + Sync>;
+
+tokio::task_local! {
+    static NOTICE_SINK: NoticeSink;
+}
+
+/// The app's `log` max level, and how many `with_notices` calls are running.
+static NOTICE_CAPTURES: Mutex<(log::LevelFilter, usize)> = Mutex::new((log::LevelFilter::Off, 0));
+
+/// Raises the `log` max level to `Trace` while any capture is alive, since
+/// sqlx logs `INFO` notices, such as the output of `VACUUM VERBOSE`, at
+/// that level.
+struct NoticeCapture;
+
+impl NoticeCapture {
+    fn start() -> Self {
+        let mut captures = NOTICE_CAPTURES.lock().unwrap();
+        captures.1 += 1;
+        log::set_max_level(log::LevelFilter::Trace);
+
+        Self
+    }
+}
+
+impl Drop for NoticeCapture {
+    fn drop(&mut self) {
+        let mut captures = NOTICE_CAPTURES.lock().unwrap();
+        captures.1 -= 1;
+        if captures.1 == 0 {
+            log::set_max_level(captures.0);
+        }
+    }
+}
+
+/// Passes notices the server sends while `future` runs to `sink`.
+///
+/// Notices are picked out of sqlx's log output, so only their severity and
+/// message survive: detail, hint and SQLSTATE are lost. Capturing also
+/// raises the process-wide `log` max level to `Trace` until the last
+/// capture ends, which makes trace logging costlier on every thread.
+pub async fn with_notices<F: Future>(sink: NoticeSink, future: F) -> F::Output {
+    let _capture = NoticeCapture::start();
+
+    NOTICE_SINK.scope(sink, future).await
+}
+
+/// Wraps the app logger, diverting notices raised inside `with_notices`.
+pub struct NoticeLogger<L> {
+    inner: L,
+}
+
+impl<L: log::Log + 'static> NoticeLogger<L> {
+    /// Installs the logger with `max_level`, the level `inner` filters at,
+    /// which is only raised while notices are captured.
+    pub fn install(inner: L, max_level: log::LevelFilter) -> Result<(), log::SetLoggerError> {
+        NOTICE_CAPTURES.lock().unwrap().0 = max_level;
+        log::set_boxed_logger(Box::new(Self { inner }))?;
+        log::set_max_level(max_level);
+
+        Ok(())
+    }
+}
+
+impl<L: log::Log> log::Log for NoticeLogger<L> {
+    fn enabled(&self, metadata: &log::Metadata) -> bool {
+        (metadata.target() == NOTICE_TARGET && NOTICE_SINK.try_with(|_| ()).is_ok())
+            || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &log::Record) {
+        if record.target() == NOTICE_TARGET {
+            let notice = ServerNotice {
+                severity: match record.level() {
+                    log::Level::Error => "ERROR",
+                    log::Level::Warn => "WARNING",
+                    log::Level::Info => "NOTICE",
+                    log::Level::Debug => "DEBUG",
+                    log::Level::Trace => "INFO",
+                },
+                message: record.args().to_string(),
+            };
+
+            if NOTICE_SINK.try_with(|sink| sink(notice)).is_ok() {
+                return;
+            }
+        }
+
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+fn query_error(error: sqlx::Error) -> QueryError {
+    let Some(error) = error
+        .as_database_error()
+        .and_then(|error| error.try_downcast_ref::<sqlx::postgres::PgDatabaseError>())
+    else {
+        return QueryError {
+            message: error.to_string(),
+            ..Default::default()
+        };
+    };
+
+    QueryError {
+        message: error.message().to_string(),
+        severity: Some(match error.severity() {
+            PgSeverity::Panic => "PANIC",
+            PgSeverity::Fatal => "FATAL",
+            PgSeverity::Error => "ERROR",
+            PgSeverity::Warning => "WARNING",
+            PgSeverity::Notice => "NOTICE",
+            PgSeverity::Debug => "DEBUG",
+            PgSeverity::Info => "INFO",
+            PgSeverity::Log => "LOG",
+        }),
+        code: Some(error.code().to_string()),
+        detail: error.detail().map(str::to_string),
+        hint: error.hint().map(str::to_string),
+        context: error.r#where().map(str::to_string),
+        schema: error.schema().map(str::to_string),
+        table: error.table().map(str::to_string),
+        column: error.column().map(str::to_string),
+        data_type: error.data_type().map(str::to_string),
+        constraint: error.constraint().map(str::to_string),
+        // Positions inside function bodies are reported in `context`.
+        position: match error.position() {
+            Some(PgErrorPosition::Original(position)) => position.checked_sub(1),
+            _ => None,
+        },
+    }
+}
+
 #[derive(Clone)]
 pub struct Database {
     pool: PgPool,
 }
 
-impl Database {
-    pub async fn new(url: impl ToString) -> Result<Self, String> {
-        let pool = PgPoolOptions::new().connect(&url.to_string()).await;
+/// A cursor in a read-only transaction of its own, for reading a large
+/// result in batches. Every column is read as text.
+pub struct Cursor {
+    transaction: sqlx::Transaction<'static, sqlx::Postgres>,
+}
 
-        if let Ok(pool) = pool {
-            return Ok(Self { pool });
-        } else if let Err(e) = pool {
-            return Err(e.to_string());
-        }
+impl Cursor {
+    /// Reads up to `count` rows, fewer once the result runs out.
+    pub async fn fetch(&mut self, count: usize) -> Result<Vec<Vec<Option<String>>>, String> {
+        let rows = sqlx::query(&format!("FETCH {} FROM rs_postgres_cursor", count))
+            .fetch_all(&mut *self.transaction)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        rows.into_iter()
+            .map(|row| (0..row.len()).map(|index| row.try_get::<Option<String>, _>(index)).collect())
+            .collect::<Result<Vec<Vec<Option<String>>>, sqlx::Error>>()
+            .map_err(|e| e.to_string())
+    }
+}
+
+impl Database {
+    /// Creates a lazy pool: no connection is opened until the first query,
+    /// and connections idle for longer than `idle_timeout` are closed.
+    pub fn new(options: PgConnectOptions, max_connections: u32, idle_timeout: Duration) -> Self {
+        let pool = PgPoolOptions::new()
+            .max_connections(max_connections)
+            .min_connections(0)
+            .idle_timeout(Some(idle_timeout))
+            .connect_lazy_with(options);
 
-        Err(String::from("Unknown error"))
+        Self { pool }
     }
 
     pub async fn get_tables(&self) -> Result<Vec<String>, String> {
@@ -42,7 +211,7 @@ impl Database {
     }
 
     pub async fn get_databases(&self) -> Result<Vec<String>, String> {
-        let rows = sqlx::query("SELECT datname FROM pg_database")
+        let rows = sqlx::query("SELECT datname FROM pg_database WHERE datallowconn AND NOT datistemplate ORDER BY datname")
             .fetch_all(&self.pool)
             .await
             .map_err(|e| e.to_string())?;
@@ -55,120 +224,1420 @@ impl Database {
         Ok(databases)
     }
 
+    pub async fn get_database_properties(&self, name: &str) -> Result<DatabaseProperties, String> {
+        let row = sqlx::query(
+            "SELECT pg_get_userbyid(d.datdba)::text AS owner,
+                pg_encoding_to_char(d.encoding)::text AS encoding,
+                d.datcollate::text AS collation,
+                d.datctype::text AS ctype,
+                t.spcname::text AS tablespace,
+                pg_database_size(d.oid) AS size,
+                (SELECT count(*) FROM pg_stat_activity a WHERE a.datid = d.oid) AS connections,
+                d.datconnlimit,
+                d.datallowconn,
+                d.datistemplate
+            FROM pg_database d
+            JOIN pg_tablespace t ON t.oid = d.dattablespace
+            WHERE d.datname = $1",
+        )
+        .bind(name)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Database {} not found", name))?;
+
+        (|| {
+            Ok(DatabaseProperties {
+                owner: row.try_get("owner")?,
+                encoding: row.try_get("encoding")?,
+                collation: row.try_get("collation")?,
+                ctype: row.try_get("ctype")?,
+                tablespace: row.try_get("tablespace")?,
+                size: row.try_get("size")?,
+                connections: row.try_get("connections")?,
+                connection_limit: row.try_get("datconnlimit")?,
+                allow_connections: row.try_get("datallowconn")?,
+                is_template: row.try_get("datistemplate")?,
+            })
+        })()
+        .map_err(|e: sqlx::Error| e.to_string())
+    }
+
+    pub async fn get_database_options(&self) -> Result<DatabaseOptions, String> {
+        let names = |query: &'static str| async move {
+            sqlx::query_scalar::<_, String>(query)
+                .fetch_all(&self.pool)
+                .await
+                .map_err(|e| e.to_string())
+        };
+
+        Ok(DatabaseOptions {
+            owners: names("SELECT rolname::text FROM pg_roles WHERE rolname NOT LIKE 'pg\\_%' ORDER BY 1").await?,
+            templates: names("SELECT datname::text FROM pg_database WHERE datallowconn OR datistemplate ORDER BY datistemplate DESC, 1").await?,
+            tablespaces: names("SELECT spcname::text FROM pg_tablespace ORDER BY 1").await?,
+        })
+    }
+
+    /// Closes every connection of the pool, including those of its clones.
+    pub async fn close(&self) {
+        self.pool.close().await;
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.pool.is_closed()
+    }
+
+    /// Loads columns, constraints and indexes of a table. Without a
+    /// schema, `public` is preferred when several schemas have the table.
+    pub async fn get_table_definition(&self, schema: Option<&str>, name: &str) -> Result<TableDefinition, String> {
+        let (oid, schema, name): (sqlx::postgres::types::Oid, String, String) = sqlx::query_as(
+            "SELECT c.oid, n.nspname::text, c.relname::text
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relname = $1 AND ($2::text IS NULL OR n.nspname = $2) AND c.relkind IN ('r', 'p')
+            ORDER BY n.nspname <> 'public', n.nspname
+            LIMIT 1",
+        )
+        .bind(name)
+        .bind(schema)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Table {} not found", name))?;
+
+        let columns = sqlx::query(
+            "SELECT a.attname::text AS name,
+                format_type(a.atttypid, a.atttypmod) AS data_type,
+                a.attnotnull,
+                COALESCE(pg_get_expr(d.adbin, d.adrelid), '') AS default
+            FROM pg_attribute a
+            LEFT JOIN pg_attrdef d ON d.adrelid = a.attrelid AND d.adnum = a.attnum
+            WHERE a.attrelid = $1 AND a.attnum > 0 AND NOT a.attisdropped
+            ORDER BY a.attnum",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| {
+            let name: String = row.try_get("name")?;
+
+            Ok(TableColumn {
+                original_name: Some(name.clone()),
+                name,
+                data_type: row.try_get("data_type")?,
+                not_null: row.try_get("attnotnull")?,
+                default: row.try_get("default")?,
+            })
+        })
+        .collect::<Result<Vec<TableColumn>, sqlx::Error>>()
+        .map_err(|e| e.to_string())?;
+
+        let constraints = sqlx::query(
+            "SELECT con.conname::text AS name,
+                con.contype::text AS kind,
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(con.conkey) WITH ORDINALITY k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                ) AS columns,
+                COALESCE(pg_get_expr(con.conbin, con.conrelid), '') AS expression,
+                COALESCE(NULLIF(con.confrelid, 0)::regclass::text, '') AS ref_table,
+                ARRAY(
+                    SELECT a.attname::text
+                    FROM unnest(con.confkey) WITH ORDINALITY k(attnum, ord)
+                    JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum
+                    ORDER BY k.ord
+                ) AS ref_columns,
+                con.confdeltype::text AS on_delete,
+                con.confupdtype::text AS on_update
+            FROM pg_constraint con
+            WHERE con.conrelid = $1 AND con.contype IN ('p', 'u', 'c', 'f')
+            ORDER BY position(con.contype IN 'pufc'), con.conname",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| {
+            let action = |code: String| {
+                match code.as_str() {
+                    "r" => "RESTRICT",
+                    "c" => "CASCADE",
+                    "n" => "SET NULL",
+                    "d" => "SET DEFAULT",
+                    _ => "NO ACTION",
+                }
+                .to_string()
+            };
+            let kind: String = row.try_get("kind")?;
+            let name: String = row.try_get("name")?;
+
+            Ok(TableConstraint {
+                original_name: Some(name.clone()),
+                name,
+                kind: match kind.as_str() {
+                    "p" => TableConstraintKind::PrimaryKey,
+                    "u" => TableConstraintKind::Unique,
+                    "c" => TableConstraintKind::Check,
+                    _ => TableConstraintKind::ForeignKey,
+                },
+                columns: row.try_get::<Vec<String>, _>("columns")?.join(", "),
+                expression: row.try_get("expression")?,
+                ref_table: row.try_get("ref_table")?,
+                ref_columns: row.try_get::<Vec<String>, _>("ref_columns")?.join(", "),
+                on_delete: action(row.try_get("on_delete")?),
+                on_update: action(row.try_get("on_update")?),
+            })
+        })
+        .collect::<Result<Vec<TableConstraint>, sqlx::Error>>()
+        .map_err(|e| e.to_string())?;
+
+        // Indexes backing constraints are managed through the constraint.
+        let indexes = sqlx::query(
+            "SELECT ic.relname::text AS name,
+                i.indisunique,
+                am.amname::text AS method,
+                pg_get_indexdef(i.indexrelid, 0, true) AS definition,
+                COALESCE(pg_get_expr(i.indpred, i.indrelid, true), '') AS predicate
+            FROM pg_index i
+            JOIN pg_class ic ON ic.oid = i.indexrelid
+            JOIN pg_am am ON am.oid = ic.relam
+            WHERE i.indrelid = $1
+                AND NOT EXISTS (
+                    SELECT 1 FROM pg_constraint con
+                    WHERE con.conindid = i.indexrelid AND con.contype IN ('p', 'u', 'x')
+                )
+            ORDER BY 1",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .into_iter()
+        .map(|row| {
+            let name: String = row.try_get("name")?;
+            let (columns, include) = index_columns(&row.try_get::<String, _>("definition")?);
+
+            Ok(TableIndex {
+                original_name: Some(name.clone()),
+                name,
+                columns,
+                include,
+                unique: row.try_get("indisunique")?,
+                method: row.try_get("method")?,
+                predicate: row.try_get("predicate")?,
+            })
+        })
+        .collect::<Result<Vec<TableIndex>, sqlx::Error>>()
+        .map_err(|e| e.to_string())?;
+
+        Ok(TableDefinition {
+            schema,
+            name,
+            columns,
+            constraints,
+            indexes,
+        })
+    }
+
+    /// Lists the indexes of a table with their statistics, along with the
+    /// schema of the table and its column names. Without a schema in the
+    /// name, `public` is preferred when several schemas have the table.
+    pub async fn get_indexes(&self, table: &str) -> Result<(String, Vec<String>, Vec<IndexStats>), String> {
+        let (oid, schema): (sqlx::postgres::types::Oid, String) = sqlx::query_as(
+            "SELECT c.oid, n.nspname::text
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relname = $1 AND c.relkind IN ('r', 'p', 'm')
+            ORDER BY n.nspname <> 'public', n.nspname
+            LIMIT 1",
+        )
+        .bind(table)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Table {} not found", table))?;
+
+        let columns: Vec<String> = sqlx::query_scalar(
+            "SELECT attname::text FROM pg_attribute
+            WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped
+            ORDER BY attnum",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        // The bloat estimate compares the pages of a btree index with the
+        // pages its tuples would fill at the default fillfactor of 90.
+        let rows = sqlx::query(
+            "SELECT ic.relname::text AS name,
+                pg_get_indexdef(i.indexrelid) AS definition,
+                pg_relation_size(i.indexrelid) AS size,
+                COALESCE(s.idx_scan, 0) AS scans,
+                COALESCE(s.idx_tup_read, 0) AS tuples_read,
+                COALESCE(s.idx_tup_fetch, 0) AS tuples_fetched,
+                i.indisunique, i.indisprimary, i.indisvalid,
+                (
+                    SELECT con.conname::text FROM pg_constraint con
+                    WHERE con.conindid = i.indexrelid AND con.conrelid = i.indrelid
+                        AND con.contype IN ('p', 'u', 'x')
+                ) AS constraint_name,
+                (
+                    SELECT o.relname::text
+                    FROM pg_index oi
+                    JOIN pg_class o ON o.oid = oi.indexrelid
+                    WHERE oi.indrelid = i.indrelid AND oi.indexrelid <> i.indexrelid
+                        AND o.relam = ic.relam
+                        AND oi.indkey::text = i.indkey::text
+                        AND oi.indclass::text = i.indclass::text
+                        AND COALESCE(pg_get_expr(oi.indexprs, oi.indrelid), '') = COALESCE(pg_get_expr(i.indexprs, i.indrelid), '')
+                        AND COALESCE(pg_get_expr(oi.indpred, oi.indrelid), '') = COALESCE(pg_get_expr(i.indpred, i.indrelid), '')
+                        AND ROW(oi.indisprimary, oi.indisunique, i.indexrelid::int8)
+                            > ROW(i.indisprimary, i.indisunique, oi.indexrelid::int8)
+                    ORDER BY oi.indisprimary DESC, oi.indisunique DESC, oi.indexrelid
+                    LIMIT 1
+                ) AS duplicate_of,
+                (
+                    SELECT o.relname::text
+                    FROM pg_index oi
+                    JOIN pg_class o ON o.oid = oi.indexrelid
+                    WHERE oi.indrelid = i.indrelid AND oi.indexrelid <> i.indexrelid
+                        AND am.amname = 'btree' AND o.relam = ic.relam
+                        AND NOT i.indisunique
+                        AND i.indexprs IS NULL AND i.indpred IS NULL AND oi.indpred IS NULL
+                        AND (array_to_string((oi.indkey::int2[])[0:oi.indnkeyatts - 1], ' ') || ' ')
+                            LIKE (i.indkey::text || ' %')
+                        AND oi.indnkeyatts > i.indnkeyatts
+                    ORDER BY o.relname
+                    LIMIT 1
+                ) AS covered_by,
+                CASE WHEN am.amname = 'btree' AND ic.reltuples >= 0 THEN (
+                    SELECT CASE WHEN count(st.avg_width) = count(*) THEN
+                        (greatest(
+                            ic.relpages - 1 - ceil(ic.reltuples * (12 + sum(st.avg_width))
+                                / ((current_setting('block_size')::numeric - 40) * 0.9)),
+                            0
+                        ) * current_setting('block_size')::numeric)::int8
+                    END
+                    FROM unnest(i.indkey) k(attnum)
+                    LEFT JOIN pg_attribute a ON a.attrelid = i.indrelid AND a.attnum = k.attnum
+                    LEFT JOIN pg_stats st ON st.schemaname = n.nspname AND st.tablename = c.relname
+                        AND st.attname = a.attname
+                ) END AS bloat
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_class ic ON ic.oid = i.indexrelid
+            JOIN pg_am am ON am.oid = ic.relam
+            LEFT JOIN pg_stat_all_indexes s ON s.indexrelid = i.indexrelid
+            WHERE i.indrelid = $1
+            ORDER BY ic.relname",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let indexes = rows
+            .into_iter()
+            .map(|row| {
+                Ok(IndexStats {
+                    name: row.try_get("name")?,
+                    definition: row.try_get("definition")?,
+                    size: row.try_get("size")?,
+                    scans: row.try_get("scans")?,
+                    tuples_read: row.try_get("tuples_read")?,
+                    tuples_fetched: row.try_get("tuples_fetched")?,
+                    bloat: row.try_get("bloat")?,
+                    unique: row.try_get("indisunique")?,
+                    primary: row.try_get("indisprimary")?,
+                    valid: row.try_get("indisvalid")?,
+                    constraint: row.try_get("constraint_name")?,
+                    duplicate_of: row.try_get("duplicate_of")?,
+                    covered_by: row.try_get("covered_by")?,
+                })
+            })
+            .collect::<Result<Vec<IndexStats>, sqlx::Error>>()
+            .map_err(|e| e.to_string())?;
+
+        Ok((schema, columns, indexes))
+    }
+
+    pub async fn get_schemas(&self) -> Result<Vec<String>, String> {
+        sqlx::query_scalar(
+            "SELECT nspname::text FROM pg_namespace
+            WHERE nspname <> 'information_schema' AND nspname NOT LIKE 'pg\\_%'
+            ORDER BY nspname <> 'public', nspname",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Reads the tables of a schema with their columns, and the foreign
+    /// keys between them. Partitions are left out in favour of their parent.
+    pub async fn get_er_model(&self, schema: &str) -> Result<ErModel, String> {
+        let rows = sqlx::query(
+            "SELECT c.relname::text AS table_name, a.attname::text AS column_name,
+                format_type(a.atttypid, a.atttypmod) AS data_type,
+                EXISTS (
+                    SELECT 1 FROM pg_constraint con
+                    WHERE con.conrelid = c.oid AND con.contype = 'p' AND a.attnum = ANY (con.conkey)
+                ) AS primary_key,
+                EXISTS (
+                    SELECT 1 FROM pg_constraint con
+                    WHERE con.conrelid = c.oid AND con.contype = 'f' AND a.attnum = ANY (con.conkey)
+                ) AS foreign_key
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+            WHERE n.nspname = $1 AND c.relkind IN ('r', 'p') AND NOT c.relispartition
+            ORDER BY c.relname, a.attnum",
+        )
+        .bind(schema)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut tables: Vec<ErTable> = Vec::new();
+        for row in rows {
+            let table_name: String = row.try_get("table_name").map_err(|e| e.to_string())?;
+            let column = ErColumn {
+                name: row.try_get("column_name").map_err(|e| e.to_string())?,
+                data_type: row.try_get("data_type").map_err(|e| e.to_string())?,
+                primary_key: row.try_get("primary_key").map_err(|e| e.to_string())?,
+                foreign_key: row.try_get("foreign_key").map_err(|e| e.to_string())?,
+            };
+
+            match tables.last_mut() {
+                Some(table) if table.name == table_name => table.columns.push(column),
+                _ => tables.push(ErTable {
+                    name: table_name,
+                    columns: vec![column],
+                }),
+            }
+        }
+
+        let rows = sqlx::query(
+            "SELECT con.conname::text AS name, c.relname::text AS table_name,
+                ARRAY(
+                    SELECT a.attname::text FROM unnest(con.conkey) WITH ORDINALITY k(attnum, position)
+                    JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+                    ORDER BY k.position
+                ) AS columns,
+                f.relname::text AS referenced_table,
+                ARRAY(
+                    SELECT a.attname::text FROM unnest(con.confkey) WITH ORDINALITY k(attnum, position)
+                    JOIN pg_attribute a ON a.attrelid = con.confrelid AND a.attnum = k.attnum
+                    ORDER BY k.position
+                ) AS referenced_columns
+            FROM pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_class f ON f.oid = con.confrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE con.contype = 'f' AND n.nspname = $1 AND f.relnamespace = c.relnamespace
+                AND NOT c.relispartition AND NOT f.relispartition AND con.conparentid = 0
+            ORDER BY c.relname, con.conname",
+        )
+        .bind(schema)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let relations = rows
+            .into_iter()
+            .map(|row| {
+                Ok(ErRelation {
+                    name: row.try_get("name")?,
+                    table: row.try_get("table_name")?,
+                    columns: row.try_get("columns")?,
+                    referenced_table: row.try_get("referenced_table")?,
+                    referenced_columns: row.try_get("referenced_columns")?,
+                })
+            })
+            .collect::<Result<Vec<ErRelation>, sqlx::Error>>()
+            .map_err(|e| e.to_string())?;
+
+        Ok(ErModel { tables, relations })
+    }
+
+    /// Reads the schemas, sequences, tables with their columns, constraints
+    /// and indexes, functions and views compared by the schema diff. Objects
+    /// of extensions, identity sequences and partitions are left out.
+    pub async fn get_schema_objects(&self) -> Result<Vec<SchemaObject>, String> {
+        const SCHEMA_FILTER: &str = "n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'";
+        const NOT_EXTENSION: &str = "NOT EXISTS (SELECT 1 FROM pg_depend d WHERE d.objid = {oid} AND d.deptype = 'e')";
+
+        let not_extension = |oid: &str| NOT_EXTENSION.replace("{oid}", oid);
+        let qualified = |name: &str| format!("quote_ident(n.nspname) || '.' || quote_ident({})", name);
+
+        let mut objects = Vec::new();
+        let read = |kind: SchemaObjectKind, rows: Vec<sqlx::postgres::PgRow>| -> Result<Vec<SchemaObject>, String> {
+            rows.into_iter()
+                .map(|row| {
+                    Ok(SchemaObject {
+                        kind,
+                        table: row.try_get("table_name").ok(),
+                        name: row.try_get("name")?,
+                        definition: row.try_get("definition")?,
+                        column: None,
+                        references: row.try_get("referenced_table").ok().flatten(),
+                    })
+                })
+                .collect::<Result<Vec<SchemaObject>, sqlx::Error>>()
+                .map_err(|e| e.to_string())
+        };
+
+        let schemas = sqlx::query(&format!(
+            "SELECT quote_ident(n.nspname) AS name, '' AS definition FROM pg_namespace n
+            WHERE {} AND {} ORDER BY n.nspname",
+            SCHEMA_FILTER,
+            not_extension("n.oid")
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        objects.extend(read(SchemaObjectKind::Schema, schemas)?);
+
+        let sequences = sqlx::query(&format!(
+            "SELECT {} AS name,
+                format('AS %s INCREMENT BY %s MINVALUE %s MAXVALUE %s START WITH %s CACHE %s %s',
+                    format_type(s.seqtypid, NULL), s.seqincrement, s.seqmin, s.seqmax, s.seqstart, s.seqcache,
+                    CASE WHEN s.seqcycle THEN 'CYCLE' ELSE 'NO CYCLE' END) AS definition
+            FROM pg_sequence s
+            JOIN pg_class c ON c.oid = s.seqrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE {} AND NOT EXISTS (
+                SELECT 1 FROM pg_depend d WHERE d.objid = c.oid AND d.deptype IN ('e', 'i')
+            )
+            ORDER BY 1",
+            qualified("c.relname"),
+            SCHEMA_FILTER
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        objects.extend(read(SchemaObjectKind::Sequence, sequences)?);
+
+        let tables = format!(
+            "c.relkind IN ('r', 'p') AND NOT c.relispartition AND {} AND {}",
+            SCHEMA_FILTER,
+            not_extension("c.oid")
+        );
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} AS table_name, quote_ident(a.attname) AS name,
+                format_type(a.atttypid, a.atttypmod) AS data_type, a.attnotnull,
+                CASE WHEN a.attgenerated = '' THEN pg_get_expr(ad.adbin, ad.adrelid) END AS default_value,
+                CASE WHEN a.attgenerated <> '' THEN pg_get_expr(ad.adbin, ad.adrelid) END AS generated,
+                CASE a.attidentity WHEN 'a' THEN 'ALWAYS' WHEN 'd' THEN 'BY DEFAULT' END AS identity
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+            LEFT JOIN pg_attrdef ad ON ad.adrelid = c.oid AND ad.adnum = a.attnum
+            WHERE {}
+            ORDER BY n.nspname, c.relname, a.attnum",
+            qualified("c.relname"),
+            tables
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let columns = rows
+            .into_iter()
+            .map(|row| {
+                let column = SchemaColumn {
+                    data_type: row.try_get("data_type")?,
+                    not_null: row.try_get("attnotnull")?,
+                    default: row.try_get("default_value")?,
+                    generated: row.try_get("generated")?,
+                    identity: row.try_get("identity")?,
+                };
+
+                Ok(SchemaObject {
+                    kind: SchemaObjectKind::Column,
+                    table: Some(row.try_get("table_name")?),
+                    name: row.try_get("name")?,
+                    definition: column.definition(),
+                    column: Some(column),
+                    references: None,
+                })
+            })
+            .collect::<Result<Vec<SchemaObject>, sqlx::Error>>()
+            .map_err(|e| e.to_string())?;
+
+        let rows = sqlx::query(&format!(
+            "SELECT {} AS name,
+                CASE WHEN c.relkind = 'p' THEN ' PARTITION BY ' || pg_get_partkeydef(c.oid) ELSE '' END AS partition
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE {}
+            ORDER BY n.nspname, c.relname",
+            qualified("c.relname"),
+            tables
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        for row in rows {
+            let name: String = row.try_get("name").map_err(|e| e.to_string())?;
+            let partition: String = row.try_get("partition").map_err(|e| e.to_string())?;
+            let body = columns
+                .iter()
+                .filter(|column| column.table.as_ref() == Some(&name))
+                .map(|column| format!("    {} {}", column.name, column.definition))
+                .collect::<Vec<String>>()
+                .join(",\n");
+
+            objects.push(SchemaObject {
+                kind: SchemaObjectKind::Table,
+                table: None,
+                name,
+                definition: format!("(\n{}\n){}", body, partition),
+                column: None,
+                references: None,
+            });
+        }
+        objects.extend(columns);
+
+        let constraints = sqlx::query(&format!(
+            "SELECT {} AS table_name, quote_ident(con.conname) AS name,
+                pg_get_constraintdef(con.oid) AS definition,
+                quote_ident(rn.nspname) || '.' || quote_ident(rc.relname) AS referenced_table
+            FROM pg_constraint con
+            JOIN pg_class c ON c.oid = con.conrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            LEFT JOIN pg_class rc ON rc.oid = con.confrelid
+            LEFT JOIN pg_namespace rn ON rn.oid = rc.relnamespace
+            WHERE con.contype IN ('p', 'u', 'c', 'f', 'x') AND {}
+            ORDER BY n.nspname, c.relname, con.conname",
+            qualified("c.relname"),
+            tables
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        objects.extend(read(SchemaObjectKind::Constraint, constraints)?);
+
+        let indexes = sqlx::query(&format!(
+            "SELECT {} AS table_name, {} AS name, pg_get_indexdef(i.indexrelid) AS definition
+            FROM pg_index i
+            JOIN pg_class c ON c.oid = i.indrelid
+            JOIN pg_class ic ON ic.oid = i.indexrelid
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE {} AND NOT EXISTS (
+                SELECT 1 FROM pg_constraint con
+                WHERE con.conindid = i.indexrelid AND con.conrelid = i.indrelid AND con.contype IN ('p', 'u', 'x')
+            )
+            ORDER BY n.nspname, c.relname, ic.relname",
+            qualified("c.relname"),
+            qualified("ic.relname"),
+            tables
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        objects.extend(read(SchemaObjectKind::Index, indexes)?);
+
+        let functions = sqlx::query(&format!(
+            "SELECT {} || '(' || pg_get_function_identity_arguments(p.oid) || ')' AS name,
+                pg_get_functiondef(p.oid) AS definition
+            FROM pg_proc p
+            JOIN pg_namespace n ON n.oid = p.pronamespace
+            WHERE p.prokind IN ('f', 'p') AND {} AND {}
+            ORDER BY 1",
+            qualified("p.proname"),
+            SCHEMA_FILTER,
+            not_extension("p.oid")
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        objects.extend(read(SchemaObjectKind::Function, functions)?);
+
+        // Views come in creation order, so views built on others follow them.
+        let views = sqlx::query(&format!(
+            "SELECT {} AS name, pg_get_viewdef(c.oid, true) AS definition
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relkind = 'v' AND {} AND {}
+            ORDER BY c.oid",
+            qualified("c.relname"),
+            SCHEMA_FILTER,
+            not_extension("c.oid")
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+        objects.extend(read(SchemaObjectKind::View, views)?);
+
+        Ok(objects)
+    }
+
+    /// Resolves `table` like `get_indexes`, and returns its quoted qualified
+    /// name, its columns except generated ones, its primary key columns and
+    /// whether it has a `GENERATED ALWAYS` identity column.
+    pub async fn get_table_key(
+        &self,
+        schema: Option<&str>,
+        table: &str,
+    ) -> Result<(String, Vec<String>, Vec<String>, bool), String> {
+        let (oid, name): (sqlx::postgres::types::Oid, String) = sqlx::query_as(
+            "SELECT c.oid, quote_ident(n.nspname) || '.' || quote_ident(c.relname)
+            FROM pg_class c
+            JOIN pg_namespace n ON n.oid = c.relnamespace
+            WHERE c.relname = $2 AND ($1::text IS NULL OR n.nspname = $1) AND c.relkind IN ('r', 'p')
+            ORDER BY n.nspname <> 'public', n.nspname
+            LIMIT 1",
+        )
+        .bind(schema)
+        .bind(table)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Table {} not found", table))?;
+
+        let columns: Vec<(String, bool)> = sqlx::query_as(
+            "SELECT attname::text, attidentity = 'a' FROM pg_attribute
+            WHERE attrelid = $1 AND attnum > 0 AND NOT attisdropped AND attgenerated = ''
+            ORDER BY attnum",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let key: Vec<String> = sqlx::query_scalar(
+            "SELECT a.attname::text
+            FROM pg_constraint con
+            CROSS JOIN unnest(con.conkey) WITH ORDINALITY k(attnum, position)
+            JOIN pg_attribute a ON a.attrelid = con.conrelid AND a.attnum = k.attnum
+            WHERE con.conrelid = $1 AND con.contype = 'p'
+            ORDER BY k.position",
+        )
+        .bind(oid)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if key.is_empty() {
+            return Err(format!("Table {} has no primary key", name));
+        }
+
+        let identity_always = columns.iter().any(|(_, always)| *always);
+        let columns = columns.into_iter().map(|(column, _)| column).collect();
+
+        Ok((name, columns, key, identity_always))
+    }
+
+    /// Opens a `Cursor` over `query`.
+    pub async fn open_cursor(&self, query: &str) -> Result<Cursor, String> {
+        let mut transaction = self.pool.begin().await.map_err(|e| e.to_string())?;
+
+        sqlx::query("SET TRANSACTION READ ONLY")
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| e.to_string())?;
+        sqlx::query(&format!("DECLARE rs_postgres_cursor NO SCROLL CURSOR FOR {}", query))
+            .execute(&mut *transaction)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        Ok(Cursor { transaction })
+    }
+
+    pub async fn get_activity(&self) -> Result<Vec<ActivitySession>, String> {
+        let rows = sqlx::query(
+            "SELECT a.pid,
+                COALESCE(a.usename::text, '') AS usename,
+                COALESCE(a.datname::text, '') AS datname,
+                COALESCE(a.application_name, '') AS application_name,
+                COALESCE(host(a.client_addr), '') AS client_addr,
+                COALESCE(a.backend_type, '') AS backend_type,
+                COALESCE(a.state, '') AS state,
+                COALESCE(a.wait_event_type || ': ' || a.wait_event, '') AS wait_event,
+                COALESCE(a.query, '') AS query,
+                EXTRACT(EPOCH FROM clock_timestamp() - a.query_start)::float8 AS duration,
+                pg_blocking_pids(a.pid) AS blocked_by,
+                COALESCE((
+                    SELECT string_agg(l.mode || ' on ' || l.locktype || COALESCE(' ' || l.relation::regclass::text, ''), ', ')
+                    FROM pg_locks l
+                    WHERE l.pid = a.pid AND NOT l.granted
+                ), '') AS waiting_for
+            FROM pg_stat_activity a
+            WHERE a.pid <> pg_backend_pid()
+            ORDER BY a.pid",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(ActivitySession {
+                    pid: row.try_get("pid")?,
+                    user: row.try_get("usename")?,
+                    database: row.try_get("datname")?,
+                    application: row.try_get("application_name")?,
+                    client_address: row.try_get("client_addr")?,
+                    backend_type: row.try_get("backend_type")?,
+                    state: row.try_get("state")?,
+                    wait_event: row.try_get("wait_event")?,
+                    query: row.try_get("query")?,
+                    duration: row.try_get("duration")?,
+                    blocked_by: row.try_get("blocked_by")?,
+                    waiting_for: row.try_get("waiting_for")?,
+                })
+            })
+            .collect::<Result<Vec<ActivitySession>, sqlx::Error>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Samples `pg_stat_database` (for all databases, or just `database_name`),
+    /// connection states, the background writer, WAL and replication.
+    pub async fn get_server_stats(&self, database_name: Option<&str>) -> Result<ServerStats, String> {
+        let row = sqlx::query(
+            "SELECT COALESCE(sum(xact_commit), 0)::int8 AS xact_commit,
+                COALESCE(sum(xact_rollback), 0)::int8 AS xact_rollback,
+                COALESCE(sum(blks_read), 0)::int8 AS blks_read,
+                COALESCE(sum(blks_hit), 0)::int8 AS blks_hit,
+                COALESCE(sum(tup_returned + tup_fetched), 0)::int8 AS tuples_read,
+                COALESCE(sum(tup_inserted + tup_updated + tup_deleted), 0)::int8 AS tuples_written,
+                COALESCE(sum(deadlocks), 0)::int8 AS deadlocks,
+                COALESCE(sum(temp_files), 0)::int8 AS temp_files,
+                COALESCE(sum(temp_bytes), 0)::int8 AS temp_bytes
+            FROM pg_stat_database
+            WHERE $1::text IS NULL OR datname = $1",
+        )
+        .bind(database_name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut stats = ServerStats {
+            xact_commit: row.try_get("xact_commit").map_err(|e| e.to_string())?,
+            xact_rollback: row.try_get("xact_rollback").map_err(|e| e.to_string())?,
+            blks_read: row.try_get("blks_read").map_err(|e| e.to_string())?,
+            blks_hit: row.try_get("blks_hit").map_err(|e| e.to_string())?,
+            tuples_read: row.try_get("tuples_read").map_err(|e| e.to_string())?,
+            tuples_written: row.try_get("tuples_written").map_err(|e| e.to_string())?,
+            deadlocks: row.try_get("deadlocks").map_err(|e| e.to_string())?,
+            temp_files: row.try_get("temp_files").map_err(|e| e.to_string())?,
+            temp_bytes: row.try_get("temp_bytes").map_err(|e| e.to_string())?,
+            ..Default::default()
+        };
+
+        let row = sqlx::query(
+            "SELECT count(*) AS total,
+                count(*) FILTER (WHERE state = 'active') AS active,
+                count(*) FILTER (WHERE state = 'idle') AS idle,
+                count(*) FILTER (WHERE state LIKE 'idle in transaction%') AS idle_in_transaction
+            FROM pg_stat_activity
+            WHERE backend_type = 'client backend' AND ($1::text IS NULL OR datname = $1)",
+        )
+        .bind(database_name)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let total: i64 = row.try_get("total").map_err(|e| e.to_string())?;
+        stats.connections_active = row.try_get("active").map_err(|e| e.to_string())?;
+        stats.connections_idle = row.try_get("idle").map_err(|e| e.to_string())?;
+        stats.connections_idle_in_transaction =
+            row.try_get("idle_in_transaction").map_err(|e| e.to_string())?;
+        stats.connections_other = total
+            - stats.connections_active
+            - stats.connections_idle
+            - stats.connections_idle_in_transaction;
+
+        // PostgreSQL 17 moved the checkpoint counters to pg_stat_checkpointer
+        // and dropped buffers_backend in favour of pg_stat_io.
+        let has_checkpointer: bool =
+            sqlx::query_scalar("SELECT to_regclass('pg_catalog.pg_stat_checkpointer') IS NOT NULL")
+                .fetch_one(&self.pool)
+                .await
+                .map_err(|e| e.to_string())?;
+        let query = if has_checkpointer {
+            "SELECT (c.num_timed + c.num_requested)::int8 AS checkpoints,
+                (c.buffers_written + b.buffers_clean + (
+                    SELECT COALESCE(sum(writes), 0)
+                    FROM pg_stat_io
+                    WHERE object = 'relation' AND backend_type NOT IN ('checkpointer', 'background writer')
+                ))::int8 AS buffers_written
+            FROM pg_stat_checkpointer c, pg_stat_bgwriter b"
+        } else {
+            "SELECT (checkpoints_timed + checkpoints_req)::int8 AS checkpoints,
+                (buffers_checkpoint + buffers_clean + buffers_backend)::int8 AS buffers_written
+            FROM pg_stat_bgwriter"
+        };
+        let row = sqlx::query(query)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        stats.checkpoints = row.try_get("checkpoints").map_err(|e| e.to_string())?;
+        stats.buffers_written = row.try_get("buffers_written").map_err(|e| e.to_string())?;
+
+        stats.wal_bytes = sqlx::query_scalar("SELECT wal_bytes::float8 FROM pg_stat_wal")
+            .fetch_one(&self.pool)
+            .await
+            .ok();
+
+        stats.in_recovery = sqlx::query_scalar("SELECT pg_is_in_recovery()")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        if stats.in_recovery {
+            stats.replication_lag = sqlx::query_scalar(
+                "SELECT EXTRACT(EPOCH FROM now() - pg_last_xact_replay_timestamp())::float8",
+            )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        } else {
+            let rows = sqlx::query(
+                "SELECT COALESCE(application_name, '') AS application_name,
+                    COALESCE(host(client_addr), '') AS client_addr,
+                    COALESCE(state, '') AS state,
+                    EXTRACT(EPOCH FROM replay_lag)::float8 AS replay_lag,
+                    pg_wal_lsn_diff(pg_current_wal_lsn(), replay_lsn)::float8 AS lag_bytes
+                FROM pg_stat_replication
+                ORDER BY application_name",
+            )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+            stats.replicas = rows
+                .into_iter()
+                .map(|row| {
+                    Ok(ReplicaStats {
+                        name: row.try_get("application_name")?,
+                        client_address: row.try_get("client_addr")?,
+                        state: row.try_get("state")?,
+                        replay_lag: row.try_get("replay_lag")?,
+                        lag_bytes: row.try_get("lag_bytes")?,
+                    })
+                })
+                .collect::<Result<Vec<ReplicaStats>, sqlx::Error>>()
+                .map_err(|e| e.to_string())?;
+
+            stats.replication_lag = stats
+                .replicas
+                .iter()
+                .filter_map(|replica| replica.replay_lag)
+                .reduce(f64::max);
+        }
+
+        Ok(stats)
+    }
+
+    pub async fn get_roles(&self) -> Result<Vec<RoleInfo>, String> {
+        let rows = sqlx::query(
+            "SELECT r.rolname::text AS rolname, r.rolsuper, r.rolinherit, r.rolcreaterole,
+                r.rolcreatedb, r.rolcanlogin, r.rolreplication, r.rolconnlimit,
+                r.rolvaliduntil::text AS rolvaliduntil,
+                ARRAY(
+                    SELECT b.rolname::text
+                    FROM pg_auth_members m
+                    JOIN pg_roles b ON b.oid = m.roleid
+                    WHERE m.member = r.oid
+                    ORDER BY 1
+                ) AS member_of
+            FROM pg_roles r
+            ORDER BY r.rolname",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(RoleInfo {
+                    name: row.try_get("rolname")?,
+                    superuser: row.try_get("rolsuper")?,
+                    inherit: row.try_get("rolinherit")?,
+                    create_role: row.try_get("rolcreaterole")?,
+                    create_db: row.try_get("rolcreatedb")?,
+                    login: row.try_get("rolcanlogin")?,
+                    replication: row.try_get("rolreplication")?,
+                    connection_limit: row.try_get("rolconnlimit")?,
+                    valid_until: row.try_get("rolvaliduntil")?,
+                    member_of: row.try_get("member_of")?,
+                })
+            })
+            .collect::<Result<Vec<RoleInfo>, sqlx::Error>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Lists objects of `kind` in the connected database with the
+    /// privileges `role` holds on them.
+    pub async fn get_privileges(
+        &self,
+        kind: PrivilegeObjectKind,
+        role: &str,
+    ) -> Result<Vec<PrivilegeObject>, String> {
+        let query = match kind {
+            PrivilegeObjectKind::Database => {
+                "SELECT quote_ident(d.datname) AS name,
+                    ARRAY(
+                        SELECT DISTINCT a.privilege_type
+                        FROM aclexplode(COALESCE(d.datacl, acldefault('d', d.datdba))) a
+                        WHERE a.grantee = r.oid
+                    ) AS granted,
+                    ARRAY(SELECT p FROM unnest($2::text[]) p WHERE has_database_privilege(r.oid, d.oid, p)) AS effective
+                FROM pg_database d, (SELECT oid FROM pg_roles WHERE rolname = $1) r
+                WHERE d.datname = current_database()"
+            }
+            PrivilegeObjectKind::Schema => {
+                "SELECT quote_ident(n.nspname) AS name,
+                    ARRAY(
+                        SELECT DISTINCT a.privilege_type
+                        FROM aclexplode(COALESCE(n.nspacl, acldefault('n', n.nspowner))) a
+                        WHERE a.grantee = r.oid
+                    ) AS granted,
+                    ARRAY(SELECT p FROM unnest($2::text[]) p WHERE has_schema_privilege(r.oid, n.oid, p)) AS effective
+                FROM pg_namespace n, (SELECT oid FROM pg_roles WHERE rolname = $1) r
+                WHERE n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'
+                ORDER BY 1"
+            }
+            PrivilegeObjectKind::Table => {
+                "SELECT format('%I.%I', n.nspname, c.relname) AS name,
+                    ARRAY(
+                        SELECT DISTINCT a.privilege_type
+                        FROM aclexplode(COALESCE(c.relacl, acldefault('r', c.relowner))) a
+                        WHERE a.grantee = r.oid
+                    ) AS granted,
+                    ARRAY(SELECT p FROM unnest($2::text[]) p WHERE has_table_privilege(r.oid, c.oid, p)) AS effective
+                FROM pg_class c
+                JOIN pg_namespace n ON n.oid = c.relnamespace,
+                    (SELECT oid FROM pg_roles WHERE rolname = $1) r
+                WHERE c.relkind IN ('r', 'p', 'v', 'm', 'f')
+                    AND n.nspname <> 'information_schema' AND n.nspname NOT LIKE 'pg\\_%'
+                ORDER BY 1"
+            }
+        };
+
+        let privileges: Vec<String> = kind.privileges().iter().map(|p| p.to_string()).collect();
+
+        let rows = sqlx::query(query)
+            .bind(role)
+            .bind(privileges)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        rows.into_iter()
+            .map(|row| {
+                let granted: Vec<String> = row.try_get("granted")?;
+
+                Ok(PrivilegeObject {
+                    name: row.try_get("name")?,
+                    selected: granted.clone(),
+                    granted,
+                    effective: row.try_get("effective")?,
+                })
+            })
+            .collect::<Result<Vec<PrivilegeObject>, sqlx::Error>>()
+            .map_err(|e| e.to_string())
+    }
+
+    /// Cancels the current query of a backend. Returns `false` if the
+    /// server refused, e.g. because the process no longer exists.
+    pub async fn cancel_backend(&self, pid: i32) -> Result<bool, String> {
+        sqlx::query_scalar("SELECT pg_cancel_backend($1)")
+            .bind(pid)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    pub async fn terminate_backend(&self, pid: i32) -> Result<bool, String> {
+        sqlx::query_scalar("SELECT pg_terminate_backend($1)")
+            .bind(pid)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Runs one or more statements without returning rows.
+    pub async fn execute(&self, query: &str) -> Result<(), String> {
+        sqlx::raw_sql(query)
+            .execute(&self.pool)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Runs a maintenance command such as `VACUUM` on a connection of its
+    /// own, passing its backend pid to `started` so the command can be
+    /// followed with `get_maintenance_progress` and cancelled.
+    pub async fn run_maintenance(&self, command: &str, started: impl FnOnce(i32)) -> Result<(), String> {
+        let mut connection = self.pool.acquire().await.map_err(|e| e.to_string())?;
+
+        let pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+            .fetch_one(&mut *connection)
+            .await
+            .map_err(|e| e.to_string())?;
+        started(pid);
+
+        connection
+            .execute(sqlx::raw_sql(command))
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Reads the progress views of `VACUUM`, `CLUSTER`/`VACUUM FULL` and
+    /// `CREATE INDEX`/`REINDEX` for the backend `pid`.
+    pub async fn get_maintenance_progress(&self, pid: i32) -> Result<Option<MaintenanceProgress>, String> {
+        let row: Option<(String, String, i64, i64)> = sqlx::query_as(
+            "SELECT relid::regclass::text, phase,
+                CASE phase WHEN 'vacuuming heap' THEN heap_blks_vacuumed ELSE heap_blks_scanned END,
+                heap_blks_total
+            FROM pg_stat_progress_vacuum WHERE pid = $1
+            UNION ALL
+            SELECT p.relid::regclass::text, p.phase,
+                CASE WHEN p.heap_blks_total > 0 THEN p.heap_blks_scanned ELSE p.heap_tuples_scanned END,
+                CASE WHEN p.heap_blks_total > 0 THEN p.heap_blks_total ELSE greatest(c.reltuples, 0)::bigint END
+            FROM pg_stat_progress_cluster p
+            LEFT JOIN pg_class c ON c.oid = p.relid
+            WHERE p.pid = $1
+            UNION ALL
+            SELECT relid::regclass::text, phase,
+                CASE WHEN blocks_total > 0 THEN blocks_done ELSE tuples_done END,
+                CASE WHEN blocks_total > 0 THEN blocks_total ELSE tuples_total END
+            FROM pg_stat_progress_create_index WHERE pid = $1
+            LIMIT 1",
+        )
+        .bind(pid)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(row.map(|(relation, phase, done, total)| MaintenanceProgress {
+            relation,
+            phase,
+            done,
+            total,
+        }))
+    }
+
+    pub async fn has_extension(&self, name: &str) -> Result<bool, String> {
+        sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM pg_extension WHERE extname = $1)")
+            .bind(name)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())
+    }
+
+    /// Reads `pg_stat_statements`, limited to the connected database unless
+    /// `all_databases` is set.
+    pub async fn get_statements(&self, all_databases: bool) -> Result<Vec<StatementStats>, String> {
+        let version: i32 = sqlx::query_scalar("SELECT current_setting('server_version_num')::int")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        // PostgreSQL 13 split planning and execution time.
+        let time = if version >= 130000 { "exec_time" } else { "time" };
+
+        let rows = sqlx::query(&format!(
+            "SELECT COALESCE(r.rolname::text, '') AS rolname,
+                COALESCE(d.datname::text, '') AS datname,
+                s.query,
+                s.calls,
+                s.total_{time} AS total_time,
+                s.mean_{time} AS mean_time,
+                s.stddev_{time} AS stddev_time,
+                s.rows,
+                s.shared_blks_hit,
+                s.shared_blks_read,
+                s.temp_blks_read + s.temp_blks_written AS temp_blks
+            FROM pg_stat_statements s
+            LEFT JOIN pg_roles r ON r.oid = s.userid
+            LEFT JOIN pg_database d ON d.oid = s.dbid
+            WHERE $1 OR d.datname = current_database()
+            ORDER BY s.total_{time} DESC"
+        ))
+        .bind(all_databases)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        rows.into_iter()
+            .map(|row| {
+                Ok(StatementStats {
+                    user: row.try_get("rolname")?,
+                    database: row.try_get("datname")?,
+                    query: row.try_get("query")?,
+                    calls: row.try_get("calls")?,
+                    total_time: row.try_get("total_time")?,
+                    mean_time: row.try_get("mean_time")?,
+                    stddev_time: row.try_get("stddev_time")?,
+                    rows: row.try_get("rows")?,
+                    shared_blks_hit: row.try_get("shared_blks_hit")?,
+                    shared_blks_read: row.try_get("shared_blks_read")?,
+                    temp_blks: row.try_get("temp_blks")?,
+                })
+            })
+            .collect::<Result<Vec<StatementStats>, sqlx::Error>>()
+            .map_err(|e| e.to_string())
+    }
+
     pub async fn execute_query(
         &self,
         query: &str,
-    ) -> Result<IndexMap<String, Vec<ValueType>>, String> {
+    ) -> Result<IndexMap<String, Vec<ValueType>>, QueryError> {
         let rows = sqlx::query(query)
             .fetch_all(&self.pool)
             .await
-            .map_err(|e| e.to_string())?;
+            .map_err(query_error)?;
 
-        let mut results = indexmap::IndexMap::new();
+        Ok(query_results(rows))
+    }
 
-        for row in rows {
-            for column in row.columns() {
-                let column_name = column.name().to_string();
-                let type_info = column.type_info();
-                let type_name = type_info.name();
-
-                let value = match type_name {
-                    // Numeric types
-                    "INT2" | "SMALLINT" => row
-                        .try_get::<i16, _>(column_name.as_str())
-                        .map(|v| ValueType::Int(v as i32))
-                        .unwrap_or(ValueType::Null),
-                    "INT4" | "INTEGER" => row
-                        .try_get::<i32, _>(column_name.as_str())
-                        .map(ValueType::Int)
-                        .unwrap_or(ValueType::Null),
-                    "INT8" | "BIGINT" => row
-                        .try_get::<i64, _>(column_name.as_str())
-                        .map(ValueType::BigInt)
-                        .unwrap_or(ValueType::Null),
-                    "FLOAT4" | "REAL" => row
-                        .try_get::<f32, _>(column_name.as_str())
-                        .map(|v| ValueType::Float(v as f64))
-                        .unwrap_or(ValueType::Null),
-                    "FLOAT8" | "DOUBLE PRECISION" => row
-                        .try_get::<f64, _>(column_name.as_str())
-                        .map(ValueType::Float)
-                        .unwrap_or(ValueType::Null),
-                    "MONEY" => row
-                        .try_get::<PgMoney, _>(column_name.as_str())
-                        .map(|v| ValueType::BigInt(v.0))
-                        .unwrap_or(ValueType::Null),
-
-                    // Text types
-                    "CHAR" | "VARCHAR" | "TEXT" | "NAME" => row
-                        .try_get::<String, _>(column_name.as_str())
-                        .map(ValueType::Text)
-                        .unwrap_or(ValueType::Null),
-                    "BPCHAR" => row
-                        .try_get::<String, _>(column_name.as_str())
-                        .map(|s| ValueType::Text(s.trim_end().to_string()))
-                        .unwrap_or(ValueType::Null),
-
-                    // Boolean
-                    "BOOL" | "BOOLEAN" => row
-                        .try_get::<bool, _>(column_name.as_str())
-                        .map(ValueType::Bool)
-                        .unwrap_or(ValueType::Null),
-
-                    // Binary
-                    "BYTEA" => row
-                        .try_get::<Vec<u8>, _>(column_name.as_str())
-                        .map(|bytes| {
-                            String::from_utf8(bytes)
-                                .map(ValueType::Text)
-                                .unwrap_or_else(|e| ValueType::Bytea(e.into_bytes()))
-                        })
-                        .unwrap_or(ValueType::Null),
-
-                    // Date/Time types
-                    "TIMESTAMP" | "TIMESTAMPTZ" => row
-                        .try_get::<DateTime<Utc>, _>(column_name.as_str())
-                        .map(|v| ValueType::Text(v.to_rfc3339()))
-                        .unwrap_or(ValueType::Null),
-                    "DATE" => row
-                        .try_get::<NaiveDate, _>(column_name.as_str())
-                        .map(|v| ValueType::Text(v.to_string()))
-                        .unwrap_or(ValueType::Null),
-                    "TIME" | "TIMETZ" => row
-                        .try_get::<NaiveTime, _>(column_name.as_str())
-                        .map(|v| ValueType::Text(v.to_string()))
-                        .unwrap_or(ValueType::Null),
-                    "INTERVAL" => row
-                        .try_get::<PgInterval, _>(column_name.as_str())
-                        .map(|_| ValueType::Text("interval".to_string())) // Simplified representation
-                        .unwrap_or(ValueType::Null),
-
-                    // JSON types
-                    "JSON" | "JSONB" => row
-                        .try_get::<serde_json::Value, _>(column_name.as_str())
-                        .map(|v| ValueType::Text(v.to_string()))
-                        .unwrap_or(ValueType::Null),
-
-                    // Array types (basic handling)
-                    typ if typ.starts_with("_") || typ.ends_with("[]") => row
-                        .try_get::<Vec<String>, _>(column_name.as_str())
-                        .map(|v| ValueType::Array(v.into_iter().map(ValueType::Text).collect()))
-                        .unwrap_or(ValueType::Null),
-
-                    // Unknown/special types that need special handling
-                    "ACLITEM" => ValueType::Text("[ACL permissions]".to_string()),
-
-                    // Unknown types
-                    _ => ValueType::Unknown(type_name.to_string()),
-                };
+    /// Runs `query` in a read-only transaction that is always rolled back.
+    pub async fn execute_query_read_only(
+        &self,
+        query: &str,
+    ) -> Result<IndexMap<String, Vec<ValueType>>, QueryError> {
+        let mut transaction = self.pool.begin().await.map_err(query_error)?;
+        sqlx::query("SET TRANSACTION READ ONLY")
+            .execute(&mut *transaction)
+            .await
+            .map_err(query_error)?;
+        let rows = sqlx::query(query)
+            .fetch_all(&mut *transaction)
+            .await
+            .map_err(query_error)?;
+        transaction.rollback().await.map_err(query_error)?;
+
+        Ok(query_results(rows))
+    }
+}
 
-                if results.get(&column_name).is_none() {
-                    results.insert(column_name.clone(), Vec::new());
+/// Splits the key columns, as written with their collations, operator
+/// classes and ordering, and the `INCLUDE` columns out of an index
+/// definition printed by `pg_get_indexdef`.
+fn index_columns(definition: &str) -> (String, String) {
+    let Some(rest) = definition
+        .find(" USING ")
+        .and_then(|start| definition[start..].find('(').map(|open| &definition[start + open..]))
+    else {
+        return (String::new(), String::new());
+    };
+
+    let (columns, rest) = parenthesized(rest);
+    let include = rest
+        .strip_prefix(" INCLUDE ")
+        .map(|rest| parenthesized(rest).0)
+        .unwrap_or_default();
+
+    (columns, include)
+}
+
+/// The contents of the parentheses `text` starts with, and what follows.
+fn parenthesized(text: &str) -> (String, &str) {
+    let mut depth = 0;
+    let mut quote = None;
+
+    for (i, c) in text.char_indices() {
+        match (quote, c) {
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '\'' | '"') => quote = Some(c),
+            (None, '(') => depth += 1,
+            (None, ')') => {
+                depth -= 1;
+                if depth == 0 {
+                    return (text[1..i].to_string(), &text[i + 1..]);
                 }
-                results.get_mut(&column_name).unwrap().push(value);
             }
+            _ => {}
         }
+    }
+
+    (String::new(), "")
+}
+
+/// Collects `rows` into values by column.
+fn query_results(rows: Vec<sqlx::postgres::PgRow>) -> IndexMap<String, Vec<ValueType>> {
+    let mut results = indexmap::IndexMap::new();
+
+    for row in rows {
+        for column in row.columns() {
+            let column_name = column.name().to_string();
+            let type_info = column.type_info();
+            let type_name = type_info.name();
+
+            let value = match type_name {
+                // Numeric types
+                "INT2" | "SMALLINT" => row
+                    .try_get::<i16, _>(column_name.as_str())
+                    .map(|v| ValueType::Int(v as i32))
+                    .unwrap_or(ValueType::Null),
+                "INT4" | "INTEGER" => row
+                    .try_get::<i32, _>(column_name.as_str())
+                    .map(ValueType::Int)
+                    .unwrap_or(ValueType::Null),
+                "INT8" | "BIGINT" => row
+                    .try_get::<i64, _>(column_name.as_str())
+                    .map(ValueType::BigInt)
+                    .unwrap_or(ValueType::Null),
+                "FLOAT4" | "REAL" => row
+                    .try_get::<f32, _>(column_name.as_str())
+                    .map(|v| ValueType::Float(v as f64))
+                    .unwrap_or(ValueType::Null),
+                "FLOAT8" | "DOUBLE PRECISION" => row
+                    .try_get::<f64, _>(column_name.as_str())
+                    .map(ValueType::Float)
+                    .unwrap_or(ValueType::Null),
+                "MONEY" => row
+                    .try_get::<PgMoney, _>(column_name.as_str())
+                    .map(|v| ValueType::BigInt(v.0))
+                    .unwrap_or(ValueType::Null),
+
+                // Text types
+                "CHAR" | "VARCHAR" | "TEXT" | "NAME" => row
+                    .try_get::<String, _>(column_name.as_str())
+                    .map(ValueType::Text)
+                    .unwrap_or(ValueType::Null),
+                "BPCHAR" => row
+                    .try_get::<String, _>(column_name.as_str())
+                    .map(|s| ValueType::Text(s.trim_end().to_string()))
+                    .unwrap_or(ValueType::Null),
+
+                // Boolean
+                "BOOL" | "BOOLEAN" => row
+                    .try_get::<bool, _>(column_name.as_str())
+                    .map(ValueType::Bool)
+                    .unwrap_or(ValueType::Null),
+
+                // Binary
+                "BYTEA" => row
+                    .try_get::<Vec<u8>, _>(column_name.as_str())
+                    .map(|bytes| {
+                        String::from_utf8(bytes)
+                            .map(ValueType::Text)
+                            .unwrap_or_else(|e| ValueType::Bytea(e.into_bytes()))
+                    })
+                    .unwrap_or(ValueType::Null),
+
+                // Date/Time types
+                "TIMESTAMP" | "TIMESTAMPTZ" => row
+                    .try_get::<DateTime<Utc>, _>(column_name.as_str())
+                    .map(|v| ValueType::Text(v.to_rfc3339()))
+                    .unwrap_or(ValueType::Null),
+                "DATE" => row
+                    .try_get::<NaiveDate, _>(column_name.as_str())
+                    .map(|v| ValueType::Text(v.to_string()))
+                    .unwrap_or(ValueType::Null),
+                "TIME" | "TIMETZ" => row
+                    .try_get::<NaiveTime, _>(column_name.as_str())
+                    .map(|v| ValueType::Text(v.to_string()))
+                    .unwrap_or(ValueType::Null),
+                "INTERVAL" => row
+                    .try_get::<PgInterval, _>(column_name.as_str())
+                    .map(|_| ValueType::Text("interval".to_string())) // Simplified representation
+                    .unwrap_or(ValueType::Null),
+
+                // JSON types
+                "JSON" | "JSONB" => row
+                    .try_get::<serde_json::Value, _>(column_name.as_str())
+                    .map(|v| ValueType::Text(v.to_string()))
+                    .unwrap_or(ValueType::Null),
+
+                // Array types (basic handling)
+                typ if typ.starts_with("_") || typ.ends_with("[]") => row
+                    .try_get::<Vec<String>, _>(column_name.as_str())
+                    .map(|v| ValueType::Array(v.into_iter().map(ValueType::Text).collect()))
+                    .unwrap_or(ValueType::Null),
+
+                // Unknown/special types that need special handling
+                "ACLITEM" => ValueType::Text("[ACL permissions]".to_string()),
+
+                // Unknown types
+                _ => ValueType::Unknown(type_name.to_string()),
+            };
+
+            if results.get(&column_name).is_none() {
+                results.insert(column_name.clone(), Vec::new());
+            }
+            results.get_mut(&column_name).unwrap().push(value);
+        }
+    }
+
+    results
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use log::Log;
+
+    /// Counts the records that reach the wrapped logger.
+    struct CountingLogger(Mutex<usize>);
+
+    impl Log for CountingLogger {
+        fn enabled(&self, _: &log::Metadata) -> bool {
+            true
+        }
+
+        fn log(&self, _: &log::Record) {
+            *self.0.lock().unwrap() += 1;
+        }
+
+        fn flush(&self) {}
+    }
+
+    fn notice(logger: &NoticeLogger<CountingLogger>, message: &str) {
+        logger.log(
+            &log::Record::builder()
+                .target(NOTICE_TARGET)
+                .level(log::Level::Warn)
+                .args(format_args!("{message}"))
+                .build(),
+        );
+    }
+
+    #[tokio::test]
+    async fn passes_notices_to_sink() {
+        let logger = NoticeLogger {
+            inner: CountingLogger(Mutex::new(0)),
+        };
+        let notices = Arc::new(Mutex::new(Vec::new()));
+        let sink = notices.clone();
+
+        with_notices(Arc::new(move |notice| sink.lock().unwrap().push(notice)), async {
+            notice(&logger, "inside");
+        })
+        .await;
+        notice(&logger, "outside");
+
+        let notices = notices.lock().unwrap();
+        assert_eq!(notices.len(), 1);
+        assert_eq!((notices[0].severity, notices[0].message.as_str()), ("WARNING", "inside"));
+        assert_eq!(*logger.inner.0.lock().unwrap(), 1);
+    }
 
-        Ok(results)
+    #[test]
+    fn reads_index_columns() {
+        assert_eq!(
+            index_columns("CREATE INDEX t_idx ON public.t USING btree (name text_pattern_ops DESC, lower(\"a)b\"))"),
+            (String::from("name text_pattern_ops DESC, lower(\"a)b\")"), String::new())
+        );
+        assert_eq!(
+            index_columns("CREATE UNIQUE INDEX t_idx ON public.t USING btree (a) INCLUDE (b, c) WHERE a > ')'"),
+            (String::from("a"), String::from("b, c"))
+        );
     }
 }
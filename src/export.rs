@@ -0,0 +1,114 @@
+use crate::data::structs::ValueType;
+
+use indexmap::IndexMap;
+use std::io::Write;
+
+type QueryOutput = IndexMap<String, Vec<ValueType>>;
+
+fn row_count(data: &QueryOutput) -> usize {
+    data.values().next().map(|column| column.len()).unwrap_or(0)
+}
+
+fn cell(column: &[ValueType], row: usize) -> String {
+    column.get(row).map(|value| value.to_string()).unwrap_or_default()
+}
+
+pub fn write_csv(data: &QueryOutput, writer: impl Write) -> Result<(), String> {
+    let mut wtr = csv::Writer::from_writer(writer);
+
+    let headers: Vec<&str> = data.keys().map(|k| k.as_str()).collect();
+
+    wtr.write_record(&headers).map_err(|e| e.to_string())?;
+
+    for row in 0..row_count(data) {
+        let record: Vec<String> = data.values().map(|column| cell(column, row)).collect();
+
+        wtr.write_record(&record).map_err(|e| e.to_string())?;
+    }
+
+    wtr.flush().map_err(|e| e.to_string())
+}
+
+fn to_json_value(value: &ValueType) -> serde_json::Value {
+    match value {
+        ValueType::Null => serde_json::Value::Null,
+        ValueType::Int(int) => serde_json::Value::from(*int),
+        ValueType::BigInt(big_int) => serde_json::Value::from(*big_int),
+        ValueType::Float(float) => serde_json::Value::from(*float),
+        ValueType::Bool(bool) => serde_json::Value::from(*bool),
+        ValueType::Array(items) => items.iter().map(to_json_value).collect(),
+        ValueType::Text(_) | ValueType::Bytea(_) | ValueType::Unknown(_) => {
+            serde_json::Value::from(value.to_string())
+        }
+    }
+}
+
+/// Writes rows as a JSON array of objects keyed by column name.
+pub fn write_json(data: &QueryOutput, mut writer: impl Write) -> Result<(), String> {
+    let rows: Vec<serde_json::Map<String, serde_json::Value>> = (0..row_count(data))
+        .map(|row| {
+            data.iter()
+                .map(|(name, column)| {
+                    let value = column.get(row).map(to_json_value).unwrap_or_default();
+                    (name.clone(), value)
+                })
+                .collect()
+        })
+        .collect();
+
+    serde_json::to_writer_pretty(&mut writer, &rows).map_err(|e| e.to_string())?;
+    writeln!(writer).map_err(|e| e.to_string())
+}
+
+/// Writes an aligned text table in the style of psql.
+pub fn write_table(data: &QueryOutput, mut writer: impl Write) -> Result<(), String> {
+    let rows: Vec<Vec<String>> = (0..row_count(data))
+        .map(|row| data.values().map(|column| cell(column, row)).collect())
+        .collect();
+
+    let widths: Vec<usize> = data
+        .keys()
+        .enumerate()
+        .map(|(idx, name)| {
+            rows.iter()
+                .map(|row| row[idx].chars().count())
+                .chain([name.chars().count()])
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    let format_row = |values: Vec<&str>| {
+        values
+            .iter()
+            .zip(&widths)
+            .map(|(value, width)| format!(" {:<width$} ", value, width = width))
+            .collect::<Vec<String>>()
+            .join("|")
+    };
+
+    let mut output = String::new();
+    output.push_str(&format_row(data.keys().map(|k| k.as_str()).collect()));
+    output.push('\n');
+    output.push_str(
+        &widths
+            .iter()
+            .map(|width| "-".repeat(width + 2))
+            .collect::<Vec<String>>()
+            .join("+"),
+    );
+    output.push('\n');
+
+    for row in rows.iter() {
+        output.push_str(&format_row(row.iter().map(|value| value.as_str()).collect()));
+        output.push('\n');
+    }
+
+    output.push_str(&format!(
+        "({} row{})\n",
+        rows.len(),
+        if rows.len() == 1 { "" } else { "s" }
+    ));
+
+    writer.write_all(output.as_bytes()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,586 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+use crate::database;
+
+use egui::{
+    Align, Button, Color32, DragValue, Id, Label, Layout, Modal, RichText, ScrollArea, Spinner,
+    TextEdit, Ui,
+};
+use egui_extras::{Column, TableBuilder};
+use log::error;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const QUERY_PREVIEW_LENGTH: usize = 120;
+
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+
+    match total {
+        0..60 => format!("{:.1}s", seconds.max(0.0)),
+        60..3600 => format!("{}m {:02}s", total / 60, total % 60),
+        _ => format!("{}h {:02}m", total / 3600, total % 3600 / 60),
+    }
+}
+
+fn query_preview(query: &str) -> String {
+    let query = query.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    if query.chars().count() > QUERY_PREVIEW_LENGTH {
+        format!("{}...", query.chars().take(QUERY_PREVIEW_LENGTH).collect::<String>())
+    } else {
+        query
+    }
+}
+
+fn state_color(session: &structs::ActivitySession) -> Option<Color32> {
+    if !session.blocked_by.is_empty() {
+        return Some(Color32::RED);
+    }
+
+    match session.state.as_str() {
+        "active" => Some(Color32::from_rgb(80, 170, 90)),
+        "idle in transaction" | "idle in transaction (aborted)" => {
+            Some(Color32::from_rgb(232, 146, 60))
+        }
+        _ => None,
+    }
+}
+
+fn session_matches(session: &structs::ActivitySession, filter: &str) -> bool {
+    filter.is_empty()
+        || session.pid.to_string().contains(filter)
+        || [
+            &session.user,
+            &session.database,
+            &session.application,
+            &session.client_address,
+            &session.state,
+            &session.wait_event,
+            &session.query,
+        ]
+        .iter()
+        .any(|text| text.to_lowercase().contains(filter))
+}
+
+fn compare_sessions(
+    a: &structs::ActivitySession,
+    b: &structs::ActivitySession,
+    column: structs::ActivityColumn,
+) -> Ordering {
+    let text = |a: &str, b: &str| a.to_lowercase().cmp(&b.to_lowercase());
+
+    match column {
+        structs::ActivityColumn::Pid => a.pid.cmp(&b.pid),
+        structs::ActivityColumn::User => text(&a.user, &b.user),
+        structs::ActivityColumn::Database => text(&a.database, &b.database),
+        structs::ActivityColumn::Application => text(&a.application, &b.application),
+        structs::ActivityColumn::ClientAddress => text(&a.client_address, &b.client_address),
+        structs::ActivityColumn::State => text(&a.state, &b.state),
+        structs::ActivityColumn::WaitEvent => text(&a.wait_event, &b.wait_event),
+        structs::ActivityColumn::Duration => a
+            .duration
+            .unwrap_or(-1.0)
+            .partial_cmp(&b.duration.unwrap_or(-1.0))
+            .unwrap_or(Ordering::Equal),
+        structs::ActivityColumn::Query => text(&a.query, &b.query),
+    }
+    .then(a.pid.cmp(&b.pid))
+}
+
+/// Draws `pid` and, indented below it, every session waiting for it.
+fn show_lock_node(
+    ui: &mut Ui,
+    pid: i32,
+    sessions: &[structs::ActivitySession],
+    visited: &mut HashSet<i32>,
+    selected: &mut Vec<i32>,
+) {
+    if !visited.insert(pid) {
+        return;
+    }
+
+    ui.horizontal(|ui| {
+        let mut is_selected = selected.contains(&pid);
+
+        match sessions.iter().find(|session| session.pid == pid) {
+            Some(session) => {
+                if ui.checkbox(&mut is_selected, pid.to_string()).changed() {
+                    if is_selected {
+                        selected.push(pid);
+                    } else {
+                        selected.retain(|selected_pid| *selected_pid != pid);
+                    }
+                }
+
+                let mut state = RichText::new(&session.state);
+                if let Some(color) = state_color(session) {
+                    state = state.color(color);
+                }
+
+                ui.label(format!("{}@{}", session.user, session.database));
+                ui.label(state);
+                if let Some(duration) = session.duration {
+                    ui.label(format_duration(duration));
+                }
+                if !session.waiting_for.is_empty() {
+                    ui.label(RichText::new(&session.waiting_for).color(Color32::from_rgb(232, 146, 60)));
+                }
+                ui.add(
+                    Label::new(RichText::new(query_preview(&session.query)).monospace())
+                        .wrap_mode(egui::TextWrapMode::Truncate),
+                );
+            }
+            None => {
+                ui.label(pid.to_string());
+            }
+        }
+    });
+
+    let waiting: Vec<i32> = sessions
+        .iter()
+        .filter(|session| session.blocked_by.contains(&pid))
+        .map(|session| session.pid)
+        .collect();
+
+    if !waiting.is_empty() {
+        ui.indent(("lock_node", pid), |ui| {
+            for waiting_pid in waiting {
+                show_lock_node(ui, waiting_pid, sessions, visited, selected);
+            }
+        });
+    }
+}
+
+impl Main<'_> {
+    /// Opens the activity page of `server`, or switches to it if it is
+    /// already open.
+    pub(super) fn open_activity_page(&mut self, server: &structs::Server) {
+        if let Some(idx) = self.pages.pages.iter().position(|page| {
+            matches!(&page.page_type, structs::PageType::Activity(activity_page) if activity_page.server_id == server.id)
+        }) {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let database = server.connect(&server.service_database);
+
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.activity(), server.alias),
+            page_type: structs::PageType::Activity(structs::ActivityPage::new(server.id, database)),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    async fn fetch_activity(
+        database: database::Database,
+        snapshot: Arc<Mutex<structs::ActivitySnapshot>>,
+    ) {
+        let result = database.get_activity().await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok(sessions) => {
+                snapshot.sessions = sessions;
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error loading server activity: {}", e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+        snapshot.refreshed_at = Some(Instant::now());
+    }
+
+    async fn signal_backends(
+        database: database::Database,
+        action: structs::BackendAction,
+        pids: Vec<i32>,
+        snapshot: Arc<Mutex<structs::ActivitySnapshot>>,
+        trans: translates::Translator,
+    ) {
+        let mut failed = Vec::new();
+
+        for pid in pids {
+            let result = match action {
+                structs::BackendAction::Cancel => database.cancel_backend(pid).await,
+                structs::BackendAction::Terminate => database.terminate_backend(pid).await,
+            };
+
+            match result {
+                Ok(true) => {}
+                Ok(false) => failed.push(pid.to_string()),
+                Err(e) => {
+                    error!("Error signalling backend {}: {}", pid, e);
+                    failed.push(format!("{} ({})", pid, e));
+                }
+            }
+        }
+
+        Self::fetch_activity(database, snapshot.clone()).await;
+
+        if !failed.is_empty() {
+            snapshot.lock().unwrap().error = Some(trans.backends_not_signalled(failed.join(", ")));
+        }
+    }
+
+    fn refresh_activity(runtime: &tokio::runtime::Runtime, activity_page: &structs::ActivityPage) {
+        activity_page.snapshot.lock().unwrap().loading = true;
+
+        let database = activity_page.database.clone();
+        let snapshot = activity_page.snapshot.clone();
+
+        runtime.spawn(async move {
+            Self::fetch_activity(database, snapshot).await;
+        });
+    }
+
+    pub(super) fn update_activity_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::Activity(activity_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = activity_page.snapshot.lock().unwrap().clone();
+        let interval = Duration::from_secs(activity_page.refresh_interval as u64);
+
+        let mut refresh = match snapshot.refreshed_at {
+            None => true,
+            Some(refreshed_at) => activity_page.auto_refresh && refreshed_at.elapsed() >= interval,
+        };
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!snapshot.loading, Button::new(self.trans.refresh())).clicked() {
+                refresh = true;
+            }
+            if snapshot.loading {
+                ui.add(Spinner::new());
+            }
+
+            ui.separator();
+
+            ui.checkbox(&mut activity_page.auto_refresh, self.trans.auto_refresh());
+            ui.add_enabled(
+                activity_page.auto_refresh,
+                DragValue::new(&mut activity_page.refresh_interval).range(1..=3600),
+            );
+
+            ui.separator();
+
+            ui.checkbox(&mut activity_page.hide_idle, self.trans.hide_idle());
+            ui.checkbox(&mut activity_page.show_background, self.trans.background_processes());
+        });
+
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut activity_page.filter)
+                    .hint_text(self.trans.search())
+                    .background_color(self.config.settings.theme.text_input_color()),
+            );
+
+            ui.separator();
+
+            if ui.selectable_label(!activity_page.show_lock_tree, self.trans.sessions()).clicked() {
+                activity_page.show_lock_tree = false;
+            }
+            if ui.selectable_label(activity_page.show_lock_tree, self.trans.lock_waits()).clicked() {
+                activity_page.show_lock_tree = true;
+            }
+
+            ui.separator();
+
+            let has_selection = !activity_page.selected.is_empty();
+            let mut action = None;
+
+            if ui.add_enabled(has_selection, Button::new(self.trans.cancel_query())).clicked() {
+                action = Some(structs::BackendAction::Cancel);
+            }
+            if ui.add_enabled(has_selection, Button::new(self.trans.terminate())).clicked() {
+                action = Some(structs::BackendAction::Terminate);
+            }
+
+            if let Some(action) = action {
+                let mut pids = activity_page.selected.clone();
+                pids.sort();
+
+                self.backend_action_window = structs::BackendActionWindow {
+                    show: true,
+                    action: Some(action),
+                    pids,
+                    page_index: Some(page_index),
+                };
+            }
+        });
+
+        if refresh && !snapshot.loading {
+            Self::refresh_activity(&self.runtime, activity_page);
+        }
+        if activity_page.auto_refresh
+            && let Some(refreshed_at) = snapshot.refreshed_at
+        {
+            ui.ctx().request_repaint_after(interval.saturating_sub(refreshed_at.elapsed()));
+        }
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        ui.separator();
+
+        let filter = activity_page.filter.trim().to_lowercase();
+        let mut sessions: Vec<structs::ActivitySession> = snapshot
+            .sessions
+            .into_iter()
+            .filter(|session| activity_page.show_background || session.backend_type == "client backend")
+            .filter(|session| !activity_page.hide_idle || session.state != "idle")
+            .collect();
+
+        activity_page
+            .selected
+            .retain(|pid| sessions.iter().any(|session| session.pid == *pid));
+
+        if activity_page.show_lock_tree {
+            let blocked: Vec<&structs::ActivitySession> = sessions
+                .iter()
+                .filter(|session| !session.blocked_by.is_empty())
+                .collect();
+
+            if blocked.is_empty() {
+                ui.label(self.trans.no_lock_waits());
+                return;
+            }
+
+            let mut roots: Vec<i32> = blocked
+                .iter()
+                .flat_map(|session| session.blocked_by.iter().copied())
+                .filter(|pid| {
+                    sessions
+                        .iter()
+                        .find(|session| session.pid == *pid)
+                        .is_none_or(|session| session.blocked_by.is_empty())
+                })
+                .collect();
+            roots.sort();
+            roots.dedup();
+
+            // Sessions in a cycle have no root; they are drawn on their own.
+            roots.extend(blocked.iter().map(|session| session.pid));
+
+            let mut visited = HashSet::new();
+            for pid in roots {
+                show_lock_node(ui, pid, &sessions, &mut visited, &mut activity_page.selected);
+            }
+
+            return;
+        }
+
+        sessions.retain(|session| session_matches(session, &filter));
+        sessions.sort_by(|a, b| {
+            let ordering = compare_sessions(a, b, activity_page.sort_column);
+            if activity_page.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let columns = [
+            (structs::ActivityColumn::Pid, String::from("PID")),
+            (structs::ActivityColumn::User, self.trans.user()),
+            (structs::ActivityColumn::Database, self.trans.database()),
+            (structs::ActivityColumn::Application, self.trans.application()),
+            (structs::ActivityColumn::ClientAddress, self.trans.client()),
+            (structs::ActivityColumn::State, self.trans.state()),
+            (structs::ActivityColumn::WaitEvent, self.trans.wait_event()),
+            (structs::ActivityColumn::Duration, self.trans.duration()),
+        ];
+
+        let available_height = ui.available_height();
+        let available_width = ui.available_width();
+
+        ScrollArea::horizontal()
+            .auto_shrink([false, false])
+            .max_width(available_width)
+            .max_height(available_height)
+            .show(ui, |ui| {
+                TableBuilder::new(ui)
+                    .id_salt(("activity_table", page_index))
+                    .striped(true)
+                    .auto_shrink([false, false])
+                    .column(Column::auto())
+                    .columns(Column::auto().resizable(true), columns.len() + 1)
+                    .column(Column::remainder().resizable(true))
+                    .header(16.0, |mut header| {
+                        header.col(|_| {});
+
+                        let mut sort_header = |ui: &mut Ui, column: structs::ActivityColumn, title: String| {
+                            let title = if activity_page.sort_column == column {
+                                format!("{} {}", title, if activity_page.sort_descending { "⏷" } else { "⏶" })
+                            } else {
+                                title
+                            };
+
+                            if ui.add(Button::new(RichText::new(title).strong()).frame(false)).clicked() {
+                                if activity_page.sort_column == column {
+                                    activity_page.sort_descending = !activity_page.sort_descending;
+                                } else {
+                                    activity_page.sort_column = column;
+                                    activity_page.sort_descending = column == structs::ActivityColumn::Duration;
+                                }
+                            }
+                        };
+
+                        for (column, title) in columns.iter() {
+                            header.col(|ui| sort_header(ui, *column, title.clone()));
+                        }
+                        header.col(|ui| {
+                            ui.label(RichText::new(self.trans.blocked_by()).strong());
+                        });
+                        header.col(|ui| sort_header(ui, structs::ActivityColumn::Query, self.trans.query()));
+                    })
+                    .body(|mut body| {
+                        for session in sessions.iter() {
+                            body.row(18.0, |mut row| {
+                                row.col(|ui| {
+                                    let mut is_selected = activity_page.selected.contains(&session.pid);
+                                    if ui.checkbox(&mut is_selected, "").changed() {
+                                        if is_selected {
+                                            activity_page.selected.push(session.pid);
+                                        } else {
+                                            activity_page.selected.retain(|pid| *pid != session.pid);
+                                        }
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.label(session.pid.to_string());
+                                });
+                                for text in [
+                                    &session.user,
+                                    &session.database,
+                                    &session.application,
+                                    &session.client_address,
+                                ] {
+                                    row.col(|ui| {
+                                        ui.add(Label::new(text).wrap_mode(egui::TextWrapMode::Truncate));
+                                    });
+                                }
+                                row.col(|ui| {
+                                    let mut state = RichText::new(&session.state);
+                                    if let Some(color) = state_color(session) {
+                                        state = state.color(color);
+                                    }
+                                    ui.label(state);
+                                });
+                                row.col(|ui| {
+                                    let label = ui.add(
+                                        Label::new(&session.wait_event).wrap_mode(egui::TextWrapMode::Truncate),
+                                    );
+                                    if !session.waiting_for.is_empty() {
+                                        label.on_hover_text(format!("{}: {}", self.trans.waiting_for(), session.waiting_for));
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.label(session.duration.map(format_duration).unwrap_or_default());
+                                });
+                                row.col(|ui| {
+                                    ui.label(
+                                        session
+                                            .blocked_by
+                                            .iter()
+                                            .map(|pid| pid.to_string())
+                                            .collect::<Vec<String>>()
+                                            .join(", "),
+                                    );
+                                });
+                                row.col(|ui| {
+                                    let label = ui.add(
+                                        Label::new(RichText::new(query_preview(&session.query)).monospace())
+                                            .wrap_mode(egui::TextWrapMode::Truncate)
+                                            .sense(egui::Sense::click()),
+                                    );
+                                    if label.clicked() {
+                                        self.sql_response_copy_window.show = true;
+                                        self.sql_response_copy_window.response = Some(session.query.clone());
+                                    } else if label.hovered() {
+                                        egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), Id::new("activity_query_tooltip"), |ui| {
+                                            ui.label(self.trans.click_to_copy());
+                                        });
+                                    }
+                                });
+                            });
+                        }
+                    });
+            });
+    }
+
+    pub(super) fn update_activity_windows(&mut self, ctx: &egui::Context) {
+        if !self.backend_action_window.show {
+            return;
+        }
+
+        let Some(action) = self.backend_action_window.action else {
+            return;
+        };
+
+        Modal::new(Id::new("backend_action_modal")).show(ctx, |ui| {
+            ui.set_max_width(400.0);
+
+            let pids = self
+                .backend_action_window
+                .pids
+                .iter()
+                .map(|pid| pid.to_string())
+                .collect::<Vec<String>>()
+                .join(", ");
+
+            match action {
+                structs::BackendAction::Cancel => {
+                    widgets::modal_label(ui, self.trans.cancel_query());
+                    ui.label(self.trans.cancel_backends_confirmation(pids));
+                }
+                structs::BackendAction::Terminate => {
+                    widgets::modal_label(ui, self.trans.terminate());
+                    ui.label(self.trans.terminate_backends_confirmation(pids));
+                }
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button(self.trans.yes()).clicked() {
+                        let window = std::mem::take(&mut self.backend_action_window);
+
+                        if let Some(structs::Page {
+                            page_type: structs::PageType::Activity(activity_page),
+                            ..
+                        }) = window.page_index.and_then(|idx| self.pages.pages.get_mut(idx))
+                        {
+                            activity_page.selected.clear();
+                            activity_page.snapshot.lock().unwrap().loading = true;
+
+                            let database = activity_page.database.clone();
+                            let snapshot = activity_page.snapshot.clone();
+                            let trans = self.trans.clone();
+
+                            self.runtime.spawn(async move {
+                                Self::signal_backends(database, action, window.pids, snapshot, trans).await;
+                            });
+                        }
+                    }
+                    if ui.button(self.trans.no()).clicked() {
+                        self.backend_action_window = structs::BackendActionWindow::default();
+                    }
+                });
+            });
+        });
+    }
+}
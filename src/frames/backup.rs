@@ -0,0 +1,431 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+
+use egui::{
+    Align, Button, Color32, ComboBox, DragValue, Grid, Id, Layout, Modal, RichText, ScrollArea, Slider, Spinner,
+    TextEdit,
+};
+use egui_file_dialog::{DialogState, FileDialog};
+use log::{error, info};
+use std::collections::VecDeque;
+use std::process::Stdio;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use uuid::Uuid;
+
+/// Older output is dropped so a chatty `psql` script can't grow the log unbounded.
+const MAX_LOG_LINES: usize = 5000;
+
+fn patterns(list: &str) -> impl Iterator<Item = &str> {
+    list.split(',').map(str::trim).filter(|pattern| !pattern.is_empty())
+}
+
+/// Command line of the selected tool. Connection parameters are passed
+/// through the environment, see `backup_env`.
+fn backup_args(window: &structs::BackupWindow) -> Vec<String> {
+    let mut args = vec!["--no-password".to_string()];
+    let path = window.path.trim().to_string();
+
+    match window.tool {
+        structs::BackupTool::Dump => {
+            args.push("--verbose".to_string());
+            args.push(format!("--format={}", window.format.flag()));
+            args.push(format!("--file={}", path));
+            args.extend(patterns(&window.schemas).map(|schema| format!("--schema={}", schema)));
+            args.extend(patterns(&window.tables).map(|table| format!("--table={}", table)));
+
+            if window.data_only {
+                args.push("--data-only".to_string());
+            }
+            if window.schema_only {
+                args.push("--schema-only".to_string());
+            }
+            if window.clean && window.format == structs::DumpFormat::Plain {
+                args.push("--clean".to_string());
+                args.push("--if-exists".to_string());
+            }
+            if matches!(window.format, structs::DumpFormat::Custom | structs::DumpFormat::Directory) {
+                args.push(format!("--compress={}", window.compression));
+            }
+            if window.format == structs::DumpFormat::Directory && window.jobs > 1 {
+                args.push(format!("--jobs={}", window.jobs));
+            }
+        }
+        structs::BackupTool::Restore => {
+            args.push("--verbose".to_string());
+            args.push(format!("--dbname={}", window.database_name));
+            args.extend(patterns(&window.schemas).map(|schema| format!("--schema={}", schema)));
+            args.extend(patterns(&window.tables).map(|table| format!("--table={}", table)));
+
+            if window.data_only {
+                args.push("--data-only".to_string());
+            }
+            if window.schema_only {
+                args.push("--schema-only".to_string());
+            }
+            if window.clean {
+                args.push("--clean".to_string());
+                args.push("--if-exists".to_string());
+            }
+            if window.single_transaction {
+                args.push("--single-transaction".to_string());
+            } else if window.jobs > 1 {
+                args.push(format!("--jobs={}", window.jobs));
+            }
+            args.push(path);
+        }
+        structs::BackupTool::Psql => {
+            args.push("--no-psqlrc".to_string());
+            args.push("--set=ON_ERROR_STOP=1".to_string());
+            args.push(format!("--file={}", path));
+
+            if window.single_transaction {
+                args.push("--single-transaction".to_string());
+            }
+        }
+    }
+
+    args
+}
+
+/// Keeps the password off the command line, where other users could see it.
+fn backup_env(server: &structs::Server, database_name: &str) -> Vec<(&'static str, String)> {
+    vec![
+        ("PGHOST", server.ip.clone()),
+        ("PGPORT", server.port.to_string()),
+        ("PGUSER", server.user.clone()),
+        ("PGPASSWORD", server.password.clone()),
+        ("PGDATABASE", database_name.to_string()),
+        ("PGAPPNAME", "rs-postgres".to_string()),
+    ]
+}
+
+async fn read_output(output: impl AsyncRead + Unpin, process: Arc<Mutex<structs::BackupProcess>>) {
+    let mut lines = BufReader::new(output).lines();
+
+    while let Ok(Some(line)) = lines.next_line().await {
+        let mut process = process.lock().unwrap();
+        if process.log.len() == MAX_LOG_LINES {
+            process.log.pop_front();
+        }
+        process.log.push_back(line);
+    }
+}
+
+impl Main<'_> {
+    pub(super) fn open_backup_window(&mut self, tool: structs::BackupTool, server_id: Uuid, database_name: &str) {
+        self.backup_window = structs::BackupWindow {
+            show: true,
+            tool,
+            server_id,
+            database_name: database_name.to_string(),
+            compression: 6,
+            jobs: 1,
+            ..Default::default()
+        };
+    }
+
+    fn backup_title(&self, tool: structs::BackupTool) -> String {
+        match tool {
+            structs::BackupTool::Dump => self.trans.backup_database(),
+            structs::BackupTool::Restore => self.trans.restore_database(),
+            structs::BackupTool::Psql => self.trans.run_sql_file(),
+        }
+    }
+
+    async fn wait_backup(
+        program: &str,
+        args: Vec<String>,
+        env: Vec<(&'static str, String)>,
+        process: Arc<Mutex<structs::BackupProcess>>,
+    ) -> structs::BackupResult {
+        let mut child = match tokio::process::Command::new(program)
+            .args(&args)
+            .envs(env)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+        {
+            Ok(child) => child,
+            Err(e) => return structs::BackupResult::Error(format!("{}: {}", program, e)),
+        };
+
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(tokio::spawn(read_output(stdout, process.clone())));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(tokio::spawn(read_output(stderr, process.clone())));
+        }
+
+        let result = loop {
+            if process.lock().unwrap().cancel {
+                if let Err(e) = child.kill().await {
+                    break structs::BackupResult::Error(e.to_string());
+                }
+                break structs::BackupResult::Cancelled;
+            }
+
+            match child.try_wait() {
+                Ok(Some(status)) if status.success() => break structs::BackupResult::Success,
+                Ok(Some(status)) => break structs::BackupResult::Failed(status.code()),
+                Ok(None) => tokio::time::sleep(Duration::from_millis(100)).await,
+                Err(e) => break structs::BackupResult::Error(e.to_string()),
+            }
+        };
+
+        for reader in readers {
+            let _ = reader.await;
+        }
+
+        result
+    }
+
+    fn start_backup(&mut self) {
+        let window = &self.backup_window;
+        let Some(server) = self.config.servers.iter().find(|server| server.id == window.server_id) else {
+            return;
+        };
+
+        let program = window.tool.program();
+        let args = backup_args(window);
+        let env = backup_env(server, &window.database_name);
+        info!("Running {} {}", program, args.join(" "));
+
+        let process = Arc::new(Mutex::new(structs::BackupProcess {
+            log: VecDeque::new(),
+            cancel: false,
+            started_at: Instant::now(),
+            finished: None,
+        }));
+        self.backup_window.process = Some(process.clone());
+
+        self.runtime.spawn(async move {
+            let result = Self::wait_backup(program, args, env, process.clone()).await;
+            if let structs::BackupResult::Error(e) = &result {
+                error!("Error running {}: {}", program, e);
+            }
+
+            let mut process = process.lock().unwrap();
+            let elapsed = process.started_at.elapsed();
+            process.finished = Some((result, elapsed));
+        });
+    }
+
+    fn browse_backup_path(&mut self) {
+        let window = &self.backup_window;
+
+        self.backup_file_dialog = FileDialog::new();
+        match window.tool {
+            structs::BackupTool::Dump if window.format == structs::DumpFormat::Directory => {
+                self.backup_file_dialog.pick_directory();
+            }
+            structs::BackupTool::Dump => {
+                self.backup_file_dialog = FileDialog::new()
+                    .default_file_name(&format!("{}.{}", window.database_name, window.format.extension()));
+                self.backup_file_dialog.save_file();
+            }
+            structs::BackupTool::Restore | structs::BackupTool::Psql => self.backup_file_dialog.pick_file(),
+        }
+    }
+
+    pub(super) fn update_backup_window(&mut self, ctx: &egui::Context) {
+        if !self.backup_window.show {
+            return;
+        }
+
+        self.backup_file_dialog.update(ctx);
+        if let Some(path) = self.backup_file_dialog.take_picked() {
+            self.backup_window.path = path.display().to_string();
+        }
+        if matches!(self.backup_file_dialog.state(), DialogState::Open) {
+            return;
+        }
+
+        let (log, finished) = match &self.backup_window.process {
+            Some(process) => {
+                let process = process.lock().unwrap();
+                (Some(process.log.clone()), Some(process.finished.clone()))
+            }
+            None => (None, None),
+        };
+        let running = matches!(finished, Some(None));
+        if running {
+            ctx.request_repaint_after(Duration::from_millis(100));
+        }
+
+        let title = self.backup_title(self.backup_window.tool);
+        let mut browse = false;
+        let mut run = false;
+
+        Modal::new(Id::new("backup_modal")).show(ctx, |ui| {
+            ui.set_width(560.0);
+
+            widgets::modal_label(ui, title);
+
+            let input_color = self.config.settings.theme.text_input_color();
+            let window = &mut self.backup_window;
+            let tool = window.tool;
+
+            ui.add_enabled_ui(!running, |ui| {
+                Grid::new("backup_form")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(self.trans.database());
+                        ui.label(&window.database_name);
+                        ui.end_row();
+
+                        ui.label(self.trans.file());
+                        ui.horizontal(|ui| {
+                            TextEdit::singleline(&mut window.path)
+                                .background_color(input_color)
+                                .desired_width(320.0)
+                                .show(ui);
+                            if ui.button(self.trans.browse()).clicked() {
+                                browse = true;
+                            }
+                        });
+                        ui.end_row();
+
+                        if tool == structs::BackupTool::Dump {
+                            ui.label(self.trans.format());
+                            ComboBox::from_id_salt("backup_format")
+                                .selected_text(window.format.label())
+                                .show_ui(ui, |ui| {
+                                    for format in structs::DumpFormat::ALL {
+                                        ui.selectable_value(&mut window.format, format, format.label());
+                                    }
+                                });
+                            ui.end_row();
+                        }
+
+                        if tool != structs::BackupTool::Psql {
+                            ui.label(self.trans.schemas());
+                            TextEdit::singleline(&mut window.schemas)
+                                .background_color(input_color)
+                                .hint_text(self.trans.comma_separated())
+                                .show(ui);
+                            ui.end_row();
+
+                            ui.label(self.trans.tables());
+                            TextEdit::singleline(&mut window.tables)
+                                .background_color(input_color)
+                                .hint_text(self.trans.comma_separated())
+                                .show(ui);
+                            ui.end_row();
+                        }
+
+                        if tool == structs::BackupTool::Dump {
+                            let compressed =
+                                matches!(window.format, structs::DumpFormat::Custom | structs::DumpFormat::Directory);
+
+                            ui.label(self.trans.compression());
+                            ui.add_enabled(compressed, Slider::new(&mut window.compression, 0..=9));
+                            ui.end_row();
+                        }
+
+                        let parallel = match tool {
+                            structs::BackupTool::Dump => window.format == structs::DumpFormat::Directory,
+                            structs::BackupTool::Restore => !window.single_transaction,
+                            structs::BackupTool::Psql => false,
+                        };
+                        if tool != structs::BackupTool::Psql {
+                            ui.label(self.trans.jobs());
+                            ui.add_enabled(parallel, DragValue::new(&mut window.jobs).range(1..=32));
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(4.0);
+                ui.horizontal_wrapped(|ui| {
+                    if tool != structs::BackupTool::Psql {
+                        if ui.checkbox(&mut window.data_only, self.trans.data_only()).changed() && window.data_only {
+                            window.schema_only = false;
+                        }
+                        if ui.checkbox(&mut window.schema_only, self.trans.schema_only()).changed()
+                            && window.schema_only
+                        {
+                            window.data_only = false;
+                        }
+                    }
+                    if tool == structs::BackupTool::Restore
+                        || (tool == structs::BackupTool::Dump && window.format == structs::DumpFormat::Plain)
+                    {
+                        ui.checkbox(&mut window.clean, self.trans.clean_before_restore());
+                    }
+                    if tool != structs::BackupTool::Dump {
+                        ui.checkbox(&mut window.single_transaction, self.trans.single_transaction());
+                    }
+                });
+            });
+
+            if let Some(log) = &log {
+                ui.add_space(8.0);
+
+                let mut text = log.iter().map(String::as_str).collect::<Vec<_>>().join("\n");
+                ScrollArea::vertical()
+                    .max_height(240.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        TextEdit::multiline(&mut text)
+                            .font(egui::TextStyle::Monospace)
+                            .desired_width(f32::INFINITY)
+                            .background_color(input_color)
+                            .interactive(false)
+                            .show(ui);
+                    });
+            }
+
+            if let Some(Some((result, elapsed))) = &finished {
+                let seconds = format!("{:.1}", elapsed.as_secs_f32());
+                let (text, color) = match result {
+                    structs::BackupResult::Success => (self.trans.finished_successfully(seconds), Color32::GREEN),
+                    structs::BackupResult::Failed(code) => (
+                        self.trans.backup_failed(code.map_or_else(|| "-".to_string(), |code| code.to_string())),
+                        Color32::RED,
+                    ),
+                    structs::BackupResult::Cancelled => (self.trans.backup_cancelled(), Color32::YELLOW),
+                    structs::BackupResult::Error(e) => (e.clone(), Color32::RED),
+                };
+                ui.label(RichText::new(text).color(color));
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if running {
+                        ui.add(Spinner::new());
+
+                        if ui.button(self.trans.cancel()).clicked()
+                            && let Some(process) = &window.process
+                        {
+                            process.lock().unwrap().cancel = true;
+                        }
+                    }
+
+                    let can_run = !running && !window.path.trim().is_empty();
+                    if ui.add_enabled(can_run, Button::new(self.trans.start())).clicked() {
+                        run = true;
+                    }
+                    if ui.add_enabled(!running, Button::new(self.trans.close())).clicked() {
+                        *window = structs::BackupWindow::default();
+                    }
+                });
+            });
+        });
+
+        if browse {
+            self.browse_backup_path();
+        }
+        if run {
+            self.start_backup();
+        }
+    }
+}
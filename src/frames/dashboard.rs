@@ -0,0 +1,399 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+
+use egui::{Button, Color32, ComboBox, DragValue, Grid, RichText, Ui};
+use log::error;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// Ten minutes at the default interval.
+const MAX_POINTS: usize = 300;
+
+const BLUE: Color32 = Color32::from_rgb(90, 150, 230);
+const GREEN: Color32 = Color32::from_rgb(80, 170, 90);
+const ORANGE: Color32 = Color32::from_rgb(232, 146, 60);
+const RED: Color32 = Color32::from_rgb(220, 80, 80);
+const GRAY: Color32 = Color32::GRAY;
+
+type Chart = (String, Vec<widgets::ChartSeries>, fn(f64) -> String);
+
+fn format_number(value: f64) -> String {
+    if value >= 100.0 {
+        format!("{:.0}", value)
+    } else {
+        format!("{:.1}", value)
+    }
+}
+
+pub(super) fn format_bytes(value: f64) -> String {
+    const UNITS: [&str; 5] = ["B", "KB", "MB", "GB", "TB"];
+
+    let mut value = value;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+
+    format!("{} {}", format_number(value), UNITS[unit])
+}
+
+fn format_rate(value: f64) -> String {
+    format!("{}/s", format_number(value))
+}
+
+fn format_bytes_rate(value: f64) -> String {
+    format!("{}/s", format_bytes(value))
+}
+
+fn format_count(value: f64) -> String {
+    format!("{:.0}", value)
+}
+
+fn format_seconds(value: f64) -> String {
+    format!("{}s", format_number(value))
+}
+
+/// Turns two consecutive samples into per-second rates.
+fn derive_point(
+    time: f64,
+    seconds: f64,
+    previous: &structs::ServerStats,
+    current: &structs::ServerStats,
+) -> structs::DashboardPoint {
+    let delta = |previous: i64, current: i64| (current - previous).max(0) as f64;
+    let rate = |previous: i64, current: i64| delta(previous, current) / seconds;
+
+    let hits = delta(previous.blks_hit, current.blks_hit);
+    let reads = delta(previous.blks_read, current.blks_read);
+    let cache_hit_ratio = if hits + reads > 0.0 {
+        hits / (hits + reads) * 100.0
+    } else if current.blks_hit + current.blks_read > 0 {
+        current.blks_hit as f64 / (current.blks_hit + current.blks_read) as f64 * 100.0
+    } else {
+        100.0
+    };
+
+    structs::DashboardPoint {
+        time,
+        transactions: rate(
+            previous.xact_commit + previous.xact_rollback,
+            current.xact_commit + current.xact_rollback,
+        ),
+        cache_hit_ratio,
+        tuples_read: rate(previous.tuples_read, current.tuples_read),
+        tuples_written: rate(previous.tuples_written, current.tuples_written),
+        deadlocks: delta(previous.deadlocks, current.deadlocks),
+        temp_bytes: rate(previous.temp_bytes, current.temp_bytes),
+        wal_bytes: previous
+            .wal_bytes
+            .zip(current.wal_bytes)
+            .map(|(previous, current)| (current - previous).max(0.0) / seconds),
+        buffers_written: rate(previous.buffers_written, current.buffers_written),
+        connections_active: current.connections_active as f64,
+        connections_idle: current.connections_idle as f64,
+        connections_idle_in_transaction: current.connections_idle_in_transaction as f64,
+        connections_other: current.connections_other as f64,
+        replication_lag: current.replication_lag,
+    }
+}
+
+fn series(
+    name: String,
+    color: Color32,
+    points: &VecDeque<structs::DashboardPoint>,
+    value: impl Fn(&structs::DashboardPoint) -> Option<f64>,
+) -> widgets::ChartSeries {
+    widgets::ChartSeries {
+        name,
+        color,
+        points: points
+            .iter()
+            .filter_map(|point| value(point).map(|value| [point.time, value]))
+            .collect(),
+    }
+}
+
+impl Main<'_> {
+    pub(super) fn open_dashboard_page(&mut self, ctx: &egui::Context, server: &structs::Server) {
+        if let Some(idx) = self.pages.pages.iter().position(|page| {
+            matches!(&page.page_type, structs::PageType::Dashboard(dashboard_page) if dashboard_page.server_id == server.id)
+        }) {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let database = server.connect(&server.service_database);
+
+        let state = Arc::new(Mutex::new(structs::DashboardState {
+            database,
+            database_name: None,
+            interval: 2,
+            paused: false,
+            started_at: Instant::now(),
+            last_sample: None,
+            points: VecDeque::new(),
+            error: None,
+        }));
+
+        let state_clone = state.clone();
+        let ctx = ctx.clone();
+        self.runtime.spawn(async move {
+            Self::sample_dashboard(state_clone, ctx).await;
+        });
+
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.dashboard(), server.alias),
+            page_type: structs::PageType::Dashboard(structs::DashboardPage {
+                server_id: server.id,
+                state,
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    /// Runs until the dashboard page holding `state` is closed.
+    async fn sample_dashboard(state: Arc<Mutex<structs::DashboardState>>, ctx: egui::Context) {
+        while Arc::strong_count(&state) > 1 {
+            let (database, database_name, interval, paused) = {
+                let state = state.lock().unwrap();
+                (
+                    state.database.clone(),
+                    state.database_name.clone(),
+                    state.interval,
+                    state.paused,
+                )
+            };
+
+            if !paused {
+                let result = database.get_server_stats(database_name.as_deref()).await;
+                let now = Instant::now();
+
+                let mut state = state.lock().unwrap();
+                match result {
+                    // The filter may have changed while the sample was taken.
+                    Ok(_) if state.database_name != database_name => {}
+                    Ok(stats) => {
+                        if let Some((sampled_at, previous)) = &state.last_sample {
+                            let point = derive_point(
+                                now.duration_since(state.started_at).as_secs_f64(),
+                                now.duration_since(*sampled_at).as_secs_f64().max(0.001),
+                                previous,
+                                &stats,
+                            );
+
+                            state.points.push_back(point);
+                            if state.points.len() > MAX_POINTS {
+                                state.points.pop_front();
+                            }
+                        }
+
+                        state.last_sample = Some((now, stats));
+                        state.error = None;
+                    }
+                    Err(e) => {
+                        error!("Error sampling server stats: {}", e);
+                        state.error = Some(e);
+                    }
+                }
+
+                ctx.request_repaint();
+            }
+
+            tokio::time::sleep(Duration::from_secs(interval as u64)).await;
+        }
+    }
+
+    pub(super) fn update_dashboard_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::Dashboard(dashboard_page),
+            ..
+        }) = self.pages.pages.get(page_index)
+        else {
+            return;
+        };
+
+        let database_names: Vec<String> = match self.db_manager.dbs.lock().unwrap().get(&dashboard_page.server_id) {
+            Some(structs::DbState::Loaded(databases)) => {
+                databases.iter().map(|database| database.name.clone()).collect()
+            }
+            _ => Vec::new(),
+        };
+
+        let mut state = dashboard_page.state.lock().unwrap();
+
+        ui.horizontal(|ui| {
+            let mut database_name = state.database_name.clone();
+
+            ComboBox::from_id_salt(("dashboard_database", page_index))
+                .selected_text(database_name.clone().unwrap_or_else(|| self.trans.all_databases()))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(&mut database_name, None, self.trans.all_databases());
+                    for name in database_names {
+                        ui.selectable_value(&mut database_name, Some(name.clone()), name);
+                    }
+                });
+
+            ui.separator();
+
+            ui.label(self.trans.interval());
+            ui.add(DragValue::new(&mut state.interval).range(1..=300));
+            ui.checkbox(&mut state.paused, self.trans.pause());
+
+            ui.separator();
+
+            if ui.add(Button::new(self.trans.clear())).clicked() || database_name != state.database_name {
+                state.database_name = database_name;
+                state.started_at = Instant::now();
+                state.last_sample = None;
+                state.points.clear();
+            }
+        });
+
+        if let Some(error) = &state.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        ui.separator();
+
+        let Some((_, stats)) = &state.last_sample else {
+            ui.label(self.trans.collecting_samples());
+            return;
+        };
+
+        Grid::new(("dashboard_summary", page_index))
+            .num_columns(2)
+            .spacing([40.0, 4.0])
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label(self.trans.role());
+                ui.label(if stats.in_recovery { self.trans.standby() } else { self.trans.primary() });
+                ui.end_row();
+
+                ui.label(self.trans.connections());
+                ui.label(
+                    (stats.connections_active
+                        + stats.connections_idle
+                        + stats.connections_idle_in_transaction
+                        + stats.connections_other)
+                        .to_string(),
+                );
+                ui.end_row();
+
+                ui.label(self.trans.deadlocks());
+                ui.label(stats.deadlocks.to_string());
+                ui.end_row();
+
+                ui.label(self.trans.temp_files());
+                ui.label(format!("{} ({})", stats.temp_files, format_bytes(stats.temp_bytes as f64)));
+                ui.end_row();
+
+                ui.label(self.trans.checkpoints());
+                ui.label(stats.checkpoints.to_string());
+                ui.end_row();
+            });
+
+        if !stats.replicas.is_empty() {
+            ui.add_space(8.0);
+            ui.label(RichText::new(self.trans.replicas()).strong());
+
+            Grid::new(("dashboard_replicas", page_index))
+                .num_columns(5)
+                .spacing([24.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new(self.trans.name()).strong());
+                    ui.label(RichText::new(self.trans.client()).strong());
+                    ui.label(RichText::new(self.trans.state()).strong());
+                    ui.label(RichText::new(self.trans.replication_lag()).strong());
+                    ui.label(RichText::new("WAL").strong());
+                    ui.end_row();
+
+                    for replica in stats.replicas.iter() {
+                        ui.label(&replica.name);
+                        ui.label(&replica.client_address);
+                        ui.label(&replica.state);
+                        ui.label(replica.replay_lag.map(format_seconds).unwrap_or_default());
+                        ui.label(replica.lag_bytes.map(format_bytes).unwrap_or_default());
+                        ui.end_row();
+                    }
+                });
+        }
+
+        ui.add_space(8.0);
+
+        let points = &state.points;
+        if points.is_empty() {
+            ui.label(self.trans.collecting_samples());
+            return;
+        }
+
+        let charts: Vec<Chart> = vec![
+            (
+                self.trans.transactions_per_second(),
+                vec![series(self.trans.transactions_per_second(), BLUE, points, |p| Some(p.transactions))],
+                format_rate,
+            ),
+            (
+                self.trans.cache_hit_ratio(),
+                vec![series(self.trans.cache_hit_ratio(), GREEN, points, |p| Some(p.cache_hit_ratio))],
+                |v| format!("{:.1}%", v),
+            ),
+            (
+                self.trans.tuples_per_second(),
+                vec![
+                    series(self.trans.read(), BLUE, points, |p| Some(p.tuples_read)),
+                    series(self.trans.written(), ORANGE, points, |p| Some(p.tuples_written)),
+                ],
+                format_rate,
+            ),
+            (
+                self.trans.connections(),
+                vec![
+                    series(String::from("active"), GREEN, points, |p| Some(p.connections_active)),
+                    series(String::from("idle"), GRAY, points, |p| Some(p.connections_idle)),
+                    series(String::from("idle in transaction"), ORANGE, points, |p| Some(p.connections_idle_in_transaction)),
+                    series(self.trans.other(), BLUE, points, |p| Some(p.connections_other)),
+                ],
+                format_count,
+            ),
+            (
+                self.trans.deadlocks(),
+                vec![series(self.trans.deadlocks(), RED, points, |p| Some(p.deadlocks))],
+                format_count,
+            ),
+            (
+                self.trans.temp_files(),
+                vec![series(self.trans.temp_files(), ORANGE, points, |p| Some(p.temp_bytes))],
+                format_bytes_rate,
+            ),
+            (
+                String::from("WAL"),
+                vec![series(String::from("WAL"), BLUE, points, |p| p.wal_bytes)],
+                format_bytes_rate,
+            ),
+            (
+                self.trans.buffers_written(),
+                vec![series(self.trans.buffers_written(), GREEN, points, |p| Some(p.buffers_written))],
+                format_rate,
+            ),
+            (
+                self.trans.replication_lag(),
+                vec![series(self.trans.replication_lag(), RED, points, |p| p.replication_lag)],
+                format_seconds,
+            ),
+        ];
+
+        for row in charts.chunks(2) {
+            ui.columns(row.len().max(2), |columns| {
+                for (column, (title, series, format_value)) in columns.iter_mut().zip(row) {
+                    widgets::line_chart(column, title.clone(), series, format_value);
+                }
+            });
+            ui.add_space(8.0);
+        }
+    }
+}
@@ -0,0 +1,550 @@
+use super::{Main, schema_diff};
+
+use crate::data::*;
+use crate::database;
+use crate::utils::{quote_ident, quote_literal};
+
+use egui::{Button, Color32, ComboBox, Grid, RichText, ScrollArea, Spinner, TextEdit, Ui};
+use log::error;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+const BATCH_SIZE: usize = 1000;
+/// Differences kept for display, the rest are only counted.
+const MAX_DIFFERENCES: usize = 10_000;
+
+type Row = Vec<Option<String>>;
+
+fn literal(value: &Option<String>) -> String {
+    value.as_deref().map(quote_literal).unwrap_or_else(|| String::from("NULL"))
+}
+
+fn key_condition(comparison: &structs::DataComparison, row: &Row) -> String {
+    comparison.columns[..comparison.key_length]
+        .iter()
+        .zip(row.iter())
+        .map(|(column, value)| format!("{} = {}", quote_ident(column), literal(value)))
+        .collect::<Vec<String>>()
+        .join(" AND ")
+}
+
+/// Builds the statements that sync the target with the source for the
+/// selected differences: deletes first, so inserted keys are free.
+fn sync_script(comparison: &structs::DataComparison) -> String {
+    let selected = |status: structs::DiffStatus| {
+        comparison
+            .differences
+            .iter()
+            .filter(move |difference| difference.selected && difference.status == status)
+    };
+    let mut statements = Vec::new();
+
+    for difference in selected(structs::DiffStatus::Removed) {
+        if let Some(target) = &difference.target {
+            statements.push(format!("DELETE FROM {} WHERE {};", comparison.table, key_condition(comparison, target)));
+        }
+    }
+
+    for difference in selected(structs::DiffStatus::Changed) {
+        let (Some(source), Some(target)) = (&difference.source, &difference.target) else {
+            continue;
+        };
+        let changes: Vec<String> = comparison
+            .columns
+            .iter()
+            .zip(source.iter().zip(target.iter()))
+            .skip(comparison.key_length)
+            .filter(|(_, (source, target))| source != target)
+            .map(|(column, (source, _))| format!("{} = {}", quote_ident(column), literal(source)))
+            .collect();
+
+        statements.push(format!(
+            "UPDATE {} SET {} WHERE {};",
+            comparison.table,
+            changes.join(", "),
+            key_condition(comparison, target)
+        ));
+    }
+
+    let columns = comparison
+        .columns
+        .iter()
+        .map(|column| quote_ident(column))
+        .collect::<Vec<String>>()
+        .join(", ");
+    for difference in selected(structs::DiffStatus::Added) {
+        if let Some(source) = &difference.source {
+            statements.push(format!(
+                "INSERT INTO {} ({}){} VALUES ({});",
+                comparison.table,
+                columns,
+                if comparison.identity_always { " OVERRIDING SYSTEM VALUE" } else { "" },
+                source.iter().map(literal).collect::<Vec<String>>().join(", ")
+            ));
+        }
+    }
+
+    statements.join("\n")
+}
+
+/// Reads rows from `cursor` into `buffer` once it runs empty.
+async fn next_row(cursor: &mut database::Cursor, buffer: &mut VecDeque<Row>, done: &mut bool) -> Result<Option<Row>, String> {
+    if buffer.is_empty() && !*done {
+        buffer.extend(cursor.fetch(BATCH_SIZE).await?);
+        *done = buffer.len() < BATCH_SIZE;
+    }
+    Ok(buffer.pop_front())
+}
+
+impl Main<'_> {
+    pub(super) fn open_data_compare(&mut self, server_id: Uuid, database_name: &str, table: &str) {
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.data_compare(), table),
+            page_type: structs::PageType::DataCompare(structs::DataComparePage {
+                source: Some((server_id, database_name.to_string())),
+                target: None,
+                table: table.to_string(),
+                snapshot: Arc::new(Mutex::new(structs::DataCompareSnapshot::default())),
+                comparison: None,
+                selected: None,
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    /// Reads both tables in primary key order and merges them. Keys are
+    /// ordered as text in the "C" collation, which is how Rust compares
+    /// strings, so the order doesn't depend on types or database collations.
+    async fn compare_data(
+        source: database::Database,
+        target: database::Database,
+        table: String,
+        snapshot: Arc<Mutex<structs::DataCompareSnapshot>>,
+    ) -> Result<structs::DataComparison, String> {
+        let (schema, name) = match table.split_once('.') {
+            Some((schema, name)) => (Some(schema), name),
+            None => (None, table.as_str()),
+        };
+        let (source_table, source_columns, source_key, _) = source.get_table_key(schema, name).await?;
+        let (target_table, target_columns, target_key, identity_always) = target.get_table_key(schema, name).await?;
+
+        if source_key != target_key {
+            return Err(format!(
+                "Primary keys differ: ({}) and ({})",
+                source_key.join(", "),
+                target_key.join(", ")
+            ));
+        }
+
+        let mut columns = source_key.clone();
+        columns.extend(
+            source_columns
+                .iter()
+                .filter(|column| !source_key.contains(column) && target_columns.contains(column))
+                .cloned(),
+        );
+        let skipped_columns: Vec<String> = source_columns
+            .iter()
+            .chain(target_columns.iter())
+            .filter(|column| !columns.contains(column))
+            .cloned()
+            .collect();
+
+        let select = |table: &str| {
+            format!(
+                "SELECT {} FROM {} ORDER BY {}",
+                columns
+                    .iter()
+                    .map(|column| format!("{}::text", quote_ident(column)))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                table,
+                source_key
+                    .iter()
+                    .map(|column| format!("{}::text COLLATE \"C\"", quote_ident(column)))
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            )
+        };
+
+        let mut source_cursor = source.open_cursor(&select(&source_table)).await?;
+        let mut target_cursor = target.open_cursor(&select(&target_table)).await?;
+        let (mut source_rows, mut target_rows) = (VecDeque::new(), VecDeque::new());
+        let (mut source_done, mut target_done) = (false, false);
+
+        let mut comparison = structs::DataComparison {
+            table: target_table,
+            key_length: source_key.len(),
+            identity_always,
+            skipped_columns,
+            ..Default::default()
+        };
+
+        let mut source_row = next_row(&mut source_cursor, &mut source_rows, &mut source_done).await?;
+        let mut target_row = next_row(&mut target_cursor, &mut target_rows, &mut target_done).await?;
+        let mut rows: u64 = 0;
+        let mut counts = [0u64; 3];
+
+        loop {
+            let status = match (&source_row, &target_row) {
+                (None, None) => break,
+                (Some(_), None) => structs::DiffStatus::Added,
+                (None, Some(_)) => structs::DiffStatus::Removed,
+                (Some(source), Some(target)) => match source[..comparison.key_length].cmp(&target[..comparison.key_length]) {
+                    std::cmp::Ordering::Less => structs::DiffStatus::Added,
+                    std::cmp::Ordering::Greater => structs::DiffStatus::Removed,
+                    std::cmp::Ordering::Equal if source == target => {
+                        source_row = next_row(&mut source_cursor, &mut source_rows, &mut source_done).await?;
+                        target_row = next_row(&mut target_cursor, &mut target_rows, &mut target_done).await?;
+                        rows += 1;
+                        continue;
+                    }
+                    std::cmp::Ordering::Equal => structs::DiffStatus::Changed,
+                },
+            };
+
+            let (source, target) = match status {
+                structs::DiffStatus::Added => (source_row.take(), None),
+                structs::DiffStatus::Removed => (None, target_row.take()),
+                structs::DiffStatus::Changed => (source_row.take(), target_row.take()),
+            };
+            if source.is_some() {
+                source_row = next_row(&mut source_cursor, &mut source_rows, &mut source_done).await?;
+            }
+            if target.is_some() {
+                target_row = next_row(&mut target_cursor, &mut target_rows, &mut target_done).await?;
+            }
+
+            rows += 1;
+            counts[status as usize] += 1;
+            if comparison.differences.len() < MAX_DIFFERENCES {
+                comparison.differences.push(structs::DataDifference {
+                    status,
+                    source,
+                    target,
+                    selected: true,
+                });
+            } else {
+                comparison.truncated = true;
+            }
+
+            if rows.is_multiple_of(BATCH_SIZE as u64) {
+                let mut snapshot = snapshot.lock().unwrap();
+                if snapshot.cancel {
+                    return Err(String::from("Cancelled"));
+                }
+                snapshot.rows = rows;
+                snapshot.counts = counts;
+            }
+        }
+
+        let mut snapshot = snapshot.lock().unwrap();
+        snapshot.rows = rows;
+        snapshot.counts = counts;
+
+        comparison.columns = columns;
+        Ok(comparison)
+    }
+
+    pub(super) fn refresh_data_compare(&self, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::DataCompare(data_compare_page),
+            ..
+        }) = self.pages.pages.get(page_index)
+        else {
+            return;
+        };
+
+        let database = |database: &Option<(Uuid, String)>| {
+            database
+                .as_ref()
+                .and_then(|(server_id, database_name)| self.loaded_database(*server_id, database_name))
+                .map(|database| database.database)
+        };
+        let (Some(source), Some(target)) = (database(&data_compare_page.source), database(&data_compare_page.target)) else {
+            data_compare_page.snapshot.lock().unwrap().error = Some(self.trans.database_not_loaded());
+            return;
+        };
+
+        *data_compare_page.snapshot.lock().unwrap() = structs::DataCompareSnapshot {
+            loading: true,
+            ..Default::default()
+        };
+
+        let table = data_compare_page.table.trim().to_string();
+        let snapshot = data_compare_page.snapshot.clone();
+        self.runtime.spawn(async move {
+            let result = Self::compare_data(source, target, table, snapshot.clone()).await;
+
+            let mut snapshot = snapshot.lock().unwrap();
+            match result {
+                Ok(comparison) => snapshot.comparison = Some(comparison),
+                Err(e) => {
+                    error!("Error comparing data: {}", e);
+                    snapshot.error = Some(e);
+                }
+            }
+            snapshot.loading = false;
+        });
+    }
+
+    pub(super) fn update_data_compare_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let databases = self.loaded_databases();
+        let source_tables = self.data_compare_tables(page_index);
+
+        let Some(structs::Page {
+            page_type: structs::PageType::DataCompare(data_compare_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = {
+            let mut snapshot = data_compare_page.snapshot.lock().unwrap();
+            if let Some(comparison) = snapshot.comparison.take() {
+                data_compare_page.comparison = Some(comparison);
+                data_compare_page.selected = None;
+            }
+            snapshot.clone()
+        };
+        let mut compare = false;
+
+        ui.horizontal(|ui| {
+            ui.label(self.trans.source());
+            schema_diff::database_picker(ui, ("data_compare_source", page_index), &databases, &mut data_compare_page.source);
+            ui.label(self.trans.target());
+            schema_diff::database_picker(ui, ("data_compare_target", page_index), &databases, &mut data_compare_page.target);
+
+            if ui.button("⇄").clicked() {
+                std::mem::swap(&mut data_compare_page.source, &mut data_compare_page.target);
+            }
+        });
+
+        ui.horizontal(|ui| {
+            ui.label(self.trans.table());
+            ui.add(
+                TextEdit::singleline(&mut data_compare_page.table)
+                    .hint_text("schema.table")
+                    .background_color(self.config.settings.theme.text_input_color()),
+            );
+            ComboBox::from_id_salt(("data_compare_table", page_index))
+                .selected_text("")
+                .width(24.0)
+                .show_ui(ui, |ui| {
+                    for table in source_tables.iter() {
+                        if ui.selectable_label(data_compare_page.table == *table, table).clicked() {
+                            data_compare_page.table = table.clone();
+                        }
+                    }
+                });
+
+            let can_compare = data_compare_page.source.is_some()
+                && data_compare_page.target.is_some()
+                && !data_compare_page.table.trim().is_empty()
+                && !snapshot.loading;
+            if ui.add_enabled(can_compare, Button::new(self.trans.compare())).clicked() {
+                compare = true;
+            }
+
+            if snapshot.loading {
+                ui.add(Spinner::new());
+                ui.label(self.trans.rows_compared(snapshot.rows));
+                if ui.button(self.trans.cancel()).clicked() {
+                    data_compare_page.snapshot.lock().unwrap().cancel = true;
+                }
+            }
+        });
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        if compare {
+            self.refresh_data_compare(page_index);
+            return;
+        }
+
+        let Some(comparison) = &mut data_compare_page.comparison else {
+            return;
+        };
+        let script = sync_script(comparison);
+        let mut review = false;
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} · {}: {}, {}: {}, {}: {}",
+                self.trans.rows_compared(snapshot.rows),
+                self.trans.added(),
+                snapshot.counts[structs::DiffStatus::Added as usize],
+                self.trans.removed(),
+                snapshot.counts[structs::DiffStatus::Removed as usize],
+                self.trans.changed(),
+                snapshot.counts[structs::DiffStatus::Changed as usize]
+            ));
+
+            ui.separator();
+
+            if ui.button(self.trans.select_all()).clicked() {
+                comparison.differences.iter_mut().for_each(|difference| difference.selected = true);
+            }
+            if ui.button(self.trans.select_none()).clicked() {
+                comparison.differences.iter_mut().for_each(|difference| difference.selected = false);
+            }
+            if ui.add_enabled(!script.is_empty(), Button::new(self.trans.sync_script())).clicked() {
+                review = true;
+            }
+        });
+
+        if comparison.truncated {
+            ui.label(RichText::new(self.trans.differences_truncated(MAX_DIFFERENCES)).color(Color32::YELLOW));
+        }
+        if !comparison.skipped_columns.is_empty() {
+            ui.label(
+                RichText::new(self.trans.skipped_columns(comparison.skipped_columns.join(", "))).color(Color32::YELLOW),
+            );
+        }
+
+        ui.separator();
+
+        if comparison.differences.is_empty() {
+            ui.label(self.trans.no_differences());
+        }
+
+        let key_columns = comparison.columns[..comparison.key_length].join(", ");
+
+        ScrollArea::vertical()
+            .id_salt(("data_compare_list", page_index))
+            .max_height(ui.available_height() / 2.0)
+            .auto_shrink([false, true])
+            .show_rows(ui, ui.spacing().interact_size.y, comparison.differences.len(), |ui, range| {
+                Grid::new(("data_compare_grid", page_index))
+                    .num_columns(4)
+                    .striped(true)
+                    .start_row(range.start)
+                    .show(ui, |ui| {
+                        for index in range {
+                            let difference = &mut comparison.differences[index];
+                            let row = difference.source.as_ref().or(difference.target.as_ref()).unwrap();
+                            let key = row[..comparison.key_length]
+                                .iter()
+                                .map(|value| value.clone().unwrap_or_default())
+                                .collect::<Vec<String>>()
+                                .join(", ");
+
+                            ui.checkbox(&mut difference.selected, "");
+                            let (status, color) = match difference.status {
+                                structs::DiffStatus::Added => (self.trans.added(), Color32::GREEN),
+                                structs::DiffStatus::Removed => (self.trans.removed(), Color32::RED),
+                                structs::DiffStatus::Changed => (self.trans.changed(), Color32::YELLOW),
+                            };
+                            ui.label(RichText::new(status).color(color));
+                            if ui
+                                .selectable_label(data_compare_page.selected == Some(index), format!("({}) = ({})", key_columns, key))
+                                .clicked()
+                            {
+                                data_compare_page.selected = Some(index);
+                            }
+                            if let (Some(source), Some(target)) = (&difference.source, &difference.target) {
+                                let changed: Vec<&str> = comparison
+                                    .columns
+                                    .iter()
+                                    .zip(source.iter().zip(target.iter()))
+                                    .filter(|(_, (source, target))| source != target)
+                                    .map(|(column, _)| column.as_str())
+                                    .collect();
+                                ui.label(changed.join(", "));
+                            } else {
+                                ui.label("");
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(difference) = data_compare_page
+            .selected
+            .and_then(|index| comparison.differences.get(index))
+        {
+            ui.separator();
+
+            ScrollArea::both()
+                .id_salt(("data_compare_row", page_index))
+                .auto_shrink([false, true])
+                .show(ui, |ui| {
+                    Grid::new(("data_compare_row_grid", page_index))
+                        .num_columns(3)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label(RichText::new(self.trans.column()).strong());
+                            ui.label(RichText::new(self.trans.source()).strong());
+                            ui.label(RichText::new(self.trans.target()).strong());
+                            ui.end_row();
+
+                            for (index, column) in comparison.columns.iter().enumerate() {
+                                let value = |row: &Option<Row>| {
+                                    row.as_ref().map(|row| row[index].clone().unwrap_or_else(|| String::from("NULL")))
+                                };
+                                let (source, target) = (value(&difference.source), value(&difference.target));
+                                let color = if source.is_some() && target.is_some() && source != target {
+                                    Color32::YELLOW
+                                } else {
+                                    ui.visuals().text_color()
+                                };
+
+                                ui.label(column);
+                                ui.label(RichText::new(source.unwrap_or_default()).color(color));
+                                ui.label(RichText::new(target.unwrap_or_default()).color(color));
+                                ui.end_row();
+                            }
+                        });
+                });
+        }
+
+        if review
+            && let Some(structs::Page {
+                page_type: structs::PageType::DataCompare(structs::DataComparePage {
+                    target: Some((server_id, database_name)),
+                    ..
+                }),
+                ..
+            }) = self.pages.pages.get(page_index)
+        {
+            let server_id = *server_id;
+            let database_name = database_name.clone();
+
+            self.open_script_window(
+                self.trans.sync_script(),
+                server_id,
+                &database_name,
+                script,
+                None,
+                structs::AfterScript::ReloadDataCompare(page_index),
+            );
+        }
+    }
+
+    /// Tables of the source database, as listed in the connection tree.
+    fn data_compare_tables(&self, page_index: usize) -> Vec<String> {
+        let Some(structs::Page {
+            page_type: structs::PageType::DataCompare(structs::DataComparePage {
+                source: Some((server_id, database_name)),
+                ..
+            }),
+            ..
+        }) = self.pages.pages.get(page_index)
+        else {
+            return Vec::new();
+        };
+
+        match self
+            .loaded_database(*server_id, database_name)
+            .and_then(|database| database.tables.lock().unwrap().clone())
+        {
+            Some(structs::TablesState::Loaded(tables)) => tables,
+            _ => Vec::new(),
+        }
+    }
+}
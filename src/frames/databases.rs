@@ -0,0 +1,563 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+use crate::utils::{quote_ident, quote_literal};
+
+use egui::{
+    Align, Button, Color32, ComboBox, Grid, Id, Layout, Modal, RichText, Spinner, TextEdit, Ui,
+};
+use log::error;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+const ENCODINGS: [&str; 10] = [
+    "UTF8", "SQL_ASCII", "LATIN1", "LATIN2", "LATIN9", "WIN1250", "WIN1251", "WIN1252", "KOI8R",
+    "EUC_JP",
+];
+
+/// A combo box over `options` where the empty string stands for the server default.
+fn option_combo(ui: &mut Ui, id: &str, value: &mut String, options: &[String], default: &str) {
+    ComboBox::from_id_salt(id)
+        .width(220.0)
+        .selected_text(if value.is_empty() { default } else { value.as_str() })
+        .show_ui(ui, |ui| {
+            ui.selectable_value(value, String::new(), default);
+            for option in options {
+                ui.selectable_value(value, option.clone(), option);
+            }
+        });
+}
+
+fn create_database_script(window: &structs::CreateDatabaseWindow) -> String {
+    let mut options = Vec::new();
+
+    if !window.owner.is_empty() {
+        options.push(format!("OWNER {}", quote_ident(&window.owner)));
+    }
+    if !window.template.is_empty() {
+        options.push(format!("TEMPLATE {}", quote_ident(&window.template)));
+    }
+    if !window.encoding.is_empty() {
+        options.push(format!("ENCODING {}", quote_literal(&window.encoding)));
+    }
+    if !window.locale.trim().is_empty() {
+        options.push(format!("LOCALE {}", quote_literal(window.locale.trim())));
+    }
+    if !window.tablespace.is_empty() {
+        options.push(format!("TABLESPACE {}", quote_ident(&window.tablespace)));
+    }
+
+    if options.is_empty() {
+        format!("CREATE DATABASE {};", quote_ident(window.name.trim()))
+    } else {
+        format!(
+            "CREATE DATABASE {} WITH {};",
+            quote_ident(window.name.trim()),
+            options.join(" ")
+        )
+    }
+}
+
+fn alter_database_script(window: &structs::AlterDatabaseWindow) -> String {
+    let mut statements = Vec::new();
+    let name = window.name.trim();
+
+    if name != window.database_name {
+        statements.push(format!(
+            "ALTER DATABASE {} RENAME TO {};",
+            quote_ident(&window.database_name),
+            quote_ident(name)
+        ));
+    }
+    if window.owner != window.original_owner {
+        statements.push(format!(
+            "ALTER DATABASE {} OWNER TO {};",
+            quote_ident(name),
+            quote_ident(&window.owner)
+        ));
+    }
+
+    statements.join("\n")
+}
+
+impl Main<'_> {
+    /// Reloads the database list of a loaded server, keeping the
+    /// connections and table lists of databases that still exist.
+    pub(super) async fn refresh_databases(
+        server: structs::Server,
+        dbs: Arc<Mutex<HashMap<Uuid, structs::DbState>>>,
+    ) {
+        let names = match server.connect(&server.service_database).get_databases().await {
+            Ok(names) => names,
+            Err(e) => {
+                error!("Error refreshing databases for server {}: {}", server.ip, e);
+                return;
+            }
+        };
+
+        let mut dbs = dbs.lock().unwrap();
+        let Some(structs::DbState::Loaded(existing)) = dbs.get(&server.id) else {
+            return;
+        };
+
+        let mut databases = Vec::new();
+        for name in names {
+            if let Some(database) = existing
+                .iter()
+                .find(|database| database.name == name && !database.database.is_closed())
+            {
+                databases.push(database.clone());
+                continue;
+            }
+
+            databases.push(structs::LoadedDatabase {
+                database: server.connect(&name),
+                name,
+                tables: Arc::new(Mutex::new(None)),
+            });
+        }
+
+        dbs.insert(server.id, structs::DbState::Loaded(databases));
+    }
+
+    fn service_database(&self, server_id: Uuid) -> Option<crate::database::Database> {
+        let server = self.config.servers.iter().find(|server| server.id == server_id)?;
+
+        Some(server.connect(&server.service_database))
+    }
+
+    fn load_database_options(
+        &self,
+        server_id: Uuid,
+    ) -> Arc<Mutex<Option<Result<structs::DatabaseOptions, String>>>> {
+        let options = Arc::new(Mutex::new(None));
+
+        if let Some(database) = self.service_database(server_id) {
+            let options = options.clone();
+            self.runtime.spawn(async move {
+                let result = database.get_database_options().await;
+                *options.lock().unwrap() = Some(result);
+            });
+        }
+
+        options
+    }
+
+    pub(super) fn open_create_database_window(&mut self, server_id: Uuid) {
+        self.create_database_window = structs::CreateDatabaseWindow {
+            show: true,
+            server_id,
+            options: self.load_database_options(server_id),
+            ..Default::default()
+        };
+    }
+
+    pub(super) fn open_alter_database_window(&mut self, server_id: Uuid, database_name: &str) {
+        let Some(database) = self.service_database(server_id) else {
+            return;
+        };
+
+        let properties = Arc::new(Mutex::new(None));
+        self.alter_database_window = structs::AlterDatabaseWindow {
+            show: true,
+            server_id,
+            database_name: database_name.to_string(),
+            name: database_name.to_string(),
+            options: self.load_database_options(server_id),
+            properties: properties.clone(),
+            ..Default::default()
+        };
+
+        let database_name = database_name.to_string();
+        self.runtime.spawn(async move {
+            let result = database.get_database_properties(&database_name).await;
+            *properties.lock().unwrap() = Some(result);
+        });
+    }
+
+    pub(super) fn open_drop_database_window(&mut self, server_id: Uuid, database_name: &str) {
+        self.drop_database_window = structs::DropDatabaseWindow {
+            show: true,
+            server_id,
+            database_name: database_name.to_string(),
+            force: false,
+        };
+    }
+
+    pub(super) fn open_database_properties_window(&mut self, server_id: Uuid, database_name: &str) {
+        let Some(database) = self.service_database(server_id) else {
+            return;
+        };
+
+        let properties = Arc::new(Mutex::new(None));
+        self.database_properties_window = structs::DatabasePropertiesWindow {
+            show: true,
+            database_name: database_name.to_string(),
+            properties: properties.clone(),
+        };
+
+        let database_name = database_name.to_string();
+        self.runtime.spawn(async move {
+            let result = database.get_database_properties(&database_name).await;
+            *properties.lock().unwrap() = Some(result);
+        });
+    }
+
+    /// Opens `sql` for review against the service database of the server.
+    fn review_database_script(
+        &mut self,
+        title: String,
+        server_id: Uuid,
+        sql: String,
+        disconnect: Option<&str>,
+        after: structs::AfterScript,
+    ) {
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id) else {
+            return;
+        };
+        let service_database = server.service_database.clone();
+        let disconnect = disconnect
+            .and_then(|name| self.loaded_database(server_id, name))
+            .map(|database| database.database);
+
+        self.open_script_window(
+            title,
+            server_id,
+            &service_database,
+            sql,
+            None,
+            after,
+        );
+        self.script_window.disconnect = disconnect;
+    }
+
+    pub(super) fn update_database_windows(&mut self, ctx: &egui::Context) {
+        self.update_create_database_window(ctx);
+        self.update_alter_database_window(ctx);
+        self.update_drop_database_window(ctx);
+        self.update_database_properties_window(ctx);
+    }
+
+    fn update_create_database_window(&mut self, ctx: &egui::Context) {
+        if !self.create_database_window.show {
+            return;
+        }
+
+        let options = self.create_database_window.options.lock().unwrap().clone();
+        let mut review = false;
+
+        Modal::new(Id::new("create_database_modal")).show(ctx, |ui| {
+            ui.set_width(420.0);
+
+            widgets::modal_label(ui, self.trans.create_database());
+
+            let input_color = self.config.settings.theme.text_input_color();
+            let default = self.trans.default_value();
+            let window = &mut self.create_database_window;
+
+            let options = match &options {
+                None => {
+                    ui.add(Spinner::new());
+                    structs::DatabaseOptions::default()
+                }
+                Some(Ok(options)) => options.clone(),
+                Some(Err(e)) => {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                    structs::DatabaseOptions::default()
+                }
+            };
+            let encodings: Vec<String> = ENCODINGS.iter().map(|encoding| encoding.to_string()).collect();
+
+            Grid::new("create_database_form")
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(self.trans.name());
+                    TextEdit::singleline(&mut window.name)
+                        .background_color(input_color)
+                        .show(ui);
+                    ui.end_row();
+
+                    ui.label(self.trans.owner());
+                    option_combo(ui, "create_database_owner", &mut window.owner, &options.owners, &default);
+                    ui.end_row();
+
+                    ui.label(self.trans.template());
+                    option_combo(ui, "create_database_template", &mut window.template, &options.templates, &default);
+                    ui.end_row();
+
+                    ui.label(self.trans.encoding());
+                    option_combo(ui, "create_database_encoding", &mut window.encoding, &encodings, &default);
+                    ui.end_row();
+
+                    ui.label(self.trans.locale());
+                    TextEdit::singleline(&mut window.locale)
+                        .background_color(input_color)
+                        .hint_text("en_US.UTF-8")
+                        .show(ui);
+                    ui.end_row();
+
+                    ui.label(self.trans.tablespace());
+                    option_combo(ui, "create_database_tablespace", &mut window.tablespace, &options.tablespaces, &default);
+                    ui.end_row();
+                });
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let can_review = !window.name.trim().is_empty();
+                    if ui.add_enabled(can_review, Button::new(self.trans.review())).clicked() {
+                        review = true;
+                    }
+                    if ui.button(self.trans.close()).clicked() {
+                        window.show = false;
+                    }
+                });
+            });
+        });
+
+        if review {
+            let window = std::mem::take(&mut self.create_database_window);
+            self.review_database_script(
+                self.trans.create_database(),
+                window.server_id,
+                create_database_script(&window),
+                None,
+                structs::AfterScript::ReloadDatabases(window.server_id),
+            );
+        }
+    }
+
+    fn update_alter_database_window(&mut self, ctx: &egui::Context) {
+        if !self.alter_database_window.show {
+            return;
+        }
+
+        let options = self.alter_database_window.options.lock().unwrap().clone();
+        let properties = self.alter_database_window.properties.lock().unwrap().clone();
+        // The rename runs on the service database, which can't rename itself.
+        let is_service_database = self.config.servers.iter().any(|server| {
+            server.id == self.alter_database_window.server_id
+                && server.service_database == self.alter_database_window.database_name
+        });
+        let mut review = false;
+
+        Modal::new(Id::new("alter_database_modal")).show(ctx, |ui| {
+            ui.set_width(420.0);
+
+            widgets::modal_label(ui, self.trans.alter_database());
+
+            let input_color = self.config.settings.theme.text_input_color();
+            let window = &mut self.alter_database_window;
+
+            // The owner is filled in once known, so an unchanged owner is not altered.
+            if window.original_owner.is_empty()
+                && let Some(Ok(properties)) = &properties
+            {
+                window.original_owner = properties.owner.clone();
+                window.owner = properties.owner.clone();
+            }
+
+            let owners = match &options {
+                Some(Ok(options)) => options.owners.clone(),
+                Some(Err(e)) => {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                    Vec::new()
+                }
+                None => Vec::new(),
+            };
+            if let Some(Err(e)) = &properties {
+                ui.label(RichText::new(e).color(Color32::RED));
+            }
+
+            Grid::new("alter_database_form")
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(self.trans.name());
+                    ui.add_enabled_ui(!is_service_database, |ui| {
+                        TextEdit::singleline(&mut window.name)
+                            .background_color(input_color)
+                            .show(ui);
+                    });
+                    ui.end_row();
+
+                    ui.label(self.trans.owner());
+                    if window.original_owner.is_empty() {
+                        ui.add(Spinner::new());
+                    } else {
+                        ComboBox::from_id_salt("alter_database_owner")
+                            .width(220.0)
+                            .selected_text(&window.owner)
+                            .show_ui(ui, |ui| {
+                                for owner in owners.iter() {
+                                    ui.selectable_value(&mut window.owner, owner.clone(), owner);
+                                }
+                            });
+                    }
+                    ui.end_row();
+                });
+
+            if is_service_database {
+                ui.label(self.trans.service_database_rename_note());
+            } else if window.name.trim() != window.database_name {
+                ui.label(self.trans.alter_database_note());
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    let can_review = !window.name.trim().is_empty() && !alter_database_script(window).is_empty();
+                    if ui.add_enabled(can_review, Button::new(self.trans.review())).clicked() {
+                        review = true;
+                    }
+                    if ui.button(self.trans.close()).clicked() {
+                        window.show = false;
+                    }
+                });
+            });
+        });
+
+        if review {
+            let window = std::mem::take(&mut self.alter_database_window);
+            let name = window.name.trim();
+            let (disconnect, after) = if name != window.database_name {
+                (
+                    Some(window.database_name.as_str()),
+                    structs::AfterScript::RenameDatabase(window.server_id, window.database_name.clone(), name.to_string()),
+                )
+            } else {
+                (None, structs::AfterScript::ReloadDatabases(window.server_id))
+            };
+
+            self.review_database_script(
+                self.trans.alter_database(),
+                window.server_id,
+                alter_database_script(&window),
+                disconnect,
+                after,
+            );
+        }
+    }
+
+    fn update_drop_database_window(&mut self, ctx: &egui::Context) {
+        if !self.drop_database_window.show {
+            return;
+        }
+
+        let mut review = false;
+
+        Modal::new(Id::new("drop_database_modal")).show(ctx, |ui| {
+            ui.set_width(360.0);
+
+            widgets::modal_label(ui, self.trans.drop_database());
+
+            let window = &mut self.drop_database_window;
+
+            ui.label(self.trans.drop_database_confirmation(&window.database_name));
+            ui.checkbox(&mut window.force, self.trans.drop_database_force())
+                .on_hover_text(self.trans.drop_database_force_hint());
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button(self.trans.review()).clicked() {
+                        review = true;
+                    }
+                    if ui.button(self.trans.close()).clicked() {
+                        window.show = false;
+                    }
+                });
+            });
+        });
+
+        if review {
+            let window = std::mem::take(&mut self.drop_database_window);
+            let sql = format!(
+                "DROP DATABASE {}{};",
+                quote_ident(&window.database_name),
+                if window.force { " WITH (FORCE)" } else { "" }
+            );
+
+            self.review_database_script(
+                self.trans.drop_database(),
+                window.server_id,
+                sql,
+                Some(&window.database_name),
+                structs::AfterScript::ReloadDatabases(window.server_id),
+            );
+        }
+    }
+
+    fn update_database_properties_window(&mut self, ctx: &egui::Context) {
+        if !self.database_properties_window.show {
+            return;
+        }
+
+        let properties = self.database_properties_window.properties.lock().unwrap().clone();
+
+        Modal::new(Id::new("database_properties_modal")).show(ctx, |ui| {
+            ui.set_width(360.0);
+
+            widgets::modal_label(ui, &self.database_properties_window.database_name);
+
+            match properties {
+                None => {
+                    ui.add(Spinner::new());
+                }
+                Some(Err(e)) => {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                }
+                Some(Ok(properties)) => {
+                    let yes_no = |value: bool| if value { self.trans.yes() } else { self.trans.no() };
+
+                    Grid::new("database_properties")
+                        .num_columns(2)
+                        .spacing([40.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            let rows = [
+                                (self.trans.owner(), properties.owner.clone()),
+                                (self.trans.size(), super::dashboard::format_bytes(properties.size as f64)),
+                                (self.trans.connections(), properties.connections.to_string()),
+                                (
+                                    self.trans.connection_limit(),
+                                    if properties.connection_limit < 0 {
+                                        self.trans.unlimited()
+                                    } else {
+                                        properties.connection_limit.to_string()
+                                    },
+                                ),
+                                (self.trans.encoding(), properties.encoding.clone()),
+                                (self.trans.collation(), properties.collation.clone()),
+                                (self.trans.ctype(), properties.ctype.clone()),
+                                (self.trans.tablespace(), properties.tablespace.clone()),
+                                (self.trans.allow_connections(), yes_no(properties.allow_connections)),
+                                (self.trans.is_template(), yes_no(properties.is_template)),
+                            ];
+
+                            for (name, value) in rows {
+                                ui.label(name);
+                                ui.label(value);
+                                ui.end_row();
+                            }
+                        });
+                }
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                if ui.button(self.trans.close()).clicked() {
+                    self.database_properties_window = structs::DatabasePropertiesWindow::default();
+                }
+            });
+        });
+    }
+}
@@ -0,0 +1,790 @@
+use super::Main;
+
+use crate::data::*;
+use crate::database;
+
+use egui::{
+    Button, Color32, ComboBox, DragValue, FontId, Pos2, Rect, RichText, Sense, Shape, Spinner, Stroke, Ui,
+    Vec2, epaint::CubicBezierShape,
+};
+use log::error;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+const FONT_SIZE: f32 = 12.0;
+/// Approximate advance of the monospace font at `FONT_SIZE`, used to size
+/// boxes the same way on the canvas and in exported files.
+const CHAR_WIDTH: f32 = 7.3;
+const HEADER_HEIGHT: f32 = 22.0;
+const ROW_HEIGHT: f32 = 18.0;
+const PADDING: f32 = 8.0;
+const LEVEL_GAP: f32 = 90.0;
+const TABLE_GAP: f32 = 30.0;
+const MIN_ZOOM: f32 = 0.2;
+const MAX_ZOOM: f32 = 4.0;
+
+fn column_label(column: &structs::ErColumn) -> String {
+    let marker = match (column.primary_key, column.foreign_key) {
+        (true, true) => "PF",
+        (true, false) => "PK",
+        (false, true) => "FK",
+        (false, false) => "  ",
+    };
+    format!("{} {}  {}", marker, column.name, column.data_type)
+}
+
+fn table_size(table: &structs::ErTable) -> Vec2 {
+    let chars = table
+        .columns
+        .iter()
+        .map(|column| column_label(column).chars().count())
+        .chain([table.name.chars().count()])
+        .max()
+        .unwrap_or_default();
+
+    Vec2::new(
+        chars as f32 * CHAR_WIDTH + PADDING * 2.0,
+        HEADER_HEIGHT + table.columns.len() as f32 * ROW_HEIGHT + PADDING / 2.0,
+    )
+}
+
+/// Vertical offset of the middle of a column row, or of the header when
+/// the column isn't found.
+fn row_offset(table: &structs::ErTable, column: Option<&String>) -> f32 {
+    match column.and_then(|column| table.columns.iter().position(|c| c.name == *column)) {
+        Some(index) => HEADER_HEIGHT + (index as f32 + 0.5) * ROW_HEIGHT,
+        None => HEADER_HEIGHT / 2.0,
+    }
+}
+
+/// Tables within `depth` foreign key hops of `focus`, in either direction.
+fn neighbourhood(model: &structs::ErModel, focus: &str, depth: usize) -> HashSet<String> {
+    let mut visible = HashSet::from([focus.to_string()]);
+    let mut queue = VecDeque::from([(focus.to_string(), 0)]);
+
+    while let Some((table, distance)) = queue.pop_front() {
+        if distance == depth {
+            continue;
+        }
+        for relation in model.relations.iter() {
+            let neighbour = if relation.table == table {
+                &relation.referenced_table
+            } else if relation.referenced_table == table {
+                &relation.table
+            } else {
+                continue;
+            };
+            if visible.insert(neighbour.clone()) {
+                queue.push_back((neighbour.clone(), distance + 1));
+            }
+        }
+    }
+
+    visible
+}
+
+fn visible_tables<'a>(model: &'a structs::ErModel, focus: Option<&str>, depth: usize) -> Vec<&'a structs::ErTable> {
+    let Some(focus) = focus else {
+        return model.tables.iter().collect();
+    };
+    let visible = neighbourhood(model, focus, depth);
+
+    model.tables.iter().filter(|table| visible.contains(&table.name)).collect()
+}
+
+fn visible_relations<'a>(
+    model: &'a structs::ErModel,
+    tables: &[&structs::ErTable],
+) -> Vec<&'a structs::ErRelation> {
+    let names: HashSet<&str> = tables.iter().map(|table| table.name.as_str()).collect();
+
+    model
+        .relations
+        .iter()
+        .filter(|relation| names.contains(relation.table.as_str()) && names.contains(relation.referenced_table.as_str()))
+        .collect()
+}
+
+/// Places tables in columns from left to right, so that referenced tables
+/// come before the tables referencing them, and orders each column by the
+/// average position of the neighbours in the columns before it.
+fn layout(tables: &[&structs::ErTable], relations: &[&structs::ErRelation]) -> HashMap<String, Pos2> {
+    let index: HashMap<&str, usize> = tables.iter().enumerate().map(|(i, table)| (table.name.as_str(), i)).collect();
+    let edges: Vec<(usize, usize)> = relations
+        .iter()
+        .filter(|relation| relation.table != relation.referenced_table)
+        .filter_map(|relation| Some((*index.get(relation.table.as_str())?, *index.get(relation.referenced_table.as_str())?)))
+        .collect();
+
+    // Cycles keep raising levels, so the relaxation stops after as many
+    // rounds as there are tables.
+    let mut levels = vec![0usize; tables.len()];
+    for _ in 0..tables.len() {
+        let mut changed = false;
+        for &(table, referenced) in edges.iter() {
+            if levels[table] < levels[referenced] + 1 {
+                levels[table] = levels[referenced] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut distinct: Vec<usize> = levels.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+
+    let mut columns: Vec<Vec<usize>> = vec![Vec::new(); distinct.len()];
+    for (table, level) in levels.iter().enumerate() {
+        columns[distinct.binary_search(level).unwrap()].push(table);
+    }
+
+    let mut order: HashMap<usize, f32> = HashMap::new();
+    for column in columns.iter_mut() {
+        let barycenters: Vec<f32> = column
+            .iter()
+            .enumerate()
+            .map(|(position, &table)| {
+                let neighbours: Vec<f32> = edges
+                    .iter()
+                    .filter_map(|&(from, to)| match (from == table, to == table) {
+                        (true, _) => order.get(&to),
+                        (_, true) => order.get(&from),
+                        _ => None,
+                    })
+                    .copied()
+                    .collect();
+
+                if neighbours.is_empty() {
+                    position as f32
+                } else {
+                    neighbours.iter().sum::<f32>() / neighbours.len() as f32
+                }
+            })
+            .collect();
+
+        let mut sorted: Vec<(usize, f32)> = column.iter().copied().zip(barycenters).collect();
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+        *column = sorted.into_iter().map(|(table, _)| table).collect();
+
+        for (position, &table) in column.iter().enumerate() {
+            order.insert(table, position as f32);
+        }
+    }
+
+    let mut positions = HashMap::new();
+    let mut x = 0.0;
+    for column in columns.iter() {
+        let mut y = 0.0;
+        let mut width: f32 = 0.0;
+        for &table in column.iter() {
+            let size = table_size(tables[table]);
+            positions.insert(tables[table].name.clone(), Pos2::new(x, y));
+            y += size.y + TABLE_GAP;
+            width = width.max(size.x);
+        }
+        x += width + LEVEL_GAP;
+    }
+
+    positions
+}
+
+/// Bounding box of the visible tables, in diagram coordinates.
+fn bounds(tables: &[&structs::ErTable], positions: &HashMap<String, Pos2>) -> Rect {
+    tables
+        .iter()
+        .filter_map(|table| Some(Rect::from_min_size(*positions.get(&table.name)?, table_size(table))))
+        .reduce(|a, b| a.union(b))
+        .unwrap_or(Rect::ZERO)
+}
+
+/// Start, end and outward directions of the edge of a foreign key, leaving
+/// the referencing column and entering the referenced one from the sides
+/// facing each other.
+fn edge(
+    tables: &[&structs::ErTable],
+    positions: &HashMap<String, Pos2>,
+    relation: &structs::ErRelation,
+) -> Option<(Pos2, f32, Pos2, f32)> {
+    let table = tables.iter().find(|table| table.name == relation.table)?;
+    let referenced = tables.iter().find(|table| table.name == relation.referenced_table)?;
+    let from = Rect::from_min_size(*positions.get(&table.name)?, table_size(table));
+    let to = Rect::from_min_size(*positions.get(&referenced.name)?, table_size(referenced));
+
+    let from_y = from.min.y + row_offset(table, relation.columns.first());
+    let to_y = to.min.y + row_offset(referenced, relation.referenced_columns.first());
+
+    Some(if from.max.x < to.min.x {
+        (Pos2::new(from.max.x, from_y), 1.0, Pos2::new(to.min.x, to_y), -1.0)
+    } else if to.max.x < from.min.x {
+        (Pos2::new(from.min.x, from_y), -1.0, Pos2::new(to.max.x, to_y), 1.0)
+    } else {
+        (Pos2::new(from.max.x, from_y), 1.0, Pos2::new(to.max.x, to_y), 1.0)
+    })
+}
+
+fn bezier_points(start: Pos2, start_direction: f32, end: Pos2, end_direction: f32, offset: f32) -> [Pos2; 4] {
+    [
+        start,
+        start + Vec2::new(start_direction * offset, 0.0),
+        end + Vec2::new(end_direction * offset, 0.0),
+        end,
+    ]
+}
+
+fn arrow_points(end: Pos2, end_direction: f32, size: f32) -> [Pos2; 3] {
+    [
+        end,
+        end + Vec2::new(end_direction * size, -size / 2.0),
+        end + Vec2::new(end_direction * size, size / 2.0),
+    ]
+}
+
+fn escape_xml(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn svg_script(model: &structs::ErModel, tables: &[&structs::ErTable], positions: &HashMap<String, Pos2>) -> String {
+    let bounds = bounds(tables, positions).expand(LEVEL_GAP / 2.0);
+    let shift = -bounds.min.to_vec2();
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{:.0}\" height=\"{:.0}\" font-family=\"monospace\" font-size=\"{}\">\n",
+        bounds.width(),
+        bounds.height(),
+        FONT_SIZE
+    );
+    svg.push_str("<rect width=\"100%\" height=\"100%\" fill=\"white\"/>\n");
+
+    for relation in visible_relations(model, tables) {
+        let Some((start, start_direction, end, end_direction)) = edge(tables, positions, relation) else {
+            continue;
+        };
+        let [p0, p1, p2, p3] = bezier_points(start + shift, start_direction, end + shift, end_direction, 40.0);
+        let [a0, a1, a2] = arrow_points(end + shift, end_direction, 8.0);
+
+        svg.push_str(&format!(
+            "<g><title>{}</title><path d=\"M {:.1} {:.1} C {:.1} {:.1}, {:.1} {:.1}, {:.1} {:.1}\" fill=\"none\" stroke=\"#666\"/>\
+            <polygon points=\"{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}\" fill=\"#666\"/></g>\n",
+            escape_xml(&relation.name),
+            p0.x, p0.y, p1.x, p1.y, p2.x, p2.y, p3.x, p3.y,
+            a0.x, a0.y, a1.x, a1.y, a2.x, a2.y
+        ));
+    }
+
+    for table in tables {
+        let Some(position) = positions.get(&table.name) else {
+            continue;
+        };
+        let position = *position + shift;
+        let size = table_size(table);
+
+        svg.push_str(&format!(
+            "<g><rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#fff\" stroke=\"#444\"/>\
+            <rect x=\"{:.1}\" y=\"{:.1}\" width=\"{:.1}\" height=\"{:.1}\" fill=\"#dde6f3\" stroke=\"#444\"/>\
+            <text x=\"{:.1}\" y=\"{:.1}\" font-weight=\"bold\">{}</text>\n",
+            position.x, position.y, size.x, size.y,
+            position.x, position.y, size.x, HEADER_HEIGHT,
+            position.x + PADDING, position.y + HEADER_HEIGHT / 2.0 + FONT_SIZE / 3.0,
+            escape_xml(&table.name)
+        ));
+        for (index, column) in table.columns.iter().enumerate() {
+            svg.push_str(&format!(
+                "<text x=\"{:.1}\" y=\"{:.1}\" xml:space=\"preserve\">{}</text>\n",
+                position.x + PADDING,
+                position.y + HEADER_HEIGHT + (index as f32 + 0.5) * ROW_HEIGHT + FONT_SIZE / 3.0,
+                escape_xml(&column_label(column))
+            ));
+        }
+        svg.push_str("</g>\n");
+    }
+
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// Graphviz source with a HTML-like label per table, leaving the layout
+/// to `dot`.
+fn dot_script(schema: &str, model: &structs::ErModel, tables: &[&structs::ErTable]) -> String {
+    let quote = |text: &str| format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""));
+    let mut dot = format!(
+        "digraph {} {{\n    rankdir=RL;\n    node [shape=plaintext, fontname=\"monospace\"];\n\n",
+        quote(schema)
+    );
+
+    for table in tables {
+        dot.push_str(&format!(
+            "    {} [label=<<table border=\"0\" cellborder=\"1\" cellspacing=\"0\">\n        <tr><td bgcolor=\"#dde6f3\"><b>{}</b></td></tr>\n",
+            quote(&table.name),
+            escape_xml(&table.name)
+        ));
+        for column in table.columns.iter() {
+            dot.push_str(&format!(
+                "        <tr><td port=\"{}\" align=\"left\">{}</td></tr>\n",
+                escape_xml(&column.name),
+                escape_xml(&column_label(column))
+            ));
+        }
+        dot.push_str("    </table>>];\n");
+    }
+
+    dot.push('\n');
+    for relation in visible_relations(model, tables) {
+        let port = |column: Option<&String>| column.map(|column| format!(":{}", quote(column))).unwrap_or_default();
+        dot.push_str(&format!(
+            "    {}{} -> {}{} [tooltip={}];\n",
+            quote(&relation.table),
+            port(relation.columns.first()),
+            quote(&relation.referenced_table),
+            port(relation.referenced_columns.first()),
+            quote(&relation.name)
+        ));
+    }
+
+    dot.push_str("}\n");
+    dot
+}
+
+/// Rasterises the output of `svg_script` at twice its size, with the
+/// monospace font of the canvas, so the whole diagram is in the image.
+fn render_png(svg: &str) -> Result<resvg::tiny_skia::Pixmap, String> {
+    use resvg::usvg::{TreeParsing, TreeTextToPath};
+
+    let mut fonts = resvg::usvg::fontdb::Database::new();
+    let font_definitions = egui::FontDefinitions::default();
+    for font in font_definitions.font_data.values() {
+        fonts.load_font_data(font.font.to_vec());
+    }
+    if let Some(family) = font_definitions.families[&egui::FontFamily::Monospace].first() {
+        fonts.set_monospace_family(family.as_str());
+    }
+
+    let mut tree = resvg::usvg::Tree::from_str(svg, &resvg::usvg::Options::default()).map_err(|e| e.to_string())?;
+    tree.convert_text(&fonts);
+    let tree = resvg::Tree::from_usvg(&tree);
+
+    let size = tree.size.to_int_size().scale_by(2.0).ok_or("The diagram is empty")?;
+    let mut pixmap = resvg::tiny_skia::Pixmap::new(size.width(), size.height()).ok_or("The diagram is empty")?;
+    tree.render(resvg::tiny_skia::Transform::from_scale(2.0, 2.0), &mut pixmap.as_mut());
+
+    Ok(pixmap)
+}
+
+impl Main<'_> {
+    pub(super) fn open_er_diagram(&mut self, server_id: uuid::Uuid, database_name: &str) {
+        if let Some(idx) = self.pages.pages.iter().position(|page| {
+            matches!(&page.page_type, structs::PageType::ErDiagram(er_diagram_page)
+                if er_diagram_page.server_id == server_id && er_diagram_page.database_name == database_name)
+        }) {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id) else {
+            return;
+        };
+        let Some(database) = self.loaded_database(server_id, database_name) else {
+            return;
+        };
+
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.er_diagram(), server.page_title(database_name)),
+            page_type: structs::PageType::ErDiagram(structs::ErDiagramPage {
+                server_id,
+                database_name: database_name.to_string(),
+                database: database.database,
+                schema: String::from("public"),
+                snapshot: Arc::new(Mutex::new(structs::ErDiagramSnapshot::default())),
+                model: structs::ErModel::default(),
+                positions: HashMap::new(),
+                pan: Vec2::ZERO,
+                zoom: 1.0,
+                focus: None,
+                depth: 1,
+                dragging: None,
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    async fn fetch_er_model(
+        database: database::Database,
+        schema: String,
+        snapshot: Arc<Mutex<structs::ErDiagramSnapshot>>,
+    ) {
+        let result = async {
+            let schemas = database.get_schemas().await?;
+            let model = database.get_er_model(&schema).await?;
+
+            Ok::<_, String>((schemas, model))
+        }
+        .await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok((schemas, model)) => {
+                snapshot.schemas = schemas;
+                snapshot.model = Some(model);
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error loading ER model: {}", e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+        snapshot.loaded = true;
+    }
+
+    fn refresh_er_model(runtime: &tokio::runtime::Runtime, er_diagram_page: &structs::ErDiagramPage) {
+        er_diagram_page.snapshot.lock().unwrap().loading = true;
+
+        let database = er_diagram_page.database.clone();
+        let schema = er_diagram_page.schema.clone();
+        let snapshot = er_diagram_page.snapshot.clone();
+
+        runtime.spawn(async move {
+            Self::fetch_er_model(database, schema, snapshot).await;
+        });
+    }
+
+    fn export_er_diagram(&mut self, page_index: usize, format: structs::DiagramFormat, path: std::path::PathBuf) {
+        let Some(structs::Page {
+            page_type: structs::PageType::ErDiagram(er_diagram_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let tables = visible_tables(
+            &er_diagram_page.model,
+            er_diagram_page.focus.as_deref(),
+            er_diagram_page.depth,
+        );
+        let svg = || svg_script(&er_diagram_page.model, &tables, &er_diagram_page.positions);
+        let write = |content: String| std::fs::write(&path, content).map_err(|e| e.to_string());
+        let result = match format {
+            structs::DiagramFormat::Svg => write(svg()),
+            structs::DiagramFormat::Dot => write(dot_script(&er_diagram_page.schema, &er_diagram_page.model, &tables)),
+            structs::DiagramFormat::Png => {
+                render_png(&svg()).and_then(|pixmap| pixmap.save_png(&path).map_err(|e| e.to_string()))
+            }
+        };
+
+        if let Err(e) = result {
+            self.show_error(e);
+        }
+    }
+
+    pub(super) fn update_er_diagram_page(&mut self, ui: &mut Ui, page_index: usize) {
+        self.diagram_file_dialog.update(ui.ctx());
+
+        if let Some((export_index, format)) = self.diagram_file_dialog_action
+            && let Some(path) = self.diagram_file_dialog.take_picked()
+        {
+            self.diagram_file_dialog_action = None;
+            self.export_er_diagram(export_index, format, path);
+        }
+
+        let Some(structs::Page {
+            page_type: structs::PageType::ErDiagram(er_diagram_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let mut fit = false;
+        let snapshot = {
+            let mut snapshot = er_diagram_page.snapshot.lock().unwrap();
+            if let Some(model) = snapshot.model.take() {
+                er_diagram_page.model = model;
+                if er_diagram_page
+                    .focus
+                    .as_ref()
+                    .is_some_and(|focus| !er_diagram_page.model.tables.iter().any(|table| table.name == *focus))
+                {
+                    er_diagram_page.focus = None;
+                }
+                er_diagram_page.positions.clear();
+            }
+            snapshot.clone()
+        };
+        let mut refresh = !snapshot.loaded && !snapshot.loading;
+        let mut relayout = false;
+        let mut export = None;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!snapshot.loading, Button::new(self.trans.refresh())).clicked() {
+                refresh = true;
+            }
+            if snapshot.loading {
+                ui.add(Spinner::new());
+            }
+
+            ui.separator();
+
+            ui.label(self.trans.schema());
+            ComboBox::from_id_salt(("er_diagram_schema", page_index))
+                .selected_text(&er_diagram_page.schema)
+                .show_ui(ui, |ui| {
+                    for schema in snapshot.schemas.iter() {
+                        if ui
+                            .selectable_label(er_diagram_page.schema == *schema, schema)
+                            .clicked()
+                            && er_diagram_page.schema != *schema
+                        {
+                            er_diagram_page.schema = schema.clone();
+                            er_diagram_page.focus = None;
+                            refresh = true;
+                        }
+                    }
+                });
+
+            ui.label(self.trans.table());
+            ComboBox::from_id_salt(("er_diagram_focus", page_index))
+                .selected_text(er_diagram_page.focus.clone().unwrap_or_else(|| self.trans.all_tables()))
+                .show_ui(ui, |ui| {
+                    if ui
+                        .selectable_label(er_diagram_page.focus.is_none(), self.trans.all_tables())
+                        .clicked()
+                        && er_diagram_page.focus.is_some()
+                    {
+                        er_diagram_page.focus = None;
+                        relayout = true;
+                    }
+                    for table in er_diagram_page.model.tables.iter() {
+                        let selected = er_diagram_page.focus.as_ref() == Some(&table.name);
+                        if ui.selectable_label(selected, &table.name).clicked() && !selected {
+                            er_diagram_page.focus = Some(table.name.clone());
+                            relayout = true;
+                        }
+                    }
+                });
+
+            ui.add_enabled_ui(er_diagram_page.focus.is_some(), |ui| {
+                ui.label(self.trans.depth());
+                if ui.add(DragValue::new(&mut er_diagram_page.depth).range(1..=5)).changed() {
+                    relayout = true;
+                }
+            });
+
+            ui.separator();
+
+            if ui.button(self.trans.auto_layout()).clicked() {
+                relayout = true;
+            }
+            if ui.button(self.trans.fit_to_view()).clicked() {
+                fit = true;
+            }
+
+            ui.menu_button(self.trans.export(), |ui| {
+                for format in structs::DiagramFormat::ALL {
+                    if ui.button(format.extension().to_uppercase()).clicked() {
+                        export = Some(format);
+                        ui.close_menu();
+                    }
+                }
+            });
+        });
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        if refresh && !snapshot.loading {
+            Self::refresh_er_model(&self.runtime, er_diagram_page);
+        }
+
+        let tables = visible_tables(
+            &er_diagram_page.model,
+            er_diagram_page.focus.as_deref(),
+            er_diagram_page.depth,
+        );
+        let relations = visible_relations(&er_diagram_page.model, &tables);
+
+        if relayout || tables.iter().any(|table| !er_diagram_page.positions.contains_key(&table.name)) {
+            er_diagram_page.positions = layout(&tables, &relations);
+            fit = true;
+        }
+
+        let (rect, response) = ui.allocate_exact_size(ui.available_size().max(Vec2::splat(200.0)), Sense::click_and_drag());
+
+        if fit {
+            let bounds = bounds(&tables, &er_diagram_page.positions).expand(PADDING * 2.0);
+            er_diagram_page.zoom = (rect.width() / bounds.width())
+                .min(rect.height() / bounds.height())
+                .clamp(MIN_ZOOM, 1.0);
+            er_diagram_page.pan = rect.size() / 2.0 - bounds.center().to_vec2() * er_diagram_page.zoom;
+        }
+
+        let zoom = er_diagram_page.zoom;
+        let pan = er_diagram_page.pan;
+        let to_screen = |position: Pos2| rect.min + pan + position.to_vec2() * zoom;
+
+        if response.drag_started() {
+            er_diagram_page.dragging = response.interact_pointer_pos().and_then(|pointer| {
+                tables
+                    .iter()
+                    .rev()
+                    .find(|table| {
+                        er_diagram_page.positions.get(&table.name).is_some_and(|position| {
+                            Rect::from_min_size(to_screen(*position), table_size(table) * zoom).contains(pointer)
+                        })
+                    })
+                    .map(|table| table.name.clone())
+            });
+        }
+        if response.dragged() {
+            let delta = response.drag_delta();
+            match er_diagram_page
+                .dragging
+                .as_ref()
+                .and_then(|table| er_diagram_page.positions.get_mut(table))
+            {
+                Some(position) => *position += delta / zoom,
+                None => er_diagram_page.pan += delta,
+            }
+        }
+        if response.drag_stopped() {
+            er_diagram_page.dragging = None;
+        }
+
+        if let Some(pointer) = response.hover_pos() {
+            let (scroll, zoom_delta) = ui.input(|i| (i.smooth_scroll_delta.y, i.zoom_delta()));
+            let factor = zoom_delta * (scroll * 0.002).exp();
+            if factor != 1.0 {
+                // Keep the point under the pointer in place.
+                let anchor = (pointer - rect.min - er_diagram_page.pan) / zoom;
+                er_diagram_page.zoom = (zoom * factor).clamp(MIN_ZOOM, MAX_ZOOM);
+                er_diagram_page.pan = pointer - rect.min - anchor * er_diagram_page.zoom;
+            }
+        }
+
+        let zoom = er_diagram_page.zoom;
+        let pan = er_diagram_page.pan;
+        let to_screen = |position: Pos2| rect.min + pan + position.to_vec2() * zoom;
+
+        let visuals = ui.visuals().clone();
+        let painter = ui.painter_at(rect);
+        painter.rect_filled(rect, 0.0, visuals.extreme_bg_color);
+
+        let edge_color = visuals.weak_text_color();
+        for relation in relations.iter() {
+            let Some((start, start_direction, end, end_direction)) =
+                edge(&tables, &er_diagram_page.positions, relation)
+            else {
+                continue;
+            };
+            let start = to_screen(start);
+            let end = to_screen(end);
+
+            painter.add(CubicBezierShape::from_points_stroke(
+                bezier_points(start, start_direction, end, end_direction, 40.0 * zoom),
+                false,
+                Color32::TRANSPARENT,
+                Stroke::new(1.0, edge_color),
+            ));
+            painter.add(Shape::convex_polygon(
+                arrow_points(end, end_direction, 8.0 * zoom).to_vec(),
+                edge_color,
+                Stroke::NONE,
+            ));
+        }
+
+        let font = FontId::monospace(FONT_SIZE * zoom);
+        let stroke = Stroke::new(1.0, visuals.widgets.noninteractive.fg_stroke.color);
+        for table in tables.iter() {
+            let Some(position) = er_diagram_page.positions.get(&table.name) else {
+                continue;
+            };
+            let table_rect = Rect::from_min_size(to_screen(*position), table_size(table) * zoom);
+            let header_rect = Rect::from_min_size(table_rect.min, Vec2::new(table_rect.width(), HEADER_HEIGHT * zoom));
+            let header_color = if er_diagram_page.focus.as_ref() == Some(&table.name) {
+                visuals.selection.bg_fill
+            } else {
+                visuals.faint_bg_color
+            };
+
+            painter.rect_filled(table_rect, 2.0, visuals.window_fill);
+            painter.rect_filled(header_rect, 2.0, header_color);
+            painter.rect_stroke(table_rect, 2.0, stroke, egui::StrokeKind::Inside);
+            painter.text(
+                header_rect.left_center() + Vec2::new(PADDING * zoom, 0.0),
+                egui::Align2::LEFT_CENTER,
+                &table.name,
+                font.clone(),
+                visuals.strong_text_color(),
+            );
+
+            for (index, column) in table.columns.iter().enumerate() {
+                painter.text(
+                    table_rect.min + Vec2::new(PADDING, HEADER_HEIGHT + (index as f32 + 0.5) * ROW_HEIGHT) * zoom,
+                    egui::Align2::LEFT_CENTER,
+                    column_label(column),
+                    font.clone(),
+                    visuals.text_color(),
+                );
+            }
+        }
+
+        if let Some(format) = export {
+            let Some(structs::Page {
+                page_type: structs::PageType::ErDiagram(er_diagram_page),
+                ..
+            }) = self.pages.pages.get(page_index)
+            else {
+                return;
+            };
+
+            self.diagram_file_dialog_action = Some((page_index, format));
+            self.diagram_file_dialog = egui_file_dialog::FileDialog::new()
+                .default_file_name(&format!("{}.{}", er_diagram_page.schema, format.extension()));
+            self.diagram_file_dialog.save_file();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_whole_diagram_to_png() {
+        let table = |name: &str| structs::ErTable {
+            name: name.to_string(),
+            columns: vec![structs::ErColumn {
+                name: String::from("id"),
+                data_type: String::from("integer"),
+                primary_key: true,
+                foreign_key: false,
+            }],
+        };
+        let model = structs::ErModel {
+            tables: vec![table("near"), table("far")],
+            relations: Vec::new(),
+        };
+        let tables: Vec<&structs::ErTable> = model.tables.iter().collect();
+        let positions = HashMap::from([
+            (String::from("near"), Pos2::ZERO),
+            (String::from("far"), Pos2::new(3000.0, 2000.0)),
+        ]);
+
+        let pixmap = render_png(&svg_script(&model, &tables, &positions)).unwrap();
+        let bounds = bounds(&tables, &positions).expand(LEVEL_GAP / 2.0);
+        assert_eq!(pixmap.width(), (bounds.width() * 2.0).round() as u32);
+        assert_eq!(pixmap.height(), (bounds.height() * 2.0).round() as u32);
+
+        // The header text of the far table is drawn in dark pixels.
+        let far = (*positions.get("far").unwrap() - bounds.min + Vec2::new(PADDING, 0.0)) * 2.0;
+        let header = (far.y as u32 + 8..(far.y + HEADER_HEIGHT * 2.0) as u32 - 8)
+            .flat_map(|y| (far.x as u32..(far.x + 60.0) as u32).map(move |x| (x, y)));
+        assert!(header.filter_map(|(x, y)| pixmap.pixel(x, y)).any(|pixel| pixel.red() < 100));
+    }
+}
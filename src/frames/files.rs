@@ -0,0 +1,252 @@
+use super::{Main, widgets};
+
+use crate::data::structs;
+
+use egui::{Align, Button, Color32, Id, Layout, Modal, RichText, ViewportCommand};
+use std::fs as std_fs;
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, Instant, SystemTime};
+
+const FILE_CHECK_INTERVAL: Duration = Duration::from_secs(2);
+const RECENT_FILES_LIMIT: usize = 10;
+
+pub(super) fn file_modified(path: impl AsRef<Path>) -> Option<SystemTime> {
+    std_fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+impl Main<'_> {
+    pub(super) fn save_code(sqlquery_page: &mut structs::SQLQueryPage) -> Result<(), String> {
+        if !sqlquery_page.code.ends_with("\n") {
+            sqlquery_page.code = format!("{}\n", sqlquery_page.code);
+        }
+
+        let code_file_path = sqlquery_page.code_file_path.clone().unwrap();
+
+        std_fs::write(&code_file_path, sqlquery_page.code.as_bytes())
+            .map_err(|e| format!("{}: {}", code_file_path, e))?;
+
+        sqlquery_page.saved_code = sqlquery_page.code.clone();
+        sqlquery_page.file_modified = file_modified(&code_file_path);
+        sqlquery_page.file_changed = false;
+
+        Ok(())
+    }
+
+    pub(super) fn load_code(
+        sqlquery_page: &mut structs::SQLQueryPage,
+        code_file_path: String,
+    ) -> Result<(), String> {
+        let code = std_fs::read_to_string(&code_file_path)
+            .map_err(|e| format!("{}: {}", code_file_path, e))?;
+
+        sqlquery_page.file_modified = file_modified(&code_file_path);
+        sqlquery_page.code_file_path = Some(code_file_path);
+        sqlquery_page.saved_code = code.clone();
+        sqlquery_page.code = code;
+        sqlquery_page.file_changed = false;
+
+        Ok(())
+    }
+
+    pub(super) fn show_error(&mut self, message: impl ToString) {
+        self.error_window = structs::ErrorWindow::with_message(message);
+    }
+
+    /// Opens a file into the current page, or into a new page bound to the
+    /// same database when the current one has unsaved edits.
+    pub(super) fn open_file(&mut self, code_file_path: String) {
+        let current_page_index = self.pages.current_page_index as usize;
+        let Some(page) = self.pages.pages.get_mut(current_page_index) else {
+            return;
+        };
+        let structs::PageType::SQLQuery(sqlquery_page) = &mut page.page_type else {
+            return;
+        };
+
+        let result = if sqlquery_page.is_dirty() {
+            let mut new_page = structs::SQLQueryPage {
+                code: String::new(),
+                code_file_path: None,
+                sql_query_execution_status: None,
+                output_is_empty: true,
+                update_page_index: None,
+                cursor: None,
+                restore_cursor: false,
+                notices: Arc::default(),
+                ..sqlquery_page.clone()
+            };
+            let title = page.title.clone();
+
+            Self::load_code(&mut new_page, code_file_path.clone()).map(|_| {
+                self.pages.pages.push(structs::Page {
+                    title,
+                    page_type: structs::PageType::SQLQuery(new_page),
+                    ..Default::default()
+                });
+                self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+            })
+        } else {
+            Self::load_code(sqlquery_page, code_file_path.clone())
+        };
+
+        match result {
+            Ok(()) => self.add_recent_file(code_file_path),
+            Err(e) => self.show_error(e),
+        }
+    }
+
+    pub(super) fn add_recent_file(&mut self, code_file_path: String) {
+        self.config.recent_files.retain(|path| path != &code_file_path);
+        self.config.recent_files.insert(0, code_file_path);
+        self.config.recent_files.truncate(RECENT_FILES_LIMIT);
+
+        self.save_config();
+    }
+
+    /// Reloads the current page when its file changed on disk, or flags it
+    /// when there are local edits that a reload would discard.
+    pub(super) fn check_file_changes(&mut self) {
+        if self.file_checked_at.elapsed() < FILE_CHECK_INTERVAL {
+            return;
+        }
+        self.file_checked_at = Instant::now();
+
+        let Some(page) = self.pages.pages.get_mut(self.pages.current_page_index as usize) else {
+            return;
+        };
+        let structs::PageType::SQLQuery(sqlquery_page) = &mut page.page_type else {
+            return;
+        };
+        let Some(code_file_path) = sqlquery_page.code_file_path.clone() else {
+            return;
+        };
+
+        let modified = file_modified(&code_file_path);
+        if modified.is_none() || modified == sqlquery_page.file_modified || sqlquery_page.file_changed {
+            return;
+        }
+
+        if sqlquery_page.is_dirty() {
+            sqlquery_page.file_changed = true;
+        } else if let Err(e) = Self::load_code(sqlquery_page, code_file_path) {
+            self.show_error(e);
+        }
+    }
+
+    pub(super) fn close_page(&mut self, idx: usize) {
+        if idx < self.pages.pages.len() {
+            self.pages.pages.remove(idx);
+            if self.pages.pages.is_empty() {
+                self.pages = structs::Pages::default();
+            } else if self.pages.current_page_index as usize >= self.pages.pages.len() {
+                self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+            }
+        }
+    }
+
+    fn save_page(&mut self, idx: usize) -> bool {
+        if let Some(page) = self.pages.pages.get_mut(idx)
+            && let structs::PageType::SQLQuery(sqlquery_page) = &mut page.page_type
+            && sqlquery_page.is_dirty()
+            && let Err(e) = Self::save_code(sqlquery_page)
+        {
+            self.show_error(e);
+            return false;
+        }
+
+        true
+    }
+
+    /// Holds the window open while file-backed pages have unsaved edits.
+    pub(super) fn confirm_app_close(&mut self, ctx: &egui::Context) {
+        if ctx.input(|i| i.viewport().close_requested())
+            && !self.allow_close
+            && self.pages.pages.iter().any(|page| page.is_dirty())
+        {
+            ctx.send_viewport_cmd(ViewportCommand::CancelClose);
+
+            self.unsaved_changes_window = structs::UnsavedChangesWindow {
+                show: true,
+                page_index: None,
+            };
+        }
+    }
+
+    pub(super) fn update_file_windows(&mut self, ctx: &egui::Context) {
+        if self.unsaved_changes_window.show {
+            let page_index = self.unsaved_changes_window.page_index;
+
+            Modal::new(Id::new("unsaved_changes_modal")).show(ctx, |ui| {
+                widgets::modal_label(ui, self.trans.unsaved_changes());
+
+                ui.label(self.trans.unsaved_changes_confirmation());
+                ui.add_space(4.0);
+
+                for (idx, page) in self.pages.pages.iter().enumerate() {
+                    if page.is_dirty() && page_index.is_none_or(|page_index| page_index == idx) {
+                        ui.label(RichText::new(format!("● {}", page.title)).strong());
+                    }
+                }
+
+                ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        if ui.button(self.trans.save()).clicked() {
+                            let saved = match page_index {
+                                Some(idx) => self.save_page(idx),
+                                None => (0..self.pages.pages.len()).all(|idx| self.save_page(idx)),
+                            };
+
+                            if saved {
+                                self.unsaved_changes_window = structs::UnsavedChangesWindow::default();
+
+                                match page_index {
+                                    Some(idx) => self.close_page(idx),
+                                    None => {
+                                        self.allow_close = true;
+                                        ctx.send_viewport_cmd(ViewportCommand::Close);
+                                    }
+                                }
+                            }
+                        }
+                        if ui
+                            .button(RichText::new(self.trans.dont_save()).color(Color32::RED))
+                            .clicked()
+                        {
+                            self.unsaved_changes_window = structs::UnsavedChangesWindow::default();
+
+                            match page_index {
+                                Some(idx) => self.close_page(idx),
+                                None => {
+                                    self.allow_close = true;
+                                    ctx.send_viewport_cmd(ViewportCommand::Close);
+                                }
+                            }
+                        }
+                        if ui.button(self.trans.cancel()).clicked() {
+                            self.unsaved_changes_window = structs::UnsavedChangesWindow::default();
+                        }
+                    });
+                });
+            });
+        }
+
+        if self.error_window.show {
+            Modal::new(Id::new("error_modal")).show(ctx, |ui| {
+                widgets::modal_label(ui, self.trans.error());
+
+                ui.label(RichText::new(self.error_window.message.clone().unwrap_or_default()).color(Color32::RED));
+
+                ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                    ui.separator();
+
+                    if ui.add(Button::new(self.trans.close())).clicked() {
+                        self.error_window = structs::ErrorWindow::default();
+                    }
+                });
+            });
+        }
+    }
+}
@@ -0,0 +1,412 @@
+use super::{Main, dashboard, table_designer, widgets};
+
+use crate::data::*;
+use crate::database;
+use crate::utils::quote_ident;
+
+use egui::{
+    Align, Button, Color32, ComboBox, Grid, Id, Label, Layout, Modal, RichText, ScrollArea, Spinner,
+    TextEdit, Ui,
+};
+use egui_extras::{Column, TableBuilder};
+use log::error;
+use std::sync::{Arc, Mutex};
+
+fn create_index_script(window: &structs::CreateIndexWindow) -> String {
+    let mut statement = String::from("CREATE ");
+    if window.unique {
+        statement.push_str("UNIQUE ");
+    }
+    statement.push_str("INDEX ");
+    if window.concurrently {
+        statement.push_str("CONCURRENTLY ");
+    }
+    if !window.name.trim().is_empty() {
+        statement.push_str(&format!("{} ", quote_ident(window.name.trim())));
+    }
+    statement.push_str(&format!(
+        "ON {}.{} USING {} ({})",
+        quote_ident(&window.schema),
+        quote_ident(&window.table),
+        window.method,
+        window.columns.trim()
+    ));
+    if !window.include.trim().is_empty() {
+        statement.push_str(&format!(" INCLUDE ({})", window.include.trim()));
+    }
+    if !window.predicate.trim().is_empty() {
+        statement.push_str(&format!(" WHERE {}", window.predicate.trim()));
+    }
+    statement.push(';');
+
+    statement
+}
+
+impl Main<'_> {
+    pub(super) fn open_indexes_page(&mut self, server_id: uuid::Uuid, database_name: &str, table: &str) {
+        if let Some(idx) = self.pages.pages.iter().position(|page| {
+            matches!(&page.page_type, structs::PageType::Indexes(indexes_page)
+                if indexes_page.server_id == server_id
+                    && indexes_page.database_name == database_name
+                    && indexes_page.table == table)
+        }) {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id) else {
+            return;
+        };
+        let Some(database) = self.loaded_database(server_id, database_name) else {
+            return;
+        };
+
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.indexes_of(table), server.page_title(database_name)),
+            page_type: structs::PageType::Indexes(structs::IndexesPage {
+                server_id,
+                database_name: database_name.to_string(),
+                database: database.database,
+                table: table.to_string(),
+                snapshot: Arc::new(Mutex::new(structs::IndexesSnapshot::default())),
+                concurrently: false,
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    async fn fetch_indexes(
+        database: database::Database,
+        table: String,
+        snapshot: Arc<Mutex<structs::IndexesSnapshot>>,
+    ) {
+        let result = database.get_indexes(&table).await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok((schema, columns, indexes)) => {
+                snapshot.schema = schema;
+                snapshot.columns = columns;
+                snapshot.indexes = indexes;
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error loading indexes of {}: {}", table, e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+        snapshot.loaded = true;
+    }
+
+    fn refresh_indexes(runtime: &tokio::runtime::Runtime, indexes_page: &structs::IndexesPage) {
+        indexes_page.snapshot.lock().unwrap().loading = true;
+
+        let database = indexes_page.database.clone();
+        let table = indexes_page.table.clone();
+        let snapshot = indexes_page.snapshot.clone();
+
+        runtime.spawn(async move {
+            Self::fetch_indexes(database, table, snapshot).await;
+        });
+    }
+
+    pub(super) fn update_indexes_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::Indexes(indexes_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = indexes_page.snapshot.lock().unwrap().clone();
+        let mut refresh = !snapshot.loaded && !snapshot.loading;
+        let mut create = false;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!snapshot.loading, Button::new(self.trans.refresh())).clicked() {
+                refresh = true;
+            }
+            if snapshot.loading {
+                ui.add(Spinner::new());
+            }
+
+            ui.separator();
+
+            if ui
+                .add_enabled(!snapshot.schema.is_empty(), Button::new(self.trans.create_index()))
+                .clicked()
+            {
+                create = true;
+            }
+            ui.checkbox(&mut indexes_page.concurrently, "CONCURRENTLY")
+                .on_hover_text(self.trans.concurrently_hint());
+        });
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        ui.separator();
+
+        if refresh && !snapshot.loading {
+            Self::refresh_indexes(&self.runtime, indexes_page);
+        }
+
+        let qualified_table = format!("{}.{}", quote_ident(&snapshot.schema), quote_ident(&indexes_page.table));
+        let concurrently = if indexes_page.concurrently { "CONCURRENTLY " } else { "" };
+        let mut script: Option<(String, String)> = None;
+
+        let available_height = ui.available_height();
+        let available_width = ui.available_width();
+
+        ScrollArea::horizontal()
+            .auto_shrink([false, false])
+            .max_width(available_width)
+            .max_height(available_height)
+            .show(ui, |ui| {
+                TableBuilder::new(ui)
+                    .id_salt(("indexes_table", page_index))
+                    .striped(true)
+                    .auto_shrink([false, false])
+                    .column(Column::auto())
+                    .columns(Column::auto().resizable(true), 7)
+                    .column(Column::remainder().resizable(true))
+                    .header(16.0, |mut header| {
+                        for title in [
+                            String::new(),
+                            self.trans.name(),
+                            self.trans.size(),
+                            self.trans.scans(),
+                            self.trans.tuples_read(),
+                            self.trans.tuples_fetched(),
+                            self.trans.bloat_estimate(),
+                            self.trans.hints(),
+                            self.trans.definition(),
+                        ] {
+                            header.col(|ui| {
+                                ui.label(RichText::new(title).strong());
+                            });
+                        }
+                    })
+                    .body(|mut body| {
+                        for index in snapshot.indexes.iter() {
+                            let qualified_index = format!("{}.{}", quote_ident(&snapshot.schema), quote_ident(&index.name));
+
+                            body.row(18.0, |mut row| {
+                                row.col(|ui| {
+                                    if ui.small_button(self.trans.reindex()).clicked() {
+                                        script = Some((
+                                            self.trans.reindex(),
+                                            format!("REINDEX INDEX {}{};", concurrently, qualified_index),
+                                        ));
+                                    }
+                                    if ui.small_button(self.trans.delete()).clicked() {
+                                        let sql = match &index.constraint {
+                                            Some(constraint) => format!(
+                                                "ALTER TABLE {} DROP CONSTRAINT {};",
+                                                qualified_table,
+                                                quote_ident(constraint)
+                                            ),
+                                            None => format!("DROP INDEX {}{};", concurrently, qualified_index),
+                                        };
+                                        script = Some((self.trans.drop_index(), sql));
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.label(if index.primary {
+                                        format!("{} (PRIMARY KEY)", index.name)
+                                    } else if index.unique {
+                                        format!("{} (UNIQUE)", index.name)
+                                    } else {
+                                        index.name.clone()
+                                    });
+                                });
+                                row.col(|ui| {
+                                    ui.label(dashboard::format_bytes(index.size as f64));
+                                });
+                                for value in [index.scans, index.tuples_read, index.tuples_fetched] {
+                                    row.col(|ui| {
+                                        ui.label(value.to_string());
+                                    });
+                                }
+                                row.col(|ui| {
+                                    ui.label(match index.bloat {
+                                        Some(bloat) => dashboard::format_bytes(bloat as f64),
+                                        None => String::from("-"),
+                                    });
+                                });
+                                row.col(|ui| {
+                                    let mut hints = Vec::new();
+                                    if !index.valid {
+                                        hints.push(self.trans.invalid_index());
+                                    }
+                                    if let Some(duplicate_of) = &index.duplicate_of {
+                                        hints.push(self.trans.duplicate_of(duplicate_of));
+                                    }
+                                    if let Some(covered_by) = &index.covered_by {
+                                        hints.push(self.trans.covered_by(covered_by));
+                                    }
+                                    if index.is_unused() {
+                                        hints.push(self.trans.unused_index());
+                                    }
+                                    if !hints.is_empty() {
+                                        ui.label(RichText::new(hints.join("; ")).color(Color32::ORANGE));
+                                    }
+                                });
+                                row.col(|ui| {
+                                    ui.add(
+                                        Label::new(RichText::new(&index.definition).monospace())
+                                            .wrap_mode(egui::TextWrapMode::Truncate),
+                                    )
+                                    .on_hover_text(&index.definition);
+                                });
+                            });
+                        }
+                    });
+            });
+
+        if create {
+            self.create_index_window = structs::CreateIndexWindow {
+                show: true,
+                page_index,
+                schema: snapshot.schema.clone(),
+                table: indexes_page.table.clone(),
+                available_columns: snapshot.columns.clone(),
+                method: String::from("btree"),
+                concurrently: indexes_page.concurrently,
+                ..Default::default()
+            };
+        }
+
+        if let Some((title, sql)) = script {
+            let server_id = indexes_page.server_id;
+            let database_name = indexes_page.database_name.clone();
+
+            self.open_script_window(
+                title,
+                server_id,
+                &database_name,
+                sql,
+                None,
+                structs::AfterScript::ReloadIndexes(page_index),
+            );
+        }
+    }
+
+    pub(super) fn update_create_index_window(&mut self, ctx: &egui::Context) {
+        if !self.create_index_window.show {
+            return;
+        }
+
+        let mut review = false;
+
+        Modal::new(Id::new("create_index_modal")).show(ctx, |ui| {
+            ui.set_width(460.0);
+
+            widgets::modal_label(ui, self.trans.create_index());
+
+            let input_color = self.config.settings.theme.text_input_color();
+            let window = &mut self.create_index_window;
+
+            Grid::new("create_index_form")
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(self.trans.table());
+                    ui.label(format!("{}.{}", window.schema, window.table));
+                    ui.end_row();
+
+                    ui.label(self.trans.name());
+                    TextEdit::singleline(&mut window.name)
+                        .background_color(input_color)
+                        .show(ui);
+                    ui.end_row();
+
+                    ui.label(self.trans.method());
+                    ComboBox::from_id_salt("create_index_method")
+                        .selected_text(&window.method)
+                        .show_ui(ui, |ui| {
+                            for method in table_designer::INDEX_METHODS {
+                                ui.selectable_value(&mut window.method, method.to_string(), method);
+                            }
+                        });
+                    ui.end_row();
+
+                    ui.label(self.trans.columns());
+                    ui.horizontal(|ui| {
+                        TextEdit::singleline(&mut window.columns)
+                            .background_color(input_color)
+                            .hint_text("col, lower(other)")
+                            .show(ui);
+                        table_designer::column_picker(ui, &mut window.columns, &window.available_columns, true);
+                    });
+                    ui.end_row();
+
+                    ui.label("INCLUDE");
+                    ui.horizontal(|ui| {
+                        TextEdit::singleline(&mut window.include)
+                            .background_color(input_color)
+                            .show(ui);
+                        table_designer::column_picker(ui, &mut window.include, &window.available_columns, true);
+                    });
+                    ui.end_row();
+
+                    ui.label("WHERE");
+                    TextEdit::singleline(&mut window.predicate)
+                        .background_color(input_color)
+                        .show(ui);
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+
+            ui.horizontal(|ui| {
+                ui.checkbox(&mut window.unique, "UNIQUE");
+                ui.checkbox(&mut window.concurrently, "CONCURRENTLY")
+                    .on_hover_text(self.trans.concurrently_hint());
+            });
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui
+                        .add_enabled(!window.columns.trim().is_empty(), Button::new(self.trans.review()))
+                        .clicked()
+                    {
+                        review = true;
+                    }
+                    if ui.button(self.trans.close()).clicked() {
+                        window.show = false;
+                    }
+                });
+            });
+        });
+
+        if review {
+            let window = std::mem::take(&mut self.create_index_window);
+            let Some(structs::Page {
+                page_type: structs::PageType::Indexes(indexes_page),
+                ..
+            }) = self.pages.pages.get(window.page_index)
+            else {
+                return;
+            };
+            let server_id = indexes_page.server_id;
+            let database_name = indexes_page.database_name.clone();
+
+            self.open_script_window(
+                self.trans.create_index(),
+                server_id,
+                &database_name,
+                create_index_script(&window),
+                None,
+                structs::AfterScript::ReloadIndexes(window.page_index),
+            );
+        }
+    }
+}
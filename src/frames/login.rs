@@ -0,0 +1,571 @@
+use super::Main;
+
+use crate::data::structs;
+use crate::secrets;
+use crate::utils;
+use crate::vault;
+
+use log::{error, info, warn};
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use zeroize::Zeroize;
+
+impl Main<'_> {
+    /// Returns `None` until the master password has been entered.
+    pub(super) fn secret_store(&self) -> Option<Arc<dyn secrets::SecretStore>> {
+        self.vault_key
+            .as_ref()
+            .map(|vault_key| secrets::open(self.config.settings.secrets_backend, vault_key, &self.keyring))
+    }
+
+    pub(super) fn unlock(&mut self, password: &str) -> Result<(), String> {
+        let Some(vault) = &self.config.vault else {
+            return self.migrate_vault(password);
+        };
+
+        let vault_key = vault
+            .unlock(password)
+            .map_err(|_| self.trans.incorrect_password_hash_mismatch())?;
+        let needs_upgrade = vault.needs_upgrade();
+
+        self.load_secrets(vault_key)?;
+
+        if needs_upgrade {
+            info!("Upgrading vault to version {}", vault::VAULT_VERSION);
+
+            if let Err(e) = self.replace_vault(password, false) {
+                error!("Error upgrading vault: {}", e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Hides the login window and reopens the pages that were open when the
+    /// app was locked, or the saved session on first login.
+    pub(super) fn finish_login(&mut self) {
+        self.login_window.password.zeroize();
+        self.login_window.new_password.zeroize();
+        self.login_window = structs::LoginWindow {
+            show: false,
+            ..Default::default()
+        };
+        self.last_activity = Instant::now();
+
+        match self.locked_session.take() {
+            Some(session) => self.apply_session(session),
+            None if self.config.settings.restore_session => self.restore_session(),
+            None => {}
+        }
+    }
+
+    /// Returns to the login window, wiping decrypted passwords and the vault
+    /// key from memory and closing pools. Open pages are kept as a session
+    /// and reopened after the next login.
+    pub(super) fn lock(&mut self) {
+        info!("Locking the vault");
+
+        if self.config.settings.restore_session {
+            self.save_session();
+        }
+        self.locked_session = Some(self.collect_session());
+
+        self.pages = structs::Pages::default();
+        self.db_manager.dbs.lock().unwrap().clear();
+        self.db_manager.roles.lock().unwrap().clear();
+
+        for server in self.config.servers.iter_mut() {
+            server.password.zeroize();
+        }
+        self.add_server_window.password_field.zeroize();
+        self.edit_server_window.password_field.zeroize();
+        self.change_password_window.old_password.zeroize();
+        self.change_password_window.new_password.zeroize();
+        self.change_password_window.confirm_password.zeroize();
+        self.recovery_key_window.recovery_key.zeroize();
+
+        self.add_server_window = structs::AddServerWindow::default();
+        self.edit_server_window = structs::EditServerWindow::default();
+        self.delete_server_window = structs::DeleteServerWindow::default();
+        self.change_password_window = structs::ChangePasswordWindow::default();
+        self.recovery_key_window = structs::RecoveryKeyWindow::default();
+        self.settings_window = structs::SettingsWindow::default();
+        self.sql_response_copy_window = structs::SQLResponseCopyWindow::default();
+        self.folder_window = structs::FolderWindow::default();
+        self.unsaved_changes_window = structs::UnsavedChangesWindow::default();
+        self.export_servers_window = structs::ExportServersWindow::default();
+        self.import_servers_window = structs::ImportServersWindow::default();
+        self.backend_action_window = structs::BackendActionWindow::default();
+        self.explain_window = structs::ExplainWindow::default();
+        self.script_window = structs::ScriptWindow::default();
+        self.role_window = structs::RoleWindow::default();
+        self.create_index_window = structs::CreateIndexWindow::default();
+        self.create_database_window = structs::CreateDatabaseWindow::default();
+        self.alter_database_window = structs::AlterDatabaseWindow::default();
+        self.drop_database_window = structs::DropDatabaseWindow::default();
+        self.backup_window = structs::BackupWindow::default();
+        self.maintenance_window = structs::MaintenanceWindow::default();
+        self.database_properties_window = structs::DatabasePropertiesWindow::default();
+
+        self.vault_key = None;
+        self.clear_stored_passwords();
+        self.missing_passwords.clear();
+        self.load_config();
+        self.login_window = structs::LoginWindow::default();
+    }
+
+    pub(super) fn check_auto_lock(&mut self, ctx: &egui::Context) {
+        if self.login_window.show {
+            return;
+        }
+
+        if ctx.input(|i| !i.events.is_empty() || i.pointer.is_moving()) {
+            self.last_activity = Instant::now();
+        }
+
+        if self.config.settings.auto_lock_minutes == 0 {
+            return;
+        }
+
+        let timeout = Duration::from_secs(self.config.settings.auto_lock_minutes as u64 * 60);
+        let idle = self.last_activity.elapsed();
+
+        if idle >= timeout {
+            self.lock();
+        } else {
+            ctx.request_repaint_after(timeout - idle);
+        }
+    }
+
+    /// Opens the vault with the recovery key and sets a new master password.
+    pub(super) fn unlock_with_recovery_key(
+        &mut self,
+        recovery_key: &str,
+        new_password: &str,
+    ) -> Result<(), String> {
+        let vault_key = self
+            .config
+            .vault
+            .as_ref()
+            .ok_or_else(|| self.trans.incorrect_recovery_key())?
+            .unlock_with_recovery_key(recovery_key)
+            .map_err(|_| self.trans.incorrect_recovery_key())?;
+
+        let servers = self.config.servers.clone();
+        self.load_secrets(vault_key)?;
+
+        if let Err(e) = self.replace_vault(new_password, true) {
+            self.config.servers = servers;
+            self.vault_key = None;
+
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    fn load_secrets(&mut self, vault_key: vault::VaultKey) -> Result<(), String> {
+        let secret_store = secrets::open(self.config.settings.secrets_backend, &vault_key, &self.keyring);
+
+        let mut servers = self.config.servers.clone();
+        let mut stored_passwords = HashMap::new();
+        let mut missing_passwords = HashSet::new();
+        for server in servers.iter_mut() {
+            let password = match secret_store
+                .load(server.id, &server.password)
+                .map_err(|e| format!("{}: {}", server.alias, e))?
+            {
+                Some(password) => password,
+                None => {
+                    warn!("No password is stored for server {}", server.alias);
+                    missing_passwords.insert(server.id);
+                    String::new()
+                }
+            };
+
+            let stored = std::mem::replace(&mut server.password, password.clone());
+            stored_passwords.insert(server.id, (password, stored));
+        }
+
+        self.config.servers = servers;
+        self.stored_passwords = stored_passwords;
+        self.missing_passwords = missing_passwords;
+        self.vault_key = Some(vault_key);
+
+        Ok(())
+    }
+
+    fn clear_stored_passwords(&mut self) {
+        for (password, _) in self.stored_passwords.values_mut() {
+            password.zeroize();
+        }
+        self.stored_passwords.clear();
+    }
+
+    /// Creates a vault with a fresh key for `password` and re-encrypts every
+    /// secret with it. If the config can't be written, the old vault and key
+    /// stay in place both in memory and on disk.
+    pub(super) fn replace_vault(
+        &mut self,
+        password: &str,
+        with_recovery_key: bool,
+    ) -> Result<(), String> {
+        let (mut vault, vault_key) = vault::Vault::create(password)?;
+        let recovery_key = if with_recovery_key {
+            Some(vault.add_recovery_key(&vault_key)?)
+        } else {
+            None
+        };
+
+        let old_vault = self.config.vault.replace(vault);
+        let old_vault_key = self.vault_key.replace(vault_key);
+        // Values encrypted with the old key have to be written again.
+        self.clear_stored_passwords();
+
+        if let Err(e) = self.try_save_config() {
+            self.config.vault = old_vault;
+            self.vault_key = old_vault_key;
+            self.clear_stored_passwords();
+
+            return Err(e);
+        }
+
+        if let Some(recovery_key) = recovery_key {
+            self.recovery_key_window = structs::RecoveryKeyWindow {
+                show: true,
+                recovery_key,
+            };
+        }
+
+        Ok(())
+    }
+
+    pub(super) fn change_master_password(
+        &mut self,
+        old_password: &str,
+        new_password: &str,
+    ) -> Result<(), String> {
+        let Some(vault) = &self.config.vault else {
+            return Err(self.trans.incorrect_password_hash_mismatch());
+        };
+
+        vault
+            .unlock(old_password)
+            .map_err(|_| self.trans.incorrect_password_hash_mismatch())?;
+        let with_recovery_key = vault.recovery.is_some();
+
+        self.replace_vault(new_password, with_recovery_key)
+    }
+
+    pub(super) fn generate_recovery_key(&mut self) {
+        let (Some(vault), Some(vault_key)) = (&self.config.vault, &self.vault_key) else {
+            return;
+        };
+
+        let mut vault = vault.clone();
+        let recovery_key = match vault.add_recovery_key(vault_key) {
+            Ok(recovery_key) => recovery_key,
+            Err(e) => {
+                self.show_error(e);
+                return;
+            }
+        };
+
+        let old_vault = self.config.vault.replace(vault);
+
+        match self.try_save_config() {
+            Ok(()) => {
+                self.recovery_key_window = structs::RecoveryKeyWindow {
+                    show: true,
+                    recovery_key,
+                };
+            }
+            Err(e) => {
+                self.config.vault = old_vault;
+                self.show_error(e);
+            }
+        }
+    }
+
+    /// Moves every server password to another backend, removing it from the
+    /// old one only after the config has been written with the new one.
+    /// Passwords missing from the old backend are moved as empty ones.
+    pub(super) fn switch_secrets_backend(&mut self, secrets_backend: secrets::SecretsBackend) {
+        let old_secret_store = self.secret_store();
+        let old_backend = self.config.settings.secrets_backend;
+
+        self.config.settings.secrets_backend = secrets_backend;
+        self.clear_stored_passwords();
+        self.missing_passwords.clear();
+
+        if let Err(e) = self.try_save_config() {
+            self.config.settings.secrets_backend = old_backend;
+            self.clear_stored_passwords();
+            self.save_config();
+            self.show_error(e);
+
+            return;
+        }
+
+        if let Some(old_secret_store) = old_secret_store {
+            for server in self.config.servers.iter() {
+                if let Err(e) = old_secret_store.delete(server.id) {
+                    error!("Error removing password of server {}: {}", server.alias, e);
+                }
+            }
+        }
+    }
+
+    /// Moves configs from the unsalted SHA-256 hash and per-password PBKDF2
+    /// format to the vault. Also used to create the vault on first login.
+    fn migrate_vault(&mut self, password: &str) -> Result<(), String> {
+        let is_new = self.config.password_hash.is_none();
+
+        if let Some(password_hash) = &self.config.password_hash
+            && utils::create_checksum(password) != *password_hash
+        {
+            return Err(self.trans.incorrect_password_hash_mismatch());
+        }
+
+        let mut servers = self.config.servers.clone();
+        for server in servers.iter_mut() {
+            server.password = utils::decrypt_string(&server.password, password)
+                .map_err(|e| format!("Incorrect password: {}", e))?;
+        }
+
+        if !is_new {
+            info!("Migrating config to vault version {}", vault::VAULT_VERSION);
+        }
+
+        let old_servers = std::mem::replace(&mut self.config.servers, servers);
+        let password_hash = self.config.password_hash.take();
+
+        if let Err(e) = self.replace_vault(password, is_new && self.login_window.generate_recovery_key) {
+            self.config.servers = old_servers;
+            self.config.password_hash = password_hash;
+
+            return Err(e);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::config::ConfigStore;
+    use crate::secrets::SecretStore;
+    use crate::secrets::tests::MockStore;
+    use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+    use ring::rand::{SecureRandom, SystemRandom};
+    use ring::{aead, pbkdf2};
+    use std::num::NonZeroU32;
+    use std::path::PathBuf;
+    use uuid::Uuid;
+
+    /// A `Main` whose config lives in a fresh temporary directory, which is
+    /// removed when the returned guard is dropped. It never touches the real
+    /// keychain.
+    fn test_main() -> (Main<'static>, TempConfig) {
+        let dir = std::env::temp_dir().join(format!("rs-postgres-test-{}", Uuid::new_v4()));
+        let path = dir.join("config.json");
+
+        let mut main = Main::new(&egui::Context::default(), false, Some(path.clone()));
+        mock_keyring(&mut main);
+
+        (main, TempConfig { dir, path })
+    }
+
+    /// Gives `main` an empty in-memory keychain and returns it.
+    fn mock_keyring(main: &mut Main) -> Arc<MockStore> {
+        let keyring = Arc::new(MockStore::default());
+        main.keyring = keyring.clone();
+
+        keyring
+    }
+
+    struct TempConfig {
+        dir: PathBuf,
+        path: PathBuf,
+    }
+
+    impl Drop for TempConfig {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.dir);
+        }
+    }
+
+    fn test_server(password: &str) -> structs::Server {
+        serde_json::from_value(serde_json::json!({
+            "id": Uuid::new_v4(),
+            "alias": "test",
+            "ip": "127.0.0.1",
+            "port": 5432,
+            "user": "postgres",
+            "password": password,
+            "service_database": "postgres",
+        }))
+        .unwrap()
+    }
+
+    /// Encrypts like the pre-vault config did: PBKDF2 per password, then
+    /// length-prefixed salt, nonce and ciphertext.
+    fn legacy_encrypt(plain_text: &str, password: &str) -> String {
+        let mut salt = [0u8; 16];
+        let mut nonce = [0u8; 12];
+        SystemRandom::new().fill(&mut salt).unwrap();
+        SystemRandom::new().fill(&mut nonce).unwrap();
+
+        let mut key = [0u8; 32];
+        pbkdf2::derive(
+            pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(100_000).unwrap(),
+            &salt,
+            password.as_bytes(),
+            &mut key,
+        );
+
+        let mut ciphertext = plain_text.as_bytes().to_vec();
+        aead::LessSafeKey::new(aead::UnboundKey::new(&aead::AES_256_GCM, &key).unwrap())
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut ciphertext,
+            )
+            .unwrap();
+
+        let mut serialized = Vec::new();
+        for part in [&salt[..], &nonce[..], &ciphertext[..]] {
+            serialized.extend_from_slice(&(part.len() as u32).to_be_bytes());
+            serialized.extend_from_slice(part);
+        }
+
+        BASE64.encode(serialized)
+    }
+
+    #[test]
+    fn migrates_legacy_config() {
+        let (mut main, config) = test_main();
+        main.config.password_hash = Some(utils::create_checksum("master"));
+        main.config.servers = vec![test_server(&legacy_encrypt("server password", "master"))];
+
+        assert!(main.unlock("wrong").is_err());
+        assert!(main.config.vault.is_none());
+
+        main.unlock("master").unwrap();
+        assert!(main.config.password_hash.is_none());
+        assert_eq!(main.config.servers[0].password, "server password");
+
+        let saved = ConfigStore::new(Some(config.path.clone())).load().unwrap();
+        let vault = saved.vault.unwrap();
+        assert!(saved.password_hash.is_none());
+        assert!(!vault.needs_upgrade());
+        assert_eq!(
+            vault.unlock("master").unwrap().decrypt(&saved.servers[0].password).unwrap(),
+            "server password"
+        );
+    }
+
+    #[test]
+    fn upgrades_key_check_vault_on_unlock() {
+        let (mut main, config) = test_main();
+        let (vault, key) = vault::tests::key_check_vault("master");
+        main.config.vault = Some(vault);
+        main.config.servers = vec![test_server(&key.encrypt("server password").unwrap())];
+
+        main.unlock("master").unwrap();
+        assert_eq!(main.config.servers[0].password, "server password");
+
+        let saved = ConfigStore::new(Some(config.path.clone())).load().unwrap();
+        let vault = saved.vault.unwrap();
+        assert_eq!(vault.version, vault::VAULT_VERSION);
+        assert!(vault.key_check.is_none());
+        assert_eq!(
+            vault.unlock("master").unwrap().decrypt(&saved.servers[0].password).unwrap(),
+            "server password"
+        );
+    }
+
+    #[test]
+    fn loads_secrets_from_keyring() {
+        let (mut main, _config) = test_main();
+        let keyring = mock_keyring(&mut main);
+        let (vault, _) = vault::Vault::create("master").unwrap();
+        let (stored, missing) = (test_server(""), test_server(""));
+        keyring.store(stored.id, "server password").unwrap();
+
+        main.config.settings.secrets_backend = secrets::SecretsBackend::Keyring;
+        main.config.vault = Some(vault);
+        main.config.servers = vec![stored.clone(), missing.clone()];
+
+        main.unlock("master").unwrap();
+        assert_eq!(main.config.servers[0].password, "server password");
+        assert_eq!(main.config.servers[1].password, "");
+        assert!(!main.missing_passwords.contains(&stored.id));
+        assert!(main.missing_passwords.contains(&missing.id));
+
+        // Saving must not fill the missing entry with an empty password.
+        let writes = keyring.writes();
+        main.try_save_config().unwrap();
+        assert_eq!(keyring.writes(), writes);
+        assert_eq!(keyring.password(missing.id), None);
+    }
+
+    #[test]
+    fn stores_only_changed_passwords() {
+        let (mut main, config) = test_main();
+        let keyring = mock_keyring(&mut main);
+        let (vault, _) = vault::Vault::create("master").unwrap();
+        let (first, second) = (test_server(""), test_server(""));
+        keyring.store(first.id, "first").unwrap();
+        keyring.store(second.id, "second").unwrap();
+
+        main.config.settings.secrets_backend = secrets::SecretsBackend::Keyring;
+        main.config.vault = Some(vault);
+        main.config.servers = vec![first.clone(), second.clone()];
+        main.unlock("master").unwrap();
+
+        let writes = keyring.writes();
+        main.config.servers[1].password = String::from("changed");
+        main.try_save_config().unwrap();
+        main.try_save_config().unwrap();
+
+        assert_eq!(keyring.writes(), writes + 1);
+        assert_eq!(keyring.password(first.id).unwrap(), "first");
+        assert_eq!(keyring.password(second.id).unwrap(), "changed");
+
+        let saved = ConfigStore::new(Some(config.path.clone())).load().unwrap();
+        assert!(saved.servers.iter().all(|server| server.password.is_empty()));
+    }
+
+    #[test]
+    fn switches_secrets_backend() {
+        let (mut main, config) = test_main();
+        let keyring = mock_keyring(&mut main);
+        let (vault, vault_key) = vault::Vault::create("master").unwrap();
+        let server = test_server(&vault_key.encrypt("server password").unwrap());
+        main.config.vault = Some(vault);
+        main.config.servers = vec![server.clone()];
+        main.unlock("master").unwrap();
+
+        main.switch_secrets_backend(secrets::SecretsBackend::Keyring);
+        assert_eq!(keyring.password(server.id).unwrap(), "server password");
+
+        let saved = ConfigStore::new(Some(config.path.clone())).load().unwrap();
+        assert_eq!(saved.settings.secrets_backend, secrets::SecretsBackend::Keyring);
+        assert_eq!(saved.servers[0].password, "");
+
+        main.switch_secrets_backend(secrets::SecretsBackend::Vault);
+        assert_eq!(keyring.password(server.id), None);
+
+        let saved = ConfigStore::new(Some(config.path.clone())).load().unwrap();
+        assert_eq!(saved.settings.secrets_backend, secrets::SecretsBackend::Vault);
+        assert_eq!(
+            saved.vault.unwrap().unlock("master").unwrap().decrypt(&saved.servers[0].password).unwrap(),
+            "server password"
+        );
+    }
+}
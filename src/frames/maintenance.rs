@@ -0,0 +1,328 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+use crate::database;
+use crate::utils::quote_ident;
+
+use egui::{
+    Align, Button, Color32, ComboBox, Grid, Id, Layout, Modal, ProgressBar, RichText, ScrollArea, Spinner, TextEdit,
+};
+use log::error;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use uuid::Uuid;
+
+/// Builds the statement for `window`, run on `relation` or, without one, on
+/// the whole database.
+fn maintenance_sql(window: &structs::MaintenanceWindow, relation: Option<&str>) -> String {
+    let verbose = if window.verbose { " VERBOSE" } else { "" };
+
+    match window.command {
+        structs::MaintenanceCommand::Vacuum | structs::MaintenanceCommand::Analyze => {
+            let options: Vec<&str> = match window.command {
+                structs::MaintenanceCommand::Vacuum => [
+                    (window.full, "FULL"),
+                    (window.freeze, "FREEZE"),
+                    (window.verbose, "VERBOSE"),
+                    (window.analyze, "ANALYZE"),
+                ]
+                .into_iter()
+                .filter(|(enabled, _)| *enabled)
+                .map(|(_, option)| option)
+                .collect(),
+                _ => window.verbose.then_some("VERBOSE").into_iter().collect(),
+            };
+
+            let mut sql = window.command.keyword().to_string();
+            if !options.is_empty() {
+                sql.push_str(&format!(" ({})", options.join(", ")));
+            }
+            if let Some(relation) = relation {
+                sql.push_str(&format!(" {}", relation));
+            }
+            sql + ";"
+        }
+        structs::MaintenanceCommand::Reindex => format!(
+            "REINDEX{} {}{} {};",
+            if window.verbose { " (VERBOSE)" } else { "" },
+            if relation.is_some() { "TABLE" } else { "DATABASE" },
+            if window.concurrently { " CONCURRENTLY" } else { "" },
+            relation.map_or_else(|| quote_ident(&window.database_name), str::to_string),
+        ),
+        structs::MaintenanceCommand::Cluster => match relation {
+            Some(relation) if !window.index.trim().is_empty() => {
+                format!("CLUSTER{} {} USING {};", verbose, relation, quote_ident(window.index.trim()))
+            }
+            Some(relation) => format!("CLUSTER{} {};", verbose, relation),
+            None => format!("CLUSTER{};", verbose),
+        },
+    }
+}
+
+impl Main<'_> {
+    pub(super) fn open_maintenance_window(&mut self, server_id: Uuid, database_name: &str, table: Option<String>) {
+        let server = self.config.servers.iter().find(|server| server.id == server_id);
+        // Progress names relations by OID, so it is read in the same database.
+        let monitor = server.map(|server| server.connect(database_name));
+        let database = self
+            .loaded_database(server_id, database_name)
+            .map(|database| database.database)
+            .or_else(|| server.map(|server| server.connect(database_name)));
+
+        let relation = Arc::new(Mutex::new(None));
+        if let (Some(database), Some(table)) = (database.clone(), table.clone()) {
+            let relation = relation.clone();
+            self.runtime.spawn(async move {
+                let result = database.get_table_key(None, &table).await.map(|(name, ..)| name);
+                *relation.lock().unwrap() = Some(result);
+            });
+        }
+
+        self.maintenance_window = structs::MaintenanceWindow {
+            show: true,
+            database_name: database_name.to_string(),
+            database,
+            monitor,
+            table,
+            relation,
+            verbose: true,
+            ..Default::default()
+        };
+    }
+
+    fn start_maintenance(&mut self, sql: String) {
+        let (Some(database), Some(monitor)) =
+            (self.maintenance_window.database.clone(), self.maintenance_window.monitor.clone())
+        else {
+            return;
+        };
+
+        let process = Arc::new(Mutex::new(structs::MaintenanceProcess {
+            notices: Vec::new(),
+            progress: None,
+            pid: None,
+            started_at: Instant::now(),
+            finished: None,
+        }));
+        self.maintenance_window.process = Some(process.clone());
+
+        let sink_process = process.clone();
+        let sink: database::NoticeSink = Arc::new(move |notice| sink_process.lock().unwrap().notices.push(notice));
+        let run_process = process.clone();
+        self.runtime.spawn(async move {
+            let pid_process = run_process.clone();
+            let result = database::with_notices(
+                sink,
+                database.run_maintenance(&sql, move |pid| pid_process.lock().unwrap().pid = Some(pid)),
+            )
+            .await;
+            if let Err(e) = &result {
+                error!("Error running maintenance: {}", e);
+            }
+
+            let mut process = run_process.lock().unwrap();
+            let elapsed = process.started_at.elapsed();
+            process.finished = Some((result, elapsed));
+        });
+
+        self.runtime.spawn(async move {
+            loop {
+                let (pid, finished) = {
+                    let process = process.lock().unwrap();
+                    (process.pid, process.finished.is_some())
+                };
+                if finished {
+                    break;
+                }
+
+                if let Some(pid) = pid
+                    && let Ok(progress) = monitor.get_maintenance_progress(pid).await
+                {
+                    process.lock().unwrap().progress = progress;
+                }
+                tokio::time::sleep(Duration::from_millis(500)).await;
+            }
+        });
+    }
+
+    pub(super) fn update_maintenance_window(&mut self, ctx: &egui::Context) {
+        if !self.maintenance_window.show {
+            return;
+        }
+
+        let relation = self.maintenance_window.relation.lock().unwrap().clone();
+        let (notices, progress, pid, finished) = match &self.maintenance_window.process {
+            Some(process) => {
+                let process = process.lock().unwrap();
+                (
+                    Some(process.notices.clone()),
+                    process.progress.clone(),
+                    process.pid,
+                    Some(process.finished.clone()),
+                )
+            }
+            None => (None, None, None, None),
+        };
+        let running = matches!(finished, Some(None));
+        if running {
+            ctx.request_repaint_after(Duration::from_millis(250));
+        }
+
+        let mut start = None;
+        let mut cancel = false;
+
+        Modal::new(Id::new("maintenance_modal")).show(ctx, |ui| {
+            ui.set_width(560.0);
+
+            widgets::modal_label(ui, self.trans.maintenance());
+
+            let input_color = self.config.settings.theme.text_input_color();
+            let window = &mut self.maintenance_window;
+
+            ui.add_enabled_ui(!running, |ui| {
+                Grid::new("maintenance_form")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(self.trans.database());
+                        ui.label(&window.database_name);
+                        ui.end_row();
+
+                        if let Some(table) = &window.table {
+                            ui.label(self.trans.table());
+                            ui.label(table);
+                            ui.end_row();
+                        }
+
+                        ui.label(self.trans.command());
+                        ComboBox::from_id_salt("maintenance_command")
+                            .selected_text(window.command.keyword())
+                            .show_ui(ui, |ui| {
+                                for command in structs::MaintenanceCommand::ALL {
+                                    ui.selectable_value(&mut window.command, command, command.keyword());
+                                }
+                            });
+                        ui.end_row();
+
+                        if window.command == structs::MaintenanceCommand::Cluster && window.table.is_some() {
+                            ui.label(self.trans.index());
+                            TextEdit::singleline(&mut window.index)
+                                .background_color(input_color)
+                                .hint_text(self.trans.cluster_index_hint())
+                                .show(ui);
+                            ui.end_row();
+                        }
+                    });
+
+                ui.add_space(4.0);
+                ui.horizontal_wrapped(|ui| {
+                    if window.command == structs::MaintenanceCommand::Vacuum {
+                        ui.checkbox(&mut window.full, "FULL");
+                        ui.checkbox(&mut window.freeze, "FREEZE");
+                        ui.checkbox(&mut window.analyze, "ANALYZE");
+                    }
+                    if window.command == structs::MaintenanceCommand::Reindex {
+                        ui.checkbox(&mut window.concurrently, "CONCURRENTLY")
+                            .on_hover_text(self.trans.concurrently_hint());
+                    }
+                    ui.checkbox(&mut window.verbose, "VERBOSE");
+                });
+            });
+
+            let sql = match (&window.table, &relation) {
+                (None, _) => Some(maintenance_sql(window, None)),
+                (Some(_), Some(Ok(relation))) => Some(maintenance_sql(window, Some(relation))),
+                (Some(_), Some(Err(e))) => {
+                    ui.label(RichText::new(e).color(Color32::RED));
+                    None
+                }
+                (Some(_), None) => {
+                    ui.add(Spinner::new());
+                    None
+                }
+            };
+            if let Some(sql) = &sql {
+                ui.add_space(4.0);
+                ui.label(RichText::new(sql).monospace());
+            }
+
+            if running && let Some(progress) = &progress {
+                let fraction = if progress.total > 0 {
+                    progress.done as f32 / progress.total as f32
+                } else {
+                    0.0
+                };
+                ui.add_space(4.0);
+                ui.add(ProgressBar::new(fraction).text(format!("{}: {}", progress.relation, progress.phase)));
+            }
+
+            if let Some(notices) = &notices {
+                ui.add_space(8.0);
+
+                let mut text = notices
+                    .iter()
+                    .map(|notice| format!("{}:  {}", notice.severity, notice.message))
+                    .collect::<Vec<_>>()
+                    .join("\n");
+                ScrollArea::vertical()
+                    .max_height(240.0)
+                    .stick_to_bottom(true)
+                    .show(ui, |ui| {
+                        TextEdit::multiline(&mut text)
+                            .font(egui::TextStyle::Monospace)
+                            .desired_width(f32::INFINITY)
+                            .background_color(input_color)
+                            .interactive(false)
+                            .show(ui);
+                    });
+            }
+
+            if let Some(Some((result, elapsed))) = &finished {
+                match result {
+                    Ok(()) => ui.label(
+                        RichText::new(self.trans.finished_successfully(format!("{:.1}", elapsed.as_secs_f32())))
+                            .color(Color32::GREEN),
+                    ),
+                    Err(e) => ui.label(RichText::new(e).color(Color32::RED)),
+                };
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if running {
+                        ui.add(Spinner::new());
+
+                        if ui.add_enabled(pid.is_some(), Button::new(self.trans.cancel())).clicked() {
+                            cancel = true;
+                        }
+                    }
+
+                    let can_start = !running && sql.is_some() && window.database.is_some();
+                    if ui.add_enabled(can_start, Button::new(self.trans.start())).clicked() {
+                        start = sql;
+                    }
+                    if ui.add_enabled(!running, Button::new(self.trans.close())).clicked() {
+                        *window = structs::MaintenanceWindow::default();
+                    }
+                });
+            });
+        });
+
+        if let Some(sql) = start {
+            self.start_maintenance(sql);
+        }
+        if cancel
+            && let Some(pid) = pid
+            && let Some(monitor) = self.maintenance_window.monitor.clone()
+        {
+            self.runtime.spawn(async move {
+                if let Err(e) = monitor.cancel_backend(pid).await {
+                    error!("Error cancelling maintenance: {}", e);
+                }
+            });
+        }
+    }
+}
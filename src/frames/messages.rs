@@ -0,0 +1,141 @@
+use egui::text::CCursor;
+use egui::text_edit::TextEditOutput;
+use egui::{Color32, Grid, Rect, RichText, ScrollArea, Spinner, Stroke, Ui, pos2};
+
+use crate::data::{structs, translates};
+
+/// One-based line and column of the character at `position` in `code`.
+fn line_column(code: &str, position: usize) -> (usize, usize) {
+    let before: Vec<char> = code.chars().take(position).collect();
+    let line = before.iter().filter(|c| **c == '\n').count() + 1;
+    let column = before.iter().rev().take_while(|c| **c != '\n').count() + 1;
+
+    (line, column)
+}
+
+/// Shows `error` with every field the server reported. Returns the error
+/// position in `code` when its location is clicked.
+pub fn query_error(
+    ui: &mut Ui,
+    trans: &translates::Translator,
+    error: &structs::QueryError,
+    code: &str,
+) -> Option<usize> {
+    let mut go_to = None;
+
+    ui.heading(&error.message);
+    ui.add_space(4.0);
+
+    let fields = [
+        ("SQLSTATE".to_string(), &error.code),
+        (trans.detail(), &error.detail),
+        (trans.hint(), &error.hint),
+        (trans.error_context(), &error.context),
+        (trans.schema(), &error.schema),
+        (trans.table(), &error.table),
+        (trans.column(), &error.column),
+        (trans.data_type(), &error.data_type),
+        (trans.constraint(), &error.constraint),
+    ];
+
+    Grid::new("query_error")
+        .num_columns(2)
+        .spacing([40.0, 4.0])
+        .striped(true)
+        .show(ui, |ui| {
+            if let Some(severity) = error.severity {
+                ui.label(trans.severity());
+                ui.label(RichText::new(severity).monospace());
+                ui.end_row();
+            }
+            for (label, value) in fields {
+                if let Some(value) = value {
+                    ui.label(label);
+                    ui.label(RichText::new(value).monospace());
+                    ui.end_row();
+                }
+            }
+            if let Some(position) = error.position {
+                let (line, column) = line_column(code, position);
+
+                ui.label(trans.position());
+                if ui.link(trans.line_column(line, column)).clicked() {
+                    go_to = Some(position);
+                }
+                ui.end_row();
+            }
+        });
+
+    go_to
+}
+
+/// The messages tab of a SQL page: server notices followed by the outcome
+/// of the query. Returns the error position when it is clicked.
+pub fn query_messages(
+    ui: &mut Ui,
+    trans: &translates::Translator,
+    notices: &[structs::ServerNotice],
+    status: &structs::SQLQueryExecutionStatusType,
+    code: &str,
+) -> Option<usize> {
+    let mut go_to = None;
+
+    ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        for notice in notices {
+            ui.label(RichText::new(format!("{}:  {}", notice.severity, notice.message)).monospace());
+        }
+        if !notices.is_empty() {
+            ui.separator();
+        }
+
+        match status {
+            structs::SQLQueryExecutionStatusType::Running => {
+                ui.horizontal(|ui| {
+                    ui.add(Spinner::new());
+                    ui.label(trans.running());
+                });
+            }
+            structs::SQLQueryExecutionStatusType::Success(result) => {
+                ui.horizontal(|ui| {
+                    ui.label(trans.success());
+                    ui.separator();
+                    ui.label(trans.rows(result.rows_count));
+                    ui.separator();
+                    ui.label(trans.time(result.execution_time));
+                });
+            }
+            structs::SQLQueryExecutionStatusType::Error(error) => {
+                go_to = query_error(ui, trans, error, code);
+            }
+        }
+    });
+
+    go_to
+}
+
+/// Marks the token the server pointed at in the code editor.
+pub fn highlight_error(ui: &Ui, output: &TextEditOutput, code: &str, position: usize) {
+    let length = code
+        .chars()
+        .skip(position)
+        .take_while(|c| c.is_alphanumeric() || *c == '_')
+        .count()
+        .max(1);
+
+    let start = output.galley.pos_from_ccursor(CCursor::new(position));
+    let end = output.galley.pos_from_ccursor(CCursor::new(position + length));
+
+    // Errors at the end of the input or tokens wrapped onto the next row
+    // get a one character wide mark.
+    let right = if end.min.y == start.min.y && end.min.x > start.min.x {
+        end.min.x
+    } else {
+        let font_id = egui::TextStyle::Monospace.resolve(ui.style());
+        start.min.x + ui.fonts(|fonts| fonts.glyph_width(&font_id, ' '))
+    };
+
+    let rect = Rect::from_min_max(start.min, pos2(right, start.max.y)).translate(output.galley_pos.to_vec2());
+    let painter = ui.painter_at(output.response.rect);
+    painter.rect_filled(rect, 0.0, Color32::from_rgba_unmultiplied(255, 0, 0, 48));
+    painter.line_segment([rect.left_bottom(), rect.right_bottom()], Stroke::new(1.5, Color32::RED));
+}
@@ -1,37 +1,62 @@
+mod activity;
+mod backup;
+mod dashboard;
+mod data_compare;
+mod databases;
 mod debug;
+mod er_diagram;
+mod files;
+mod indexes;
+mod login;
+mod maintenance;
+mod messages;
+mod privileges;
+mod roles;
+mod schema_diff;
+mod script;
+mod session;
+mod statements;
+mod table_designer;
+mod transfer;
+mod tree;
 mod widgets;
 
+use crate::config;
 use crate::data::*;
 use crate::database;
-use crate::utils;
+use crate::export;
 
-use crate::utils::{decrypt_string, encrypt_string};
+use crate::secrets::{self, SecretStore};
+use crate::vault;
 use eframe::{App, egui};
 use egui::TopBottomPanel;
 use egui::{
-    Align, Button, CentralPanel, CollapsingHeader, Color32, Grid, Id, Key, Label, Layout, Modal,
+    Align, Button, CentralPanel, CollapsingHeader, Color32, DragValue, Grid, Id, Key, Label, Layout, Modal,
     RichText, ScrollArea, Slider, Spinner, TextEdit,
 };
+use egui::text::{CCursor, CCursorRange};
+use egui::text_edit::TextEditState;
 use egui_extras::{Column, TableBuilder};
 use egui_file_dialog::FileDialog;
 use indexmap::IndexMap;
 use log::{error, info};
-use serde_json;
-use std::collections::HashMap;
-use std::fs as std_fs;
+use std::collections::{HashMap, HashSet};
 use std::fs::File;
-use std::io::Read;
-use std::io::Write;
+use std::path::PathBuf;
 use std::sync::{Arc, Mutex};
 use std::time::Instant;
+use uuid::Uuid;
 
 struct DbManager {
-    dbs: Arc<Mutex<HashMap<String, structs::DbState>>>,
+    dbs: Arc<Mutex<HashMap<Uuid, structs::DbState>>>,
+    roles: Arc<Mutex<HashMap<Uuid, structs::RolesState>>>,
 }
 
 pub struct Main<'a> {
     db_manager: DbManager,
     config: structs::Config,
+    config_store: config::ConfigStore,
+    config_error: Option<String>,
     add_server_window: structs::AddServerWindow,
     delete_server_window: structs::DeleteServerWindow,
     edit_server_window: structs::EditServerWindow,
@@ -39,30 +64,70 @@ pub struct Main<'a> {
     settings_window: structs::SettingsWindow,
     login_window: structs::LoginWindow,
     change_password_window: structs::ChangePasswordWindow,
+    folder_window: structs::FolderWindow,
+    unsaved_changes_window: structs::UnsavedChangesWindow,
+    error_window: structs::ErrorWindow,
+    backend_action_window: structs::BackendActionWindow,
+    explain_window: structs::ExplainWindow,
+    script_window: structs::ScriptWindow,
+    role_window: structs::RoleWindow,
+    create_index_window: structs::CreateIndexWindow,
+    create_database_window: structs::CreateDatabaseWindow,
+    alter_database_window: structs::AlterDatabaseWindow,
+    drop_database_window: structs::DropDatabaseWindow,
+    database_properties_window: structs::DatabasePropertiesWindow,
+    backup_window: structs::BackupWindow,
+    maintenance_window: structs::MaintenanceWindow,
+    recovery_key_window: structs::RecoveryKeyWindow,
+    export_servers_window: structs::ExportServersWindow,
+    import_servers_window: structs::ImportServersWindow,
     icons: structs::Icons<'a>,
     runtime: tokio::runtime::Runtime,
     pages: structs::Pages,
     actions: Vec<structs::Action>,
-    password: Option<String>,
+    vault_key: Option<vault::VaultKey>,
+    /// Store used for the keyring backend.
+    keyring: Arc<dyn SecretStore>,
+    /// Password of each server as last loaded from or written to the secret
+    /// store, with the value the config keeps for it.
+    stored_passwords: HashMap<Uuid, (String, String)>,
+    /// Servers the secret store had no password for at login.
+    missing_passwords: HashSet<Uuid>,
     select_file_dialog: FileDialog,
     select_file_dialog_action: Option<structs::SelectFileDialogAction>,
+    servers_file_dialog: FileDialog,
+    servers_file_dialog_action: Option<structs::ServersFileDialogAction>,
+    diagram_file_dialog: FileDialog,
+    /// Page index and format of a pending diagram export.
+    diagram_file_dialog_action: Option<(usize, structs::DiagramFormat)>,
+    backup_file_dialog: FileDialog,
     trans: translates::Translator,
+    tree_filter: String,
+    session_saved_at: Instant,
+    last_session: Option<structs::Session>,
+    locked_session: Option<structs::Session>,
+    last_activity: Instant,
+    file_checked_at: Instant,
+    allow_close: bool,
     frame_history: debug::FrameHistory,
     debug: bool,
 }
 
 impl Main<'_> {
-    pub fn new(ctx: &egui::Context, debug: bool) -> Self {
+    pub fn new(ctx: &egui::Context, debug: bool, config_path: Option<PathBuf>) -> Self {
         egui_extras::install_image_loaders(ctx);
 
         let dbs = Arc::new(Mutex::new(HashMap::new()));
-        let db_manager = DbManager { dbs };
+        let roles = Arc::new(Mutex::new(HashMap::new()));
+        let db_manager = DbManager { dbs, roles };
 
         let runtime = tokio::runtime::Runtime::new().unwrap();
 
         let mut main = Self {
             db_manager,
             config: structs::Config::default(),
+            config_store: config::ConfigStore::new(config_path),
+            config_error: None,
             add_server_window: structs::AddServerWindow::default(),
             delete_server_window: structs::DeleteServerWindow::default(),
             edit_server_window: structs::EditServerWindow::default(),
@@ -70,6 +135,23 @@ impl Main<'_> {
             login_window: structs::LoginWindow::default(),
             settings_window: structs::SettingsWindow::default(),
             change_password_window: structs::ChangePasswordWindow::default(),
+            folder_window: structs::FolderWindow::default(),
+            unsaved_changes_window: structs::UnsavedChangesWindow::default(),
+            error_window: structs::ErrorWindow::default(),
+            backend_action_window: structs::BackendActionWindow::default(),
+            explain_window: structs::ExplainWindow::default(),
+            script_window: structs::ScriptWindow::default(),
+            role_window: structs::RoleWindow::default(),
+            create_index_window: structs::CreateIndexWindow::default(),
+            create_database_window: structs::CreateDatabaseWindow::default(),
+            alter_database_window: structs::AlterDatabaseWindow::default(),
+            drop_database_window: structs::DropDatabaseWindow::default(),
+            database_properties_window: structs::DatabasePropertiesWindow::default(),
+            backup_window: structs::BackupWindow::default(),
+            maintenance_window: structs::MaintenanceWindow::default(),
+            recovery_key_window: structs::RecoveryKeyWindow::default(),
+            export_servers_window: structs::ExportServersWindow::default(),
+            import_servers_window: structs::ImportServersWindow::default(),
             icons: structs::Icons {
                 warning_light: egui::Image::new(icons::WARNING_LIGHT)
                     .bg_fill(Color32::TRANSPARENT)
@@ -84,10 +166,25 @@ impl Main<'_> {
             runtime,
             pages: structs::Pages::default(),
             actions: Vec::new(),
-            password: None,
+            vault_key: None,
+            keyring: Arc::new(secrets::KeyringStore),
+            stored_passwords: HashMap::new(),
+            missing_passwords: HashSet::new(),
             select_file_dialog: FileDialog::new(),
             select_file_dialog_action: None,
+            servers_file_dialog: FileDialog::new(),
+            servers_file_dialog_action: None,
+            diagram_file_dialog: FileDialog::new(),
+            diagram_file_dialog_action: None,
+            backup_file_dialog: FileDialog::new(),
             trans: translates::Translator::new(translates::Language::English),
+            tree_filter: String::new(),
+            session_saved_at: Instant::now(),
+            last_session: None,
+            locked_session: None,
+            last_activity: Instant::now(),
+            file_checked_at: Instant::now(),
+            allow_close: false,
             frame_history: debug::FrameHistory::default(),
             debug,
         };
@@ -97,162 +194,134 @@ impl Main<'_> {
     }
 
     fn load_config(&mut self) {
-        let config_dir = dirs::config_dir().unwrap().join("rs-postgres");
-        if !config_dir.exists() {
-            std_fs::create_dir_all(&config_dir).unwrap();
-        }
-
-        let config_path = config_dir.join("config.json");
-        if !config_path.exists() {
-            std_fs::write(
-                &config_path,
-                serde_json::to_string(&structs::Config::default()).unwrap(),
-            )
-            .unwrap();
+        match self.config_store.load() {
+            Ok(config) => {
+                self.trans.language = config.settings.language.clone();
+                self.config = config;
+                self.config_error = None;
+            }
+            Err(e) => {
+                error!("Error loading config: {}", e);
+                self.config_error = Some(e);
+            }
         }
+    }
 
-        let mut write_config = false;
+    /// Shown instead of the login window when the config can't be loaded.
+    fn update_config_error(&mut self, ctx: &egui::Context) {
+        CentralPanel::default().show(ctx, |ui| {
+            ui.vertical_centered(|ui| {
+                ui.add_space(64.0);
+                ui.heading(self.trans.config_error());
+                ui.label(self.config_store.path.display().to_string());
+                ui.add_space(8.0);
+                ui.label(
+                    RichText::new(self.config_error.clone().unwrap_or_default()).color(Color32::RED),
+                );
+                ui.add_space(16.0);
 
-        let config_file = std_fs::read_to_string(&config_path).unwrap();
-        let mut config = match serde_json::from_str::<structs::Config>(&config_file) {
-            Ok(config) => config,
-            Err(_) => {
-                let mut default_config = structs::Config::default();
-                if let Ok(mut partial_config) =
-                    serde_json::from_str::<serde_json::Value>(&config_file)
+                if ui.button(self.trans.retry()).clicked() {
+                    self.load_config();
+                }
+                if self.config_store.latest_backup().is_some()
+                    && ui.button(self.trans.restore_backup()).clicked()
                 {
-                    Self::merge_defaults(
-                        &mut partial_config,
-                        &serde_json::to_value(&default_config).unwrap(),
-                    );
-                    default_config =
-                        serde_json::from_value(partial_config).unwrap_or(default_config);
+                    match self.config_store.restore_latest_backup() {
+                        Ok(()) => self.load_config(),
+                        Err(e) => self.config_error = Some(e),
+                    }
                 }
-                write_config = true;
-                default_config
-            }
-        };
-
-        if !config.settings.theme.is_inited() {
-            config.settings.theme = structs::Theme::Dark;
-            write_config = true;
-        }
-
-        if write_config {
-            std_fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
-        }
-
-        self.trans.language = config.settings.language.clone();
-        self.config = config;
-    }
-
-    fn merge_defaults(current: &mut serde_json::Value, default: &serde_json::Value) {
-        match (current, default) {
-            (serde_json::Value::Object(current_map), serde_json::Value::Object(default_map)) => {
-                for (key, default_value) in default_map {
-                    if !current_map.contains_key(key) {
-                        current_map.insert(key.clone(), default_value.clone());
-                    } else {
-                        Self::merge_defaults(current_map.get_mut(key).unwrap(), default_value);
+                if ui
+                    .button(RichText::new(self.trans.reset_config()).color(Color32::RED))
+                    .clicked()
+                {
+                    match self.config_store.reset() {
+                        Ok(()) => self.load_config(),
+                        Err(e) => self.config_error = Some(e),
                     }
                 }
-            }
-            _ => {}
-        }
+                if ui.button(self.trans.quit()).clicked() {
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+            });
+        });
     }
 
     fn save_config(&mut self) {
-        let config_dir = dirs::config_dir().unwrap().join("rs-postgres");
-        let config_path = config_dir.join("config.json");
+        if let Err(e) = self.try_save_config() {
+            self.show_error(e);
+        }
+    }
 
+    fn try_save_config(&mut self) -> Result<(), String> {
         self.trans.language = self.config.settings.language.clone();
 
         let mut config = self.config.clone();
-        config.servers.iter_mut().for_each(|server| {
-            server.password =
-                encrypt_string(&server.password, self.password.as_ref().unwrap()).unwrap();
-        });
-
-        std_fs::write(&config_path, serde_json::to_string_pretty(&config).unwrap()).unwrap();
-    }
-
-    fn decrypt_passwords(&mut self) {
-        for server in self.config.servers.iter_mut() {
-            let encrypted_password = decrypt_string(
-                &server.password,
-                self.password.as_ref().unwrap_or(&"".to_string()),
-            );
-
-            match encrypted_password {
-                Ok(password) => {
-                    server.password = password;
+        if let Some(secret_store) = self.secret_store() {
+            for server in config.servers.iter_mut() {
+                // Only passwords changed since they were loaded or last
+                // stored are written, which also keeps a missing keyring
+                // entry from being replaced with an empty password.
+                if let Some((password, stored)) = self.stored_passwords.get(&server.id)
+                    && *password == server.password
+                {
+                    server.password = stored.clone();
+                    continue;
                 }
-                Err(e) => {
-                    self.login_window.error = Some(format!("Incorrect password: {}", e));
 
-                    return;
-                }
+                let stored = secret_store.store(server.id, &server.password)?;
+                self.stored_passwords
+                    .insert(server.id, (server.password.clone(), stored.clone()));
+                self.missing_passwords.remove(&server.id);
+                server.password = stored;
             }
         }
+
+        self.config_store.save(&config)
     }
 
     async fn load_db(
-        id: String,
         server: structs::Server,
-        dbs: Arc<Mutex<HashMap<String, structs::DbState>>>,
+        dbs: Arc<Mutex<HashMap<Uuid, structs::DbState>>>,
     ) {
         info!("Starting to load database for server {}", server.ip);
-        let database_url = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            server.user, server.password, server.ip, server.port, server.service_database
-        );
-        match database::Database::new(&database_url).await {
-            Ok(db) => {
+
+        let state = match server.connect(&server.service_database).get_databases().await {
+            Ok(databases_names) => {
                 info!("Database loaded for server {}", server.ip);
-                let databases_names = db.get_databases().await;
-
-                if let Ok(databases_names) = databases_names {
-                    let mut databases: Vec<structs::LoadedDatabase> = Vec::new();
-                    for name in databases_names {
-                        let database_url = format!(
-                            "postgres://{}:{}@{}:{}/{}",
-                            server.user, server.password, server.ip, server.port, name
-                        );
-                        let database = database::Database::new(&database_url).await;
-                        if let Ok(database) = database {
-                            let tables = database.get_tables().await;
-
-                            if let Ok(tables) = tables {
-                                databases.push(structs::LoadedDatabase {
-                                    name: name.clone(),
-                                    database,
-                                    tables,
-                                });
-                            } else if let Err(e) = tables {
-                                error!("Error loading tables for database {}: {}", name, e);
-                            }
-                        } else if let Err(e) = database {
-                            error!(
-                                "Error loading database for server {} ({}): {}",
-                                server.ip, name, e
-                            );
-                        }
-                    }
 
-                    let mut dbs = dbs.lock().unwrap();
-                    dbs.insert(id, structs::DbState::Loaded(databases));
-                } else if let Err(e) = databases_names {
-                    error!("Error loading database for server {}: {}", server.ip, e);
-                    let mut dbs = dbs.lock().unwrap();
-                    dbs.insert(id, structs::DbState::Error(e.to_string()));
-                }
+                let databases = databases_names
+                    .into_iter()
+                    .map(|name| structs::LoadedDatabase {
+                        database: server.connect(&name),
+                        name,
+                        tables: Arc::new(Mutex::new(None)),
+                    })
+                    .collect();
+
+                structs::DbState::Loaded(databases)
             }
             Err(e) => {
                 error!("Error loading database for server {}: {}", server.ip, e);
-                let mut dbs = dbs.lock().unwrap();
-                dbs.insert(id, structs::DbState::Error(e.to_string()));
+                structs::DbState::Error(e)
             }
-        }
+        };
+
+        let mut dbs = dbs.lock().unwrap();
+        dbs.insert(server.id, state);
+    }
+
+    async fn load_tables(database: structs::LoadedDatabase) {
+        let state = match database.database.get_tables().await {
+            Ok(tables) => structs::TablesState::Loaded(tables),
+            Err(e) => {
+                error!("Error loading tables for database {}: {}", database.name, e);
+                structs::TablesState::Error(e)
+            }
+        };
+
+        let mut tables = database.tables.lock().unwrap();
+        *tables = Some(state);
     }
 
     fn get_sql_query_slice(
@@ -284,10 +353,17 @@ impl Main<'_> {
     async fn fetch_sql_query(
         database: database::Database,
         code: &str,
+        read_only: bool,
         sql_query_execution_status: Option<Arc<Mutex<structs::SQLQueryExecutionStatusType>>>,
+        notices: Arc<Mutex<Vec<structs::ServerNotice>>>,
     ) {
+        let sink: database::NoticeSink = Arc::new(move |notice| notices.lock().unwrap().push(notice));
+
         let start_time = Instant::now();
-        let result = database.execute_query(&code).await;
+        let result = match read_only {
+            true => database::with_notices(sink, database.execute_query_read_only(code)).await,
+            false => database::with_notices(sink, database.execute_query(code)).await,
+        };
         let execution_time = start_time.elapsed().as_millis() as u64;
 
         let execution_status = match result {
@@ -312,13 +388,13 @@ impl Main<'_> {
                     } else {
                         result
                     }),
-                    pages_count: pages_count,
-                    rows_count: rows_count,
-                    execution_time: execution_time,
+                    pages_count,
+                    rows_count,
+                    execution_time,
                     page_index: 0,
                 })
             }
-            Err(e) => structs::SQLQueryExecutionStatusType::Error(e.to_string()),
+            Err(e) => structs::SQLQueryExecutionStatusType::Error(e),
         };
 
         if let Some(sql_query_execution_status) = sql_query_execution_status {
@@ -327,111 +403,149 @@ impl Main<'_> {
         }
     }
 
-    fn save_code(sqlquery_page: &mut structs::SQLQueryPage) {
-        if !sqlquery_page.code.ends_with("\n") {
-            sqlquery_page.code = format!("{}\n", sqlquery_page.code);
-        }
+    fn export_output_to_csv(
+        data: &IndexMap<String, Vec<structs::ValueType>>,
+        file_path: String,
+    ) -> Result<(), String> {
+        let file = File::create(&file_path).map_err(|e| format!("{}: {}", file_path, e))?;
 
-        let mut file = File::create(sqlquery_page.code_file_path.as_ref().unwrap()).unwrap();
-        file.write_all(sqlquery_page.code.as_bytes()).unwrap();
+        export::write_csv(data, file).map_err(|e| format!("{}: {}", file_path, e))
     }
 
-    fn export_output_to_csv(data: &IndexMap<String, Vec<structs::ValueType>>, file_path: String) {
-        let file = File::create(file_path).unwrap();
-        let mut wtr = csv::Writer::from_writer(file);
-
-        let headers: Vec<&str> = data.keys().map(|k| k.as_str()).collect();
-
-        wtr.write_record(&headers).unwrap();
-
-        if let Some(first_column) = data.values().next() {
-            let row_count = first_column.len();
-            for i in 0..row_count {
-                let mut record = Vec::with_capacity(data.len());
+    async fn reload_server(
+        server: structs::Server,
+        dbs: Arc<Mutex<HashMap<Uuid, structs::DbState>>>,
+    ) {
+        {
+            let mut dbs = dbs.lock().unwrap();
+            dbs.insert(server.id, structs::DbState::Loading);
+        }
 
-                for col in data.values() {
-                    let value = col.get(i).map(|v| v.to_string()).unwrap_or_default();
-                    record.push(value);
-                }
+        Self::load_db(server, dbs).await;
+    }
 
-                wtr.write_record(&record).unwrap();
+    /// Part of the title of a page bound to a database that precedes the
+    /// database and server.
+    fn page_title_prefix(&self, page_type: &structs::PageType) -> Option<String> {
+        match page_type {
+            structs::PageType::Statements(_) => Some(self.trans.query_statistics()),
+            structs::PageType::Privileges(_) => Some(self.trans.privileges()),
+            structs::PageType::TableDesigner(page) => {
+                let name = page.original.as_ref().map_or(&page.table.name, |table| &table.name);
+                Some(if name.is_empty() { self.trans.new_table() } else { name.clone() })
             }
+            structs::PageType::Indexes(page) => Some(self.trans.indexes_of(&page.table)),
+            structs::PageType::ErDiagram(_) => Some(self.trans.er_diagram()),
+            _ => None,
         }
-
-        wtr.flush().unwrap();
     }
 
-    async fn reload_server(
-        index: usize,
-        config: structs::Config,
-        dbs: Arc<Mutex<HashMap<String, structs::DbState>>>,
-    ) {
-        let server = &config.servers[index];
-        let id = format!("server:{}:{}:{}", server.ip, server.port, server.user);
+    /// Reconnects the pages of `server` after its settings changed.
+    fn rebind_pages(&mut self, server: &structs::Server) {
+        let activity = self.trans.activity();
+        let dashboard = self.trans.dashboard();
+
+        for idx in 0..self.pages.pages.len() {
+            let prefix = self.page_title_prefix(&self.pages.pages[idx].page_type);
+            let page = &mut self.pages.pages[idx];
+
+            if let Some((server_id, database_name, database)) = page.page_type.binding_mut()
+                && server_id == server.id
+            {
+                *database = server.connect(database_name);
+                page.title = match prefix {
+                    Some(prefix) => format!("{}: {}", prefix, server.page_title(database_name)),
+                    None => server.page_title(database_name),
+                };
+            }
 
-        {
-            let mut dbs = dbs.lock().unwrap();
-            dbs.remove(&id);
+            match &mut page.page_type {
+                structs::PageType::Activity(activity_page) if activity_page.server_id == server.id => {
+                    activity_page.database = server.connect(&server.service_database);
+                    page.title = format!("{}: {}", activity, server.alias);
+                }
+                structs::PageType::Dashboard(dashboard_page) if dashboard_page.server_id == server.id => {
+                    dashboard_page.state.lock().unwrap().database = server.connect(&server.service_database);
+                    page.title = format!("{}: {}", dashboard, server.alias);
+                }
+                _ => {}
+            }
         }
+    }
 
-        let database_url = format!(
-            "postgres://{}:{}@{}:{}/{}",
-            server.user, server.password, server.ip, server.port, server.service_database
-        );
-        match database::Database::new(&database_url).await {
-            Ok(database) => {
-                let databases = database.get_databases().await;
-                match databases {
-                    Ok(databases) => {
-                        let mut loaded_databases: Vec<structs::LoadedDatabase> = Vec::new();
-
-                        for db_name in databases {
-                            let db_url = format!(
-                                "postgres://{}:{}@{}:{}/{}",
-                                server.user, server.password, server.ip, server.port, db_name
-                            );
-
-                            match database::Database::new(&db_url).await {
-                                Ok(db_connection) => {
-                                    let tables = match db_connection.get_tables().await {
-                                        Ok(tables) => tables,
-                                        Err(_) => Vec::new(),
-                                    };
-
-                                    loaded_databases.push(structs::LoadedDatabase {
-                                        name: db_name,
-                                        database: db_connection,
-                                        tables,
-                                    });
-                                }
-                                Err(_) => {
-                                    loaded_databases.push(structs::LoadedDatabase {
-                                        name: db_name,
-                                        database: database.clone(),
-                                        tables: Vec::new(),
-                                    });
-                                }
-                            }
-                        }
+    /// Points pages at the new name of a renamed database. They still have
+    /// to be rebound, which `reconnect_databases` does.
+    fn rename_database_pages(&mut self, server_id: Uuid, old_name: &str, new_name: &str) {
+        for page in self.pages.pages.iter_mut() {
+            if let Some((page_server_id, database_name, _)) = page.page_type.binding_mut()
+                && page_server_id == server_id
+                && database_name == old_name
+            {
+                *database_name = new_name.to_string();
+            }
 
-                        let mut dbs = dbs.lock().unwrap();
-                        dbs.insert(id, structs::DbState::Loaded(loaded_databases));
-                    }
-                    Err(e) => {
-                        error!("Error loading databases for server {}: {}", server.ip, e);
-                        let mut dbs = dbs.lock().unwrap();
-                        dbs.insert(id, structs::DbState::Error(e.to_string()));
-                    }
+            for (page_server_id, database_name) in page.page_type.compared_databases_mut() {
+                if *page_server_id == server_id && database_name == old_name {
+                    *database_name = new_name.to_string();
                 }
             }
-            Err(e) => {
-                error!("Error connecting to server {}: {}", server.ip, e);
-                let mut dbs = dbs.lock().unwrap();
-                dbs.insert(id, structs::DbState::Error(e.to_string()));
+
+            if let structs::PageType::Dashboard(dashboard_page) = &page.page_type
+                && dashboard_page.server_id == server_id
+            {
+                let mut state = dashboard_page.state.lock().unwrap();
+                if state.database_name.as_deref() == Some(old_name) {
+                    state.database_name = Some(new_name.to_string());
+                }
             }
         }
     }
 
+    /// Returns the database of the tree, if the server's databases are loaded.
+    fn loaded_database(&self, server_id: Uuid, database_name: &str) -> Option<structs::LoadedDatabase> {
+        match self.db_manager.dbs.lock().unwrap().get(&server_id) {
+            Some(structs::DbState::Loaded(databases)) => databases
+                .iter()
+                .find(|database| database.name == database_name)
+                .cloned(),
+            _ => None,
+        }
+    }
+
+    /// Opens `sqlquery_page` in a new page and optionally runs its code.
+    fn open_sql_page(&mut self, mut sqlquery_page: structs::SQLQueryPage, run: bool) {
+        let title = self
+            .config
+            .servers
+            .iter()
+            .find(|server| server.id == sqlquery_page.server_id)
+            .map(|server| server.page_title(&sqlquery_page.database_name))
+            .unwrap_or_else(|| sqlquery_page.database_name.clone());
+
+        if run {
+            let sql_query_execution_status =
+                Arc::new(Mutex::new(structs::SQLQueryExecutionStatusType::Running));
+            sqlquery_page.sql_query_execution_status = Some(sql_query_execution_status.clone());
+            sqlquery_page.notices = Arc::default();
+
+            let database = sqlquery_page.database.clone();
+            let code = sqlquery_page.code.clone();
+            let read_only = sqlquery_page.read_only;
+            let notices = sqlquery_page.notices.clone();
+
+            self.runtime.spawn(async move {
+                Self::fetch_sql_query(database, &code, read_only, Some(sql_query_execution_status), notices).await;
+            });
+        }
+
+        self.pages.pages.push(structs::Page {
+            title,
+            page_type: structs::PageType::SQLQuery(sqlquery_page),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
     fn update_windows(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         if self.add_server_window.show {
             Modal::new(Id::new("add_server_modal")).show(ctx, |ui| {
@@ -490,6 +604,26 @@ impl Main<'_> {
                             .background_color(input_color),
                         );
                         ui.end_row();
+
+                        ui.label(self.trans.pool_size());
+                        let is_error = self.add_server_window.pool_size_field.parse::<u32>().map_or(true, |v| v == 0);
+                        let mut field =
+                            TextEdit::singleline(&mut self.add_server_window.pool_size_field);
+                        if is_error {
+                            field = field.text_color(Color32::from_rgb(255, 0, 0));
+                        }
+                        ui.add(field.background_color(input_color));
+                        ui.end_row();
+
+                        ui.label(self.trans.pool_idle_timeout());
+                        let is_error = self.add_server_window.pool_idle_timeout_field.parse::<u64>().is_err();
+                        let mut field =
+                            TextEdit::singleline(&mut self.add_server_window.pool_idle_timeout_field);
+                        if is_error {
+                            field = field.text_color(Color32::from_rgb(255, 0, 0));
+                        }
+                        ui.add(field.background_color(input_color));
+                        ui.end_row();
                     });
 
                 let is_name_error = {
@@ -543,12 +677,24 @@ impl Main<'_> {
                         false
                     }
                 };
+                let is_pool_error = {
+                    if self.add_server_window.pool_size_field.parse::<u32>().map_or(true, |v| v == 0) {
+                        ui.label(self.trans.incorrect_pool_size_value());
+                        true
+                    } else if self.add_server_window.pool_idle_timeout_field.parse::<u64>().is_err() {
+                        ui.label(self.trans.incorrect_pool_idle_timeout_value());
+                        true
+                    } else {
+                        false
+                    }
+                };
 
                 let enable_save_button = !is_name_error
                     && !is_ip_error
                     && !is_port_error
                     && !is_user_error
-                    && !is_service_database_error;
+                    && !is_service_database_error
+                    && !is_pool_error;
 
                 ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
                     ui.separator();
@@ -559,6 +705,7 @@ impl Main<'_> {
                             .clicked()
                         {
                             let server = structs::Server {
+                                id: Uuid::new_v4(),
                                 alias: self.add_server_window.name_field.clone(),
                                 ip: self.add_server_window.ip_field.clone(),
                                 port: self.add_server_window.port_field.parse::<u16>().unwrap(),
@@ -568,6 +715,15 @@ impl Main<'_> {
                                     .add_server_window
                                     .service_database_field
                                     .clone(),
+                                pool_size: self.add_server_window.pool_size_field.parse::<u32>().unwrap(),
+                                pool_idle_timeout: self
+                                    .add_server_window
+                                    .pool_idle_timeout_field
+                                    .parse::<u64>()
+                                    .unwrap(),
+                                folder: None,
+                                color: None,
+                                favorite: false,
                             };
                             self.config.servers.push(server);
                             self.save_config();
@@ -581,51 +737,125 @@ impl Main<'_> {
             });
         }
 
-        if self.delete_server_window.show {
-            if let Some(server) = &self.delete_server_window.server {
-                let needed_id_string =
-                    format!("server:{}:{}:{}", server.ip, server.port, server.user);
-                let mut idx_to_delete: Option<usize> = None;
-
-                for server_idx in 0..self.config.servers.len() {
-                    let server_in_find = &self.config.servers[server_idx];
-                    let id_string = format!(
-                        "server:{}:{}:{}",
-                        server_in_find.ip, server_in_find.port, server_in_find.user
-                    );
-
-                    if needed_id_string == id_string {
-                        idx_to_delete = Some(server_idx);
-                    }
-                }
+        if self.delete_server_window.show
+            && let Some(server) = &self.delete_server_window.server
+        {
+            let idx_to_delete = self
+                .config
+                .servers
+                .iter()
+                .position(|server_in_find| server_in_find.id == server.id);
 
-                if let Some(idx_to_delete) = idx_to_delete {
-                    Modal::new(Id::new("delete_server_modal")).show(ctx, |ui| {
-                        widgets::modal_label(ui, self.trans.delete_server());
+            if let Some(idx_to_delete) = idx_to_delete {
+                Modal::new(Id::new("delete_server_modal")).show(ctx, |ui| {
+                    widgets::modal_label(ui, self.trans.delete_server());
 
-                        ui.label(self.trans.delete_server_confirmation());
+                    ui.label(self.trans.delete_server_confirmation());
 
-                        ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
-                            ui.separator();
+                    ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                        ui.separator();
 
-                            ui.horizontal(|ui| {
-                                if ui.button(self.trans.yes()).clicked() {
-                                    self.config.servers.remove(idx_to_delete);
-                                    self.save_config();
-                                    self.delete_server_window =
-                                        structs::DeleteServerWindow::default();
-                                }
-                                if ui.button(self.trans.no()).clicked() {
-                                    self.delete_server_window =
-                                        structs::DeleteServerWindow::default();
+                        ui.horizontal(|ui| {
+                            if ui.button(self.trans.yes()).clicked() {
+                                let server = self.config.servers.remove(idx_to_delete);
+                                self.db_manager.dbs.lock().unwrap().remove(&server.id);
+                                self.db_manager.roles.lock().unwrap().remove(&server.id);
+                                self.stored_passwords.remove(&server.id);
+                                if let Some(secret_store) = self.secret_store()
+                                    && let Err(e) = secret_store.delete(server.id)
+                                {
+                                    self.show_error(e);
                                 }
-                            });
+                                self.save_config();
+                                self.delete_server_window =
+                                    structs::DeleteServerWindow::default();
+                            }
+                            if ui.button(self.trans.no()).clicked() {
+                                self.delete_server_window =
+                                    structs::DeleteServerWindow::default();
+                            }
                         });
                     });
-                }
+                });
             }
         }
 
+        if self.folder_window.show {
+            Modal::new(Id::new("folder_modal")).show(ctx, |ui| {
+                widgets::modal_label(
+                    ui,
+                    if self.folder_window.original_path.is_some() {
+                        self.trans.rename()
+                    } else {
+                        self.trans.add_folder()
+                    },
+                );
+
+                Grid::new("folder_form")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .striped(true)
+                    .show(ui, |ui| {
+                        ui.label(self.trans.name());
+                        ui.add(
+                            TextEdit::singleline(&mut self.folder_window.name_field)
+                                .background_color(self.config.settings.theme.text_input_color()),
+                        );
+                        ui.end_row();
+                    });
+
+                let name = self.folder_window.name_field.trim().to_string();
+                let path = match &self.folder_window.parent {
+                    Some(parent) => format!("{}/{}", parent, name),
+                    None => name.clone(),
+                };
+
+                let is_name_error = {
+                    if name.is_empty() {
+                        ui.label(self.trans.name_is_required());
+                        true
+                    } else if name.chars().count() > 32 {
+                        ui.label(self.trans.name_must_be_less_than_32_characters());
+                        true
+                    } else if name.contains('/') {
+                        ui.label(self.trans.name_must_not_contain_slash());
+                        true
+                    } else if self.config.folders.contains(&path)
+                        && self.folder_window.original_path.as_ref() != Some(&path)
+                    {
+                        ui.label(self.trans.name_must_be_unique());
+                        true
+                    } else {
+                        false
+                    }
+                };
+
+                ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        if ui
+                            .add_enabled(!is_name_error, Button::new(self.trans.save()))
+                            .clicked()
+                        {
+                            match self.folder_window.original_path.clone() {
+                                Some(original_path) => self.rename_folder(&original_path, &path),
+                                None => {
+                                    self.config.folders.push(path);
+                                    self.config.folders.sort();
+                                    self.save_config();
+                                }
+                            }
+                            self.folder_window = structs::FolderWindow::default();
+                        }
+                        if ui.button(self.trans.back()).clicked() {
+                            self.folder_window = structs::FolderWindow::default();
+                        }
+                    });
+                });
+            });
+        }
+
         if self.edit_server_window.show {
             Modal::new(Id::new("edit_server_modal")).show(ctx, |ui| {
                 widgets::modal_label(ui, self.trans.edit_server());
@@ -683,6 +913,26 @@ impl Main<'_> {
                             .background_color(input_color),
                         );
                         ui.end_row();
+
+                        ui.label(self.trans.pool_size());
+                        let is_error = self.edit_server_window.pool_size_field.parse::<u32>().map_or(true, |v| v == 0);
+                        let mut field =
+                            TextEdit::singleline(&mut self.edit_server_window.pool_size_field);
+                        if is_error {
+                            field = field.text_color(Color32::from_rgb(255, 0, 0));
+                        }
+                        ui.add(field.background_color(input_color));
+                        ui.end_row();
+
+                        ui.label(self.trans.pool_idle_timeout());
+                        let is_error = self.edit_server_window.pool_idle_timeout_field.parse::<u64>().is_err();
+                        let mut field =
+                            TextEdit::singleline(&mut self.edit_server_window.pool_idle_timeout_field);
+                        if is_error {
+                            field = field.text_color(Color32::from_rgb(255, 0, 0));
+                        }
+                        ui.add(field.background_color(input_color));
+                        ui.end_row();
                     });
 
                 let is_name_error = {
@@ -744,12 +994,24 @@ impl Main<'_> {
                         false
                     }
                 };
+                let is_pool_error = {
+                    if self.edit_server_window.pool_size_field.parse::<u32>().map_or(true, |v| v == 0) {
+                        ui.label(self.trans.incorrect_pool_size_value());
+                        true
+                    } else if self.edit_server_window.pool_idle_timeout_field.parse::<u64>().is_err() {
+                        ui.label(self.trans.incorrect_pool_idle_timeout_value());
+                        true
+                    } else {
+                        false
+                    }
+                };
 
                 let enable_save_button = !is_name_error
                     && !is_ip_error
                     && !is_port_error
                     && !is_user_error
-                    && !is_service_database_error;
+                    && !is_service_database_error
+                    && !is_pool_error;
 
                 ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
                     ui.separator();
@@ -759,7 +1021,11 @@ impl Main<'_> {
                             .add_enabled(enable_save_button, Button::new(self.trans.save()))
                             .clicked()
                         {
+                            let original_server =
+                                self.edit_server_window.original_server.clone().unwrap();
+
                             let server = structs::Server {
+                                id: original_server.id,
                                 alias: self.edit_server_window.name_field.clone(),
                                 ip: self.edit_server_window.ip_field.clone(),
                                 port: self.edit_server_window.port_field.parse::<u16>().unwrap(),
@@ -769,39 +1035,33 @@ impl Main<'_> {
                                     .edit_server_window
                                     .service_database_field
                                     .clone(),
+                                pool_size: self.edit_server_window.pool_size_field.parse::<u32>().unwrap(),
+                                pool_idle_timeout: self
+                                    .edit_server_window
+                                    .pool_idle_timeout_field
+                                    .parse::<u64>()
+                                    .unwrap(),
+                                folder: original_server.folder.clone(),
+                                color: original_server.color,
+                                favorite: original_server.favorite,
                             };
-                            let mut original_server_index: Option<usize> = None;
+                            if let Some(original_server_index) = self
+                                .config
+                                .servers
+                                .iter()
+                                .position(|server_in_find| server_in_find.id == original_server.id)
+                            {
+                                self.config.servers[original_server_index] = server.clone();
+                                self.save_config();
+                                self.rebind_pages(&server);
 
-                            let original_server =
-                                self.edit_server_window.original_server.clone().unwrap();
-                            let original_server_id = format!(
-                                "server:{}:{}:{}",
-                                original_server.ip, original_server.port, original_server.user
-                            );
-
-                            for server_idx in 0..self.config.servers.len() {
-                                let server_in_find = &self.config.servers[server_idx];
-                                let id_string = format!(
-                                    "server:{}:{}:{}",
-                                    server_in_find.ip, server_in_find.port, server_in_find.user
-                                );
-
-                                if original_server_id == id_string {
-                                    original_server_index = Some(server_idx);
-                                }
-                            }
+                                let dbs = self.db_manager.dbs.clone();
 
-                            self.config.servers[original_server_index.unwrap()] = server;
-                            self.save_config();
+                                self.runtime.spawn(async move {
+                                    Self::reload_server(server, dbs).await;
+                                });
+                            }
                             self.edit_server_window = structs::EditServerWindow::default();
-
-                            let dbs = self.db_manager.dbs.clone();
-                            let config = self.config.clone();
-
-                            self.runtime.spawn(async move {
-                                Self::reload_server(original_server_index.unwrap(), config, dbs)
-                                    .await;
-                            });
                         }
                         if ui.button(self.trans.back()).clicked() {
                             self.edit_server_window = structs::EditServerWindow::default();
@@ -858,6 +1118,15 @@ impl Main<'_> {
             if self.settings_window.language.is_none() {
                 self.settings_window.language = Some(self.config.settings.language.clone());
             }
+            if self.settings_window.restore_session.is_none() {
+                self.settings_window.restore_session = Some(self.config.settings.restore_session);
+            }
+            if self.settings_window.secrets_backend.is_none() {
+                self.settings_window.secrets_backend = Some(self.config.settings.secrets_backend);
+            }
+            if self.settings_window.auto_lock_minutes.is_none() {
+                self.settings_window.auto_lock_minutes = Some(self.config.settings.auto_lock_minutes);
+            }
 
             Modal::new(Id::new("settings_modal")).show(ctx, |ui| {
                 widgets::modal_label(ui, self.trans.settings());
@@ -910,11 +1179,47 @@ impl Main<'_> {
                         });
                         ui.end_row();
 
+                        ui.label(self.trans.restore_session());
+                        if let Some(restore_session) = &mut self.settings_window.restore_session {
+                            ui.checkbox(restore_session, "");
+                        }
+                        ui.end_row();
+
+                        ui.with_layout(Layout::top_down(Align::LEFT), |ui| {
+                            ui.label(self.trans.passwords_storage());
+                        });
+                        let backend_name = self
+                            .settings_window
+                            .secrets_backend
+                            .map(|backend| self.trans.secrets_backend_name(backend))
+                            .unwrap_or_default();
+                        CollapsingHeader::new(backend_name).show(ui, |ui| {
+                            for backend in secrets::SecretsBackend::ALL {
+                                if ui.button(self.trans.secrets_backend_name(backend)).clicked() {
+                                    self.settings_window.secrets_backend = Some(backend);
+                                }
+                            }
+                        });
+                        ui.end_row();
+
+                        ui.label(self.trans.auto_lock());
+                        if let Some(auto_lock_minutes) = &mut self.settings_window.auto_lock_minutes {
+                            ui.add(DragValue::new(auto_lock_minutes).range(0..=1440));
+                        }
+                        ui.end_row();
+
                         ui.label(self.trans.change_password());
                         if ui.button(self.trans.change_password()).clicked() {
                             self.change_password_window.show = true;
                             self.settings_window = structs::SettingsWindow::default();
                         }
+                        ui.end_row();
+
+                        ui.label(self.trans.recovery_key());
+                        if ui.button(self.trans.generate_new_recovery_key()).clicked() {
+                            self.settings_window = structs::SettingsWindow::default();
+                            self.generate_recovery_key();
+                        }
                     });
 
                 ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
@@ -926,10 +1231,24 @@ impl Main<'_> {
                             self.config.settings.theme = self.settings_window.theme.clone();
                             self.config.settings.language =
                                 self.settings_window.language.clone().unwrap();
+                            self.config.settings.restore_session =
+                                self.settings_window.restore_session.unwrap();
+                            self.config.settings.auto_lock_minutes =
+                                self.settings_window.auto_lock_minutes.unwrap();
+
+                            if !self.config.settings.restore_session {
+                                self.clear_session();
+                            }
+
+                            let secrets_backend = self.settings_window.secrets_backend.unwrap();
 
                             self.settings_window = structs::SettingsWindow::default();
 
-                            self.save_config();
+                            if secrets_backend != self.config.settings.secrets_backend {
+                                self.switch_secrets_backend(secrets_backend);
+                            } else {
+                                self.save_config();
+                            }
                         }
                         if ui.button(self.trans.close()).clicked() {
                             self.settings_window = structs::SettingsWindow::default();
@@ -996,25 +1315,15 @@ impl Main<'_> {
                             .add_enabled(!is_passwords_match_error, Button::new(self.trans.save()))
                             .clicked()
                         {
-                            let new_password_hash =
-                                utils::create_checksum(&self.change_password_window.new_password);
-
-                            if self.config.password_hash.is_some()
-                                && self.config.password_hash.clone().unwrap()
-                                    == utils::create_checksum(
-                                        &self.change_password_window.old_password,
-                                    )
-                            {
-                                self.password =
-                                    Some(self.change_password_window.new_password.clone());
-                                self.config.password_hash = Some(new_password_hash);
-                                self.save_config();
+                            let old_password = self.change_password_window.old_password.clone();
+                            let new_password = self.change_password_window.new_password.clone();
 
-                                self.change_password_window =
-                                    structs::ChangePasswordWindow::default();
-                            } else {
-                                self.change_password_window.error =
-                                    Some(self.trans.incorrect_password_hash_mismatch());
+                            match self.change_master_password(&old_password, &new_password) {
+                                Ok(()) => {
+                                    self.change_password_window =
+                                        structs::ChangePasswordWindow::default();
+                                }
+                                Err(e) => self.change_password_window.error = Some(e),
                             }
                         }
                         if ui.button(self.trans.close()).clicked() {
@@ -1024,6 +1333,33 @@ impl Main<'_> {
                 });
             });
         }
+
+        if self.recovery_key_window.show {
+            Modal::new(Id::new("recovery_key_modal")).show(ctx, |ui| {
+                widgets::modal_label(ui, self.trans.recovery_key());
+
+                ui.label(self.trans.recovery_key_description());
+                ui.add_space(4.0);
+                ui.label(
+                    RichText::new(&self.recovery_key_window.recovery_key)
+                        .monospace()
+                        .strong(),
+                );
+
+                ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        if ui.button(self.trans.copy()).clicked() {
+                            ui.ctx().copy_text(self.recovery_key_window.recovery_key.clone());
+                        }
+                        if ui.button(self.trans.close()).clicked() {
+                            self.recovery_key_window = structs::RecoveryKeyWindow::default();
+                        }
+                    });
+                });
+            });
+        }
     }
 
     fn update_pages(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
@@ -1034,6 +1370,9 @@ impl Main<'_> {
                     button_title =
                         format!("{}...", &button_title.chars().take(16).collect::<String>());
                 }
+                if page.is_dirty() {
+                    button_title = format!("● {}", button_title);
+                }
 
                 let btn = ui.button(&button_title);
                 let btn_id = Id::new(idx);
@@ -1078,7 +1417,49 @@ impl Main<'_> {
                         return;
                     }
 
-                    let page = &mut self.pages.pages[self.pages.current_page_index as usize];
+                    let page_index = self.pages.current_page_index;
+
+                    match self.pages.pages[page_index as usize].page_type {
+                        structs::PageType::Activity(_) => {
+                            self.update_activity_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::Dashboard(_) => {
+                            self.update_dashboard_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::Statements(_) => {
+                            self.update_statements_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::Privileges(_) => {
+                            self.update_privileges_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::TableDesigner(_) => {
+                            self.update_table_designer_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::Indexes(_) => {
+                            self.update_indexes_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::ErDiagram(_) => {
+                            self.update_er_diagram_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::SchemaDiff(_) => {
+                            self.update_schema_diff_page(ui, page_index as usize);
+                            return;
+                        }
+                        structs::PageType::DataCompare(_) => {
+                            self.update_data_compare_page(ui, page_index as usize);
+                            return;
+                        }
+                        _ => {}
+                    }
+
+                    let page = &mut self.pages.pages[page_index as usize];
 
                     match &mut page.page_type {
                         structs::PageType::Welcome => {
@@ -1139,34 +1520,58 @@ impl Main<'_> {
                                         let runtime = &self.runtime;
 
                                         sqlquery_page.sql_query_execution_status = Some(Arc::new(Mutex::new(structs::SQLQueryExecutionStatusType::Running)));
+                                        sqlquery_page.notices = Arc::default();
 
                                         let database_clone = sqlquery_page.database.clone();
                                         let code_clone = sqlquery_page.code.clone();
+                                        let read_only = sqlquery_page.read_only;
                                         let sql_query_execution_status = sqlquery_page.sql_query_execution_status.clone();
+                                        let notices = sqlquery_page.notices.clone();
 
                                         runtime.spawn(async move {
-                                            Self::fetch_sql_query(database_clone, &code_clone, sql_query_execution_status).await;
+                                            Self::fetch_sql_query(database_clone, &code_clone, read_only, sql_query_execution_status, notices).await;
                                         });
                                     }
 
-                                    if ui.add_enabled(!code_is_empty, Button::new(self.trans.save())).clicked() || (ui.input(|i| i.modifiers.ctrl && i.key_pressed(Key::S)) && !code_is_empty) {
+                                    if ui.add_enabled(!code_is_empty, Button::new(self.trans.save())).clicked() || (ui.input(|i| i.modifiers.ctrl && !i.modifiers.shift && i.key_pressed(Key::S)) && !code_is_empty) {
                                         if sqlquery_page.code_file_path.is_some() {
-                                            Self::save_code(sqlquery_page);
+                                            if let Err(e) = Self::save_code(sqlquery_page) {
+                                                self.error_window = structs::ErrorWindow::with_message(e);
+                                            }
                                         } else {
                                             self.select_file_dialog_action = Some(structs::SelectFileDialogAction::SaveFile);
                                             self.select_file_dialog.save_file();
                                         }
                                     }
+                                    if ui.add_enabled(!code_is_empty, Button::new(self.trans.save_as())).clicked() || (ui.input(|i| i.modifiers.ctrl && i.modifiers.shift && i.key_pressed(Key::S)) && !code_is_empty) {
+                                        self.select_file_dialog_action = Some(structs::SelectFileDialogAction::SaveFile);
+                                        self.select_file_dialog.save_file();
+                                    }
                                     if ui.button(self.trans.open()).clicked() || (ui.input(|i| i.modifiers.ctrl && i.key_pressed(Key::O))) {
                                         self.select_file_dialog_action = Some(structs::SelectFileDialogAction::OpenFile);
                                         self.select_file_dialog.pick_file();
                                     }
+                                    ui.add_enabled_ui(!self.config.recent_files.is_empty(), |ui| {
+                                        ui.menu_button(self.trans.open_recent(), |ui| {
+                                            for recent_file in &self.config.recent_files {
+                                                if ui.button(recent_file).clicked() {
+                                                    self.actions.push(structs::Action::OpenFile(recent_file.clone()));
+                                                    ui.close_menu();
+                                                }
+                                            }
+                                        });
+                                    });
 
                                     if ui.add_enabled(!sqlquery_page.output_is_empty, Button::new(self.trans.export_to_csv())).clicked() || (ui.input(|i| i.modifiers.ctrl && i.key_pressed(Key::E)) && !sqlquery_page.output_is_empty) {
                                         self.select_file_dialog_action = Some(structs::SelectFileDialogAction::ExportToCsv);
                                         self.select_file_dialog.save_file();
                                     }
 
+                                    if sqlquery_page.read_only {
+                                        ui.separator();
+                                        ui.label(RichText::new(self.trans.read_only_rolled_back()).color(Color32::GRAY));
+                                    }
+
                                     self.select_file_dialog.update(ctx);
 
                                     if let Some(action) = &self.select_file_dialog_action {
@@ -1175,38 +1580,31 @@ impl Main<'_> {
                                                 if let Some(code_file_path) = self.select_file_dialog.take_picked() {
                                                     self.select_file_dialog_action = None;
 
-                                                    sqlquery_page.code_file_path = Some(code_file_path.to_string_lossy().to_string());
+                                                    let code_file_path = code_file_path.to_string_lossy().to_string();
+                                                    sqlquery_page.code_file_path = Some(code_file_path.clone());
 
-                                                    Self::save_code(sqlquery_page);
+                                                    match Self::save_code(sqlquery_page) {
+                                                        Ok(()) => self.actions.push(structs::Action::AddRecentFile(code_file_path)),
+                                                        Err(e) => self.error_window = structs::ErrorWindow::with_message(e),
+                                                    }
                                                 }
                                             },
                                             structs::SelectFileDialogAction::OpenFile => {
                                                 if let Some(code_file_path) = self.select_file_dialog.take_picked() {
                                                     self.select_file_dialog_action = None;
 
-                                                    sqlquery_page.code_file_path = Some(code_file_path.to_string_lossy().to_string());
-
-                                                    match File::open(code_file_path) {
-                                                        Ok(mut file) => {
-                                                            let mut file_content = String::new();
-                                                            let _ = file.read_to_string(&mut file_content);
-
-                                                            sqlquery_page.code = file_content;
-                                                        },
-                                                        Err(_) => {},
-                                                    }
+                                                    self.actions.push(structs::Action::OpenFile(code_file_path.to_string_lossy().to_string()));
                                                 }
                                             },
                                             structs::SelectFileDialogAction::ExportToCsv => {
-                                                if let Some(file_path) = self.select_file_dialog.take_picked() {
-                                                    match sqlquery_page.sql_query_execution_status.clone().unwrap().lock().unwrap().clone() {
-                                                        structs::SQLQueryExecutionStatusType::Success(sqlquery_execution_success) => {
-                                                            let result = &sqlquery_execution_success.result;
-
-                                                            Self::export_output_to_csv(result, file_path.to_string_lossy().to_string());
-                                                        },
-                                                        _ => {},
-                                                    };
+                                                if let Some(file_path) = self.select_file_dialog.take_picked()
+                                                    && let structs::SQLQueryExecutionStatusType::Success(sqlquery_execution_success) = sqlquery_page.sql_query_execution_status.clone().unwrap().lock().unwrap().clone()
+                                                {
+                                                    let result = &sqlquery_execution_success.result;
+
+                                                    if let Err(e) = Self::export_output_to_csv(result, file_path.to_string_lossy().to_string()) {
+                                                        self.error_window = structs::ErrorWindow::with_message(e);
+                                                    }
                                                 }
                                             }
                                         }
@@ -1222,15 +1620,36 @@ impl Main<'_> {
                                     });
                                 }
 
+                                if sqlquery_page.file_changed {
+                                    ui.horizontal(|ui| {
+                                        ui.label(RichText::new(self.trans.file_changed_on_disk()).color(Color32::from_rgb(232, 146, 60)));
+
+                                        if ui.button(self.trans.reload()).clicked() {
+                                            let code_file_path = sqlquery_page.code_file_path.clone().unwrap();
+
+                                            if let Err(e) = Self::load_code(sqlquery_page, code_file_path) {
+                                                self.error_window = structs::ErrorWindow::with_message(e);
+                                            }
+                                        }
+                                        if ui.button(self.trans.keep_mine()).clicked() {
+                                            sqlquery_page.file_changed = false;
+                                            sqlquery_page.file_modified = sqlquery_page
+                                                .code_file_path
+                                                .as_ref()
+                                                .and_then(files::file_modified);
+                                        }
+                                    });
+                                }
+
                                 ui.add_space(8.0);
 
-                                let mut theme = egui_extras::syntax_highlighting::CodeTheme::light(12.0);
+                                let theme = egui_extras::syntax_highlighting::CodeTheme::light(12.0);
 
                                 let mut layouter = |ui: &egui::Ui, string: &str, wrap_width: f32| {
                                     let mut layout_job = egui_extras::syntax_highlighting::highlight(
                                         ui.ctx(),
                                         ui.style(),
-                                        &mut theme,
+                                        &theme,
                                         string,
                                         "sql",
                                     );
@@ -1238,16 +1657,46 @@ impl Main<'_> {
                                     ui.fonts(|f| f.layout_job(layout_job))
                                 };
 
-                                let code_editor = ui.add(
-                                    TextEdit::multiline(&mut sqlquery_page.code)
-                                        .font(egui::TextStyle::Monospace)
-                                        .code_editor()
-                                        .desired_width(f32::INFINITY)
-                                        .desired_rows(10)
-                                        .background_color(self.config.settings.theme.text_input_color())
-                                        .hint_text("SELECT * FROM ...")
-                                        .layouter(&mut layouter),
-                                );
+                                let code_editor_id = Id::new("code_editor").with(page_index);
+
+                                if sqlquery_page.restore_cursor {
+                                    let mut state = TextEditState::load(ui.ctx(), code_editor_id).unwrap_or_default();
+                                    if let Some(cursor) = sqlquery_page.cursor {
+                                        state.cursor.set_char_range(Some(CCursorRange::one(CCursor::new(cursor))));
+                                    }
+                                    state.store(ui.ctx(), code_editor_id);
+
+                                    sqlquery_page.restore_cursor = false;
+                                }
+
+                                let code_editor_output = TextEdit::multiline(&mut sqlquery_page.code)
+                                    .id(code_editor_id)
+                                    .font(egui::TextStyle::Monospace)
+                                    .code_editor()
+                                    .desired_width(f32::INFINITY)
+                                    .desired_rows(10)
+                                    .background_color(self.config.settings.theme.text_input_color())
+                                    .hint_text("SELECT * FROM ...")
+                                    .layouter(&mut layouter)
+                                    .show(ui);
+
+                                if let Some(cursor_range) = code_editor_output.state.cursor.char_range() {
+                                    sqlquery_page.cursor = Some(cursor_range.primary.index);
+                                }
+
+                                if let Some(sql_query_execution_status) = &sqlquery_page.sql_query_execution_status
+                                    && let structs::SQLQueryExecutionStatusType::Error(e) = &mut *sql_query_execution_status.lock().unwrap()
+                                {
+                                    // The position no longer matches once the code is edited.
+                                    if code_editor_output.response.changed() {
+                                        e.position = None;
+                                    }
+                                    if let Some(position) = e.position {
+                                        messages::highlight_error(ui, &code_editor_output, &sqlquery_page.code, position);
+                                    }
+                                }
+
+                                let code_editor = code_editor_output.response;
                                 if code_editor.secondary_clicked() {
                                     ui.memory_mut(|mem| mem.open_popup(Id::new("code_editor_popup")));
                                 }
@@ -1262,7 +1711,35 @@ impl Main<'_> {
 
                                 ui.add_space(8.0);
 
+                                let mut go_to_error = None;
+
                                 if let Some(sql_query_execution_status) = &sqlquery_page.sql_query_execution_status {
+                                    let notices = sqlquery_page.notices.lock().unwrap().clone();
+
+                                    ui.horizontal(|ui| {
+                                        if ui.selectable_label(!sqlquery_page.show_messages, self.trans.data_output()).clicked() {
+                                            sqlquery_page.show_messages = false;
+                                        }
+                                        let messages_label = match notices.len() {
+                                            0 => self.trans.messages(),
+                                            count => format!("{} ({})", self.trans.messages(), count),
+                                        };
+                                        if ui.selectable_label(sqlquery_page.show_messages, messages_label).clicked() {
+                                            sqlquery_page.show_messages = true;
+                                        }
+                                    });
+
+                                    if sqlquery_page.show_messages {
+                                        ui.separator();
+
+                                        let status = sql_query_execution_status.lock().unwrap().clone();
+                                        go_to_error = messages::query_messages(ui, &self.trans, &notices, &status, &sqlquery_page.code);
+                                    }
+                                }
+
+                                if let Some(sql_query_execution_status) = &sqlquery_page.sql_query_execution_status
+                                    && !sqlquery_page.show_messages
+                                {
                                     let mut sql_query_execution_status_mutex = sql_query_execution_status.lock().unwrap();
                                     if let Some(update_page_index) = sqlquery_page.update_page_index {
                                         if let structs::SQLQueryExecutionStatusType::Success(ref mut result) = *sql_query_execution_status_mutex {
@@ -1331,7 +1808,7 @@ impl Main<'_> {
                                                                     .collect::<Vec<String>>())
                                                                 .collect::<Vec<Vec<String>>>();
 
-                                                            for i in 0..values.iter().next().unwrap().len() {
+                                                            for i in 0..values.first().unwrap().len() {
                                                                 body.row(16.0, |mut row| {
                                                                     for value in &values {
                                                                         row.col(|ui| {
@@ -1384,11 +1861,11 @@ impl Main<'_> {
 
                                                             ui.separator();
 
-                                                            if ui.add_enabled(result.page_index != pages_count as u32 - 1, Button::new(">")).clicked() {
+                                                            if ui.add_enabled(result.page_index != pages_count - 1, Button::new(">")).clicked() {
                                                                 sqlquery_page.update_page_index = Some(result.page_index + 1);
                                                             }
-                                                            if ui.add_enabled(result.page_index != pages_count as u32 - 1, Button::new(">>>")).clicked() {
-                                                                sqlquery_page.update_page_index = Some(pages_count as u32 - 1);
+                                                            if ui.add_enabled(result.page_index != pages_count - 1, Button::new(">>>")).clicked() {
+                                                                sqlquery_page.update_page_index = Some(pages_count - 1);
                                                             }
                                                         });
                                                     }
@@ -1413,12 +1890,27 @@ impl Main<'_> {
                                                 ui.label(self.trans.error());
                                             });
 
-                                            ui.heading(e);
+                                            go_to_error = messages::query_error(ui, &self.trans, e, &sqlquery_page.code);
                                         }
                                     }
                                 }
+
+                                if let Some(position) = go_to_error {
+                                    sqlquery_page.cursor = Some(position);
+                                    sqlquery_page.restore_cursor = true;
+                                    ui.memory_mut(|mem| mem.request_focus(code_editor_id));
+                                }
                             });
                         },
+                        structs::PageType::Activity(_)
+                        | structs::PageType::Dashboard(_)
+                        | structs::PageType::Statements(_)
+                        | structs::PageType::Privileges(_)
+                        | structs::PageType::TableDesigner(_)
+                        | structs::PageType::Indexes(_)
+                        | structs::PageType::ErDiagram(_)
+                        | structs::PageType::SchemaDiff(_)
+                        | structs::PageType::DataCompare(_) => {}
                     }
                 });
         });
@@ -1427,13 +1919,60 @@ impl Main<'_> {
         for action in actions {
             match action {
                 structs::Action::ClosePage(idx) => {
-                    if idx < self.pages.pages.len() {
-                        self.pages.pages.remove(idx);
-                        if self.pages.pages.is_empty() {
-                            self.pages = structs::Pages::default();
-                        } else if self.pages.current_page_index as usize >= self.pages.pages.len() {
-                            self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
-                        }
+                    if self.pages.pages.get(idx).is_some_and(|page| page.is_dirty()) {
+                        self.unsaved_changes_window = structs::UnsavedChangesWindow {
+                            show: true,
+                            page_index: Some(idx),
+                        };
+                    } else {
+                        self.close_page(idx);
+                    }
+                }
+                structs::Action::AddRecentFile(code_file_path) => self.add_recent_file(code_file_path),
+                structs::Action::OpenFile(code_file_path) => self.open_file(code_file_path),
+                structs::Action::OpenStatementsPage(server_id, database_name) => {
+                    self.open_statements_page(server_id, &database_name)
+                }
+                structs::Action::OpenPrivilegesPage(server_id, database_name) => {
+                    self.open_privileges_page(server_id, &database_name)
+                }
+                structs::Action::OpenTableDesigner(server_id, database_name, table) => {
+                    self.open_table_designer(server_id, &database_name, table)
+                }
+                structs::Action::OpenIndexesPage(server_id, database_name, table) => {
+                    self.open_indexes_page(server_id, &database_name, &table)
+                }
+                structs::Action::OpenErDiagram(server_id, database_name) => {
+                    self.open_er_diagram(server_id, &database_name)
+                }
+                structs::Action::OpenSchemaDiff(server_id, database_name) => {
+                    self.open_schema_diff(server_id, &database_name)
+                }
+                structs::Action::OpenDataCompare(server_id, database_name, table) => {
+                    self.open_data_compare(server_id, &database_name, &table)
+                }
+                structs::Action::OpenMaintenance(server_id, database_name, table) => {
+                    self.open_maintenance_window(server_id, &database_name, table)
+                }
+                structs::Action::MoveServer(from, to) => {
+                    let from = self.config.servers.iter().position(|server| server.id == from);
+                    let to = self.config.servers.iter().position(|server| server.id == to);
+
+                    if let (Some(from), Some(to)) = (from, to)
+                        && from != to
+                    {
+                        let mut server = self.config.servers.remove(from);
+                        server.folder = self.config.servers[if to > from { to - 1 } else { to }].folder.clone();
+                        self.config.servers.insert(to, server);
+                        self.save_config();
+                    }
+                }
+                structs::Action::MoveServerToFolder(id, folder) => {
+                    if let Some(idx) = self.config.servers.iter().position(|server| server.id == id) {
+                        let mut server = self.config.servers.remove(idx);
+                        server.folder = folder;
+                        self.config.servers.push(server);
+                        self.save_config();
                     }
                 }
             }
@@ -1443,6 +1982,9 @@ impl Main<'_> {
 
 impl App for Main<'_> {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.confirm_app_close(ctx);
+        self.check_auto_lock(ctx);
+
         ctx.set_theme(self.config.settings.theme.to_egui());
         ctx.set_zoom_factor(self.config.settings.scale_factor);
 
@@ -1461,9 +2003,21 @@ impl App for Main<'_> {
             });
         }
 
+        if self.config_error.is_some() {
+            self.update_config_error(ctx);
+
+            return;
+        }
+
         if self.login_window.show {
             CentralPanel::default().show(ctx, |_| {});
 
+            if self.servers_file_dialog_is_open() {
+                self.update_transfer_windows(ctx);
+
+                return;
+            }
+
             Modal::new(Id::new("login_window")).show(ctx, |ui| {
                 ui.set_width(360.0);
 
@@ -1474,11 +2028,27 @@ impl App for Main<'_> {
 
                     ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
                         ui.horizontal(|ui| {
+                            if ui.button(self.trans.export_servers_without_passwords()).clicked() {
+                                self.servers_file_dialog_action =
+                                    Some(structs::ServersFileDialogAction::ExportWithoutPasswords);
+                                self.servers_file_dialog.save_file();
+                            }
                             if ui.button(self.trans.yes()).clicked() {
                                 self.login_window = structs::LoginWindow::default();
 
+                                if self.config.settings.secrets_backend
+                                    == secrets::SecretsBackend::Keyring
+                                {
+                                    for server in self.config.servers.iter() {
+                                        if let Err(e) = secrets::KeyringStore.delete(server.id) {
+                                            error!("Error removing password of server {}: {}", server.alias, e);
+                                        }
+                                    }
+                                }
+
                                 self.config.servers = Vec::new();
                                 self.config.password_hash = None;
+                                self.config.vault = None;
 
                                 self.save_config();
                             }
@@ -1491,17 +2061,73 @@ impl App for Main<'_> {
                     return;
                 }
 
+                if self.login_window.recovery {
+                    widgets::modal_label(ui, self.trans.recover_access());
+
+                    if let Some(error) = &self.login_window.error {
+                        ui.label(RichText::new(error).color(Color32::RED));
+                    }
+
+                    Grid::new("recovery_form")
+                        .num_columns(2)
+                        .spacing([40.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label(self.trans.recovery_key());
+                            TextEdit::singleline(&mut self.login_window.recovery_key)
+                                .background_color(self.config.settings.theme.text_input_color())
+                                .show(ui);
+                            ui.end_row();
+
+                            ui.label(self.trans.new_password());
+                            TextEdit::singleline(&mut self.login_window.new_password)
+                                .background_color(self.config.settings.theme.text_input_color())
+                                .password(true)
+                                .show(ui);
+                            ui.end_row();
+                        });
+
+                    ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                        ui.separator();
+
+                        ui.horizontal(|ui| {
+                            if ui.button(self.trans.back()).clicked() {
+                                self.login_window.recovery = false;
+                                self.login_window.error = None;
+                            }
+                            if ui
+                                .add_enabled(
+                                    !self.login_window.new_password.is_empty(),
+                                    Button::new(self.trans.reset_password()),
+                                )
+                                .clicked()
+                            {
+                                let recovery_key = self.login_window.recovery_key.clone();
+                                let new_password = self.login_window.new_password.clone();
+
+                                match self.unlock_with_recovery_key(&recovery_key, &new_password) {
+                                    Ok(()) => self.finish_login(),
+                                    Err(e) => self.login_window.error = Some(e),
+                                }
+                            }
+                        });
+                    });
+
+                    return;
+                }
+
                 widgets::modal_label(ui, self.trans.login());
 
                 if let Some(error) = &self.login_window.error {
                     ui.label(RichText::new(error).color(Color32::RED));
                 }
 
+                let is_new_vault = self.config.vault.is_none() && self.config.password_hash.is_none();
+
                 ui.horizontal(|ui| {
-                    if self.config.password_hash.is_some() {
-                        ui.label(self.trans.enter_encryption_password());
-                    } else {
+                    if is_new_vault {
                         ui.label(self.trans.create_encryption_password());
+                    } else {
+                        ui.label(self.trans.enter_encryption_password());
                     }
 
                     TextEdit::singleline(&mut self.login_window.password)
@@ -1510,22 +2136,34 @@ impl App for Main<'_> {
                         .show(ui);
                 });
 
+                if is_new_vault {
+                    ui.checkbox(
+                        &mut self.login_window.generate_recovery_key,
+                        self.trans.generate_recovery_key(),
+                    );
+                }
+
                 ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
                     ui.add_space(8.0);
                     ui.separator();
 
                     ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
                         ui.horizontal(|ui| {
-                            if !self.config.servers.is_empty() {
-                                if ui
+                            if !self.config.servers.is_empty()
+                                && ui
                                     .button(
                                         RichText::new(self.trans.clear_storage())
                                             .color(Color32::RED),
                                     )
                                     .clicked()
-                                {
-                                    self.login_window.clear_storage = true;
-                                }
+                            {
+                                self.login_window.clear_storage = true;
+                            }
+                            if self.config.vault.as_ref().is_some_and(|vault| vault.recovery.is_some())
+                                && ui.button(self.trans.forgot_password()).clicked()
+                            {
+                                self.login_window.recovery = true;
+                                self.login_window.error = None;
                             }
                             if ui.button(self.trans.login()).clicked()
                                 || (ui.input(|i| i.key_pressed(Key::Enter)))
@@ -1533,30 +2171,12 @@ impl App for Main<'_> {
                                 let password = self.login_window.password.clone();
 
                                 self.login_window.error = None;
-                                self.password = Some(password.clone());
 
                                 ui.spinner();
 
-                                if self.config.password_hash.is_some() {
-                                    if utils::create_checksum(&password)
-                                        != self.config.password_hash.clone().unwrap()
-                                    {
-                                        self.login_window.error =
-                                            Some(self.trans.incorrect_password_hash_mismatch());
-                                    }
-                                }
-
-                                if self.login_window.error.is_none() {
-                                    self.decrypt_passwords();
-                                }
-                                if self.login_window.error.is_none() {
-                                    self.login_window.show = false;
-
-                                    if self.config.password_hash.is_none() {
-                                        self.config.password_hash =
-                                            Some(utils::create_checksum(&password));
-                                        self.save_config();
-                                    }
+                                match self.unlock(&password) {
+                                    Ok(()) => self.finish_login(),
+                                    Err(e) => self.login_window.error = Some(e),
                                 }
                             }
                         });
@@ -1564,174 +2184,53 @@ impl App for Main<'_> {
                 });
             });
 
+            self.update_transfer_windows(ctx);
+            self.update_file_windows(ctx);
+
             return;
         }
 
         widgets::left_panel(ctx, |ui| {
-            CollapsingHeader::new(self.trans.servers())
-                .default_open(true)
-                .show(ui, |ui| {
-                    let server_indices: Vec<usize> = (0..self.config.servers.len()).collect();
-
-                    for &idx in &server_indices {
-                        let server = self.config.servers[idx].clone();
-                        ui.horizontal(|ui| {
-                            let server_id = format!("server:{}:{}:{}", server.ip, server.port, server.user);
-
-                            let db_state = {
-                                let dbs = self.db_manager.dbs.lock().expect("Failed to lock dbs mutex");
-                                dbs.get(&server_id).cloned()
-                            };
-
-                            let id_string = format!("server:{}:{}:{}:warning", server.ip, server.port, server.user);
-                            let id = Id::new(&id_string);
-
-                            let server_button: Option<egui::Response> = match db_state {
-                                Some(structs::DbState::Loading) => {
-                                    ui.add(Spinner::new());
-
-                                    Some(ui.label(format!(
-                                        "{} ({}:{})",
-                                        server.alias, server.ip, server.port
-                                    )))
-                                }
-                                Some(structs::DbState::Loaded(_db)) => {
-                                    Some(CollapsingHeader::new(format!(
-                                        "{} ({}:{})",
-                                        server.alias, server.ip, server.port
-                                    ))
-                                    .show(ui, |ui| {
-                                        CollapsingHeader::new(self.trans.databases()).show(ui, |ui| {
-                                            let db_state = {
-                                                let dbs = self.db_manager.dbs.lock().expect("Failed to lock dbs mutex");
-                                                dbs.get(&server_id).cloned()
-                                            };
-                                            if let Some(structs::DbState::Loaded(_db)) = db_state {
-                                                for database in _db {
-                                                    let pages = &mut self.pages;
-                                                    let server = &self.config.servers[idx];
-
-                                                    CollapsingHeader::new(&database.name).id_salt(format!("db_{}_{}_{}", server.ip, server.port, database.name)).show(ui, |ui| {
-                                                        CollapsingHeader::new(self.trans.tables()).id_salt(format!("tables_{}", database.name)).show(ui, |ui| {
-                                                            for table in &database.tables {
-                                                                CollapsingHeader::new(table).id_salt(format!("table_{}_{}", database.name, table)).show(ui, |ui| {
-                                                                    CollapsingHeader::new(self.trans.scripts()).id_salt(format!("scripts_{}_{}_{}", server.ip, database.name, table)).show(ui, |ui| {
-                                                                        widgets::script_preset(ui, pages, &database, server, "Insert", scripts::INSERT.replace("{table_name}", table));
-                                                                        widgets::script_preset(ui, pages, &database, server, "Update", scripts::UPDATE.replace("{table_name}", table));
-                                                                        widgets::script_preset(ui, pages, &database, server, "Delete", scripts::DELETE.replace("{table_name}", table));
-                                                                        widgets::script_preset(ui, pages, &database, server, "Select", scripts::SELECT.replace("{table_name}", table));
-                                                                        widgets::script_preset(ui, pages, &database, server, "Select 100", scripts::SELECT_100.replace("{table_name}", table));
-                                                                        widgets::script_preset(ui, pages, &database, server, self.trans.get_columns(), scripts::GET_TABLE_COLUMNS.replace("{table_name}", table));
-                                                                    });
-                                                                });
-                                                            }
-                                                        });
-
-                                                        CollapsingHeader::new(self.trans.scripts()).id_salt(format!("db_scripts_{}", database.name)).show(ui, |ui| {
-                                                            widgets::script_preset(ui, pages, &database, server, "Create table", scripts::CREATE_TABLE);
-                                                            widgets::script_preset(ui, pages, &database, server, "Create index", scripts::CREATE_INDEX);
-                                                            widgets::script_preset(ui, pages, &database, server, "Drop table", scripts::DROP_TABLE);
-                                                        });
-
-                                                        widgets::script_preset(ui, pages, &database, server, "SQL Query", String::new());
-                                                    });
-                                                }
-                                            }
-                                        });
-                                    }).header_response)
-                                }
-                                Some(structs::DbState::Error(e)) => {
-                                    let warning_icon = match self.config.settings.theme {
-                                        structs::Theme::Light => self.icons.warning_dark.clone(),
-                                        _ => self.icons.warning_light.clone(),
-                                    };
-                                    let warning = ui.add(warning_icon);
-                                    if warning.hovered() {
-                                        egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), id, |ui| {
-                                            ui.label(e);
-                                        });
-                                    }
-
-                                    Some(ui.label(format!(
-                                        "{} ({}:{})",
-                                        server.alias, server.ip, server.port
-                                    )))
-                                }
-                                None => {
-                                    let dbs = self.db_manager.dbs.clone();
-                                    let server_id_clone = server_id.clone();
-                                    let server_clone = server.clone();
-                                    {
-                                        let mut dbs = dbs.lock().expect("Failed to lock dbs mutex");
-                                        dbs.insert(server_id.clone(), structs::DbState::Loading);
-                                    }
-                                    self.runtime.spawn(async move {
-                                        Self::load_db(server_id_clone, server_clone, dbs).await;
-                                    });
-                                    ui.add(Spinner::new());
-
-                                    None
-                                }
-                            };
-
-                            if let Some(server_button) = server_button {
-                                if server_button.secondary_clicked() {
-                                    ui.memory_mut(|mem| mem.open_popup(id));
-                                }
-
-                                if ui.memory(|mem| mem.is_popup_open(id)) {
-                                    server_button.context_menu(|ui| {
-                                        if ui.button(self.trans.delete()).clicked() {
-                                            ui.memory_mut(|mem| mem.close_popup());
-                                            self.delete_server_window.show = true;
-                                            self.delete_server_window.server = Some(server.clone());
-                                        } else if ui.button(self.trans.edit()).clicked() {
-                                            self.edit_server_window.show = true;
-                                            self.edit_server_window.server = Some(server.clone());
-                                            self.edit_server_window.original_server = Some(server.clone());
-
-                                            self.edit_server_window.name_field = server.alias.clone();
-                                            self.edit_server_window.ip_field = server.ip.clone();
-                                            self.edit_server_window.port_field = server.port.to_string();
-                                            self.edit_server_window.user_field = server.user.clone();
-                                            self.edit_server_window.password_field = server.password.clone();
-                                            self.edit_server_window.service_database_field = server.service_database.clone();
-                                        } else if ui.button(self.trans.reload()).clicked() {
-                                            let dbs = self.db_manager.dbs.clone();
-                                            let config = self.config.clone();
-                                            let idx = idx.clone();
-
-                                            ui.memory_mut(|mem| mem.close_popup());
-
-                                            self.runtime.spawn(async move {
-                                                Self::reload_server(idx, config, dbs).await;
-                                            });
-                                        }
-                                    });
-                                }
-                            }
-                        });
-                    }
-
-                    if ui.button(self.trans.add_server()).clicked() {
-                        self.add_server_window.show = true;
-                    }
-                });
+            self.update_tree(ui);
 
             ui.add_space(32.0);
 
             ui.with_layout(Layout::bottom_up(Align::LEFT), |ui| {
                 ui.add_space(4.0);
 
-                if ui.button(self.trans.settings()).clicked() {
-                    self.settings_window.show = true;
-                }
+                ui.horizontal(|ui| {
+                    if ui.button(self.trans.settings()).clicked() {
+                        self.settings_window.show = true;
+                    }
+                    if ui.button(self.trans.lock()).clicked() {
+                        self.lock();
+                    }
+                });
 
                 ui.separator();
             });
         });
 
         self.update_windows(ctx, _frame);
+        self.update_activity_windows(ctx);
+        self.update_statements_windows(ctx);
+        self.update_role_window(ctx);
+        self.update_database_windows(ctx);
+        self.update_backup_window(ctx);
+        self.update_maintenance_window(ctx);
+        self.update_create_index_window(ctx);
+        self.update_script_window(ctx);
+        self.update_transfer_windows(ctx);
+        self.update_file_windows(ctx);
+        self.check_file_changes();
         self.update_pages(ctx, _frame);
+
+        self.autosave_session();
+    }
+
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        if self.config.settings.restore_session && !self.login_window.show {
+            self.save_session();
+        }
     }
 }
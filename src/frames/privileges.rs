@@ -0,0 +1,283 @@
+use super::Main;
+
+use crate::data::*;
+use crate::database;
+use crate::utils::quote_ident;
+
+use egui::{Button, Color32, ComboBox, Grid, RichText, ScrollArea, Spinner, TextEdit, Ui};
+use log::error;
+use std::sync::{Arc, Mutex};
+
+/// Builds the GRANT and REVOKE statements that turn the granted
+/// privileges of `objects` into the selected ones.
+fn privileges_script(kind: structs::PrivilegeObjectKind, role: &str, objects: &[structs::PrivilegeObject]) -> String {
+    let role = quote_ident(role);
+    let mut statements = Vec::new();
+
+    for object in objects {
+        let grant: Vec<&str> = object
+            .selected
+            .iter()
+            .filter(|privilege| !object.granted.contains(privilege))
+            .map(String::as_str)
+            .collect();
+        let revoke: Vec<&str> = object
+            .granted
+            .iter()
+            .filter(|privilege| !object.selected.contains(privilege))
+            .map(String::as_str)
+            .collect();
+
+        if !grant.is_empty() {
+            statements.push(format!(
+                "GRANT {} ON {} {} TO {};",
+                grant.join(", "),
+                kind.keyword(),
+                object.name,
+                role
+            ));
+        }
+        if !revoke.is_empty() {
+            statements.push(format!(
+                "REVOKE {} ON {} {} FROM {};",
+                revoke.join(", "),
+                kind.keyword(),
+                object.name,
+                role
+            ));
+        }
+    }
+
+    statements.join("\n")
+}
+
+impl Main<'_> {
+    pub(super) fn open_privileges_page(&mut self, server_id: uuid::Uuid, database_name: &str) {
+        if let Some(idx) = self.pages.pages.iter().position(|page| {
+            matches!(&page.page_type, structs::PageType::Privileges(privileges_page)
+                if privileges_page.server_id == server_id && privileges_page.database_name == database_name)
+        }) {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id) else {
+            return;
+        };
+        let Some(database) = self.loaded_database(server_id, database_name) else {
+            return;
+        };
+
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.privileges(), server.page_title(database_name)),
+            page_type: structs::PageType::Privileges(structs::PrivilegesPage {
+                server_id,
+                database_name: database_name.to_string(),
+                database: database.database,
+                kind: structs::PrivilegeObjectKind::default(),
+                role: server.user.clone(),
+                snapshot: Arc::new(Mutex::new(structs::PrivilegesSnapshot::default())),
+                objects: Vec::new(),
+                filter: String::new(),
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    async fn fetch_privileges(
+        database: database::Database,
+        kind: structs::PrivilegeObjectKind,
+        role: String,
+        snapshot: Arc<Mutex<structs::PrivilegesSnapshot>>,
+    ) {
+        let result = async {
+            let roles = database.get_roles().await?;
+            let objects = database.get_privileges(kind, &role).await?;
+
+            Ok::<_, String>((roles, objects))
+        }
+        .await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok((roles, objects)) => {
+                snapshot.roles = roles
+                    .into_iter()
+                    .filter(|role| !role.is_predefined())
+                    .map(|role| role.name)
+                    .collect();
+                snapshot.objects = Some(objects);
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error loading privileges: {}", e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+        snapshot.loaded = true;
+    }
+
+    fn refresh_privileges(runtime: &tokio::runtime::Runtime, privileges_page: &structs::PrivilegesPage) {
+        privileges_page.snapshot.lock().unwrap().loading = true;
+
+        let database = privileges_page.database.clone();
+        let kind = privileges_page.kind;
+        let role = privileges_page.role.clone();
+        let snapshot = privileges_page.snapshot.clone();
+
+        runtime.spawn(async move {
+            Self::fetch_privileges(database, kind, role, snapshot).await;
+        });
+    }
+
+    pub(super) fn update_privileges_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::Privileges(privileges_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = {
+            let mut snapshot = privileges_page.snapshot.lock().unwrap();
+            if let Some(objects) = snapshot.objects.take() {
+                privileges_page.objects = objects;
+            }
+            snapshot.clone()
+        };
+        let mut refresh = !snapshot.loaded && !snapshot.loading;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!snapshot.loading, Button::new(self.trans.refresh())).clicked() {
+                refresh = true;
+            }
+            if snapshot.loading {
+                ui.add(Spinner::new());
+            }
+
+            ui.separator();
+
+            ui.label(self.trans.role());
+            ComboBox::from_id_salt(("privileges_role", page_index))
+                .selected_text(&privileges_page.role)
+                .show_ui(ui, |ui| {
+                    for role in snapshot.roles.iter() {
+                        if ui
+                            .selectable_label(privileges_page.role == *role, role)
+                            .clicked()
+                            && privileges_page.role != *role
+                        {
+                            privileges_page.role = role.clone();
+                            refresh = true;
+                        }
+                    }
+                });
+
+            ComboBox::from_id_salt(("privileges_kind", page_index))
+                .selected_text(privileges_page.kind.keyword())
+                .show_ui(ui, |ui| {
+                    for kind in structs::PrivilegeObjectKind::ALL {
+                        if ui
+                            .selectable_label(privileges_page.kind == kind, kind.keyword())
+                            .clicked()
+                            && privileges_page.kind != kind
+                        {
+                            privileges_page.kind = kind;
+                            refresh = true;
+                        }
+                    }
+                });
+        });
+
+        ui.add(
+            TextEdit::singleline(&mut privileges_page.filter)
+                .hint_text(self.trans.search())
+                .background_color(self.config.settings.theme.text_input_color()),
+        );
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        if refresh && !snapshot.loading {
+            Self::refresh_privileges(&self.runtime, privileges_page);
+        }
+
+        let script = privileges_script(privileges_page.kind, &privileges_page.role, &privileges_page.objects);
+        let mut review = false;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!script.is_empty(), Button::new(self.trans.review())).clicked() {
+                review = true;
+            }
+            if ui.add_enabled(!script.is_empty(), Button::new(self.trans.discard())).clicked() {
+                for object in privileges_page.objects.iter_mut() {
+                    object.selected = object.granted.clone();
+                }
+            }
+        });
+
+        ui.separator();
+
+        let filter = privileges_page.filter.trim().to_lowercase();
+        let privileges = privileges_page.kind.privileges();
+
+        ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+            Grid::new(("privileges_grid", page_index))
+                .num_columns(privileges.len() + 1)
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(RichText::new(self.trans.name()).strong());
+                    for privilege in privileges {
+                        ui.label(RichText::new(*privilege).strong());
+                    }
+                    ui.end_row();
+
+                    for object in privileges_page
+                        .objects
+                        .iter_mut()
+                        .filter(|object| filter.is_empty() || object.name.to_lowercase().contains(&filter))
+                    {
+                        ui.label(&object.name);
+
+                        for privilege in privileges {
+                            let mut checked = object.selected.iter().any(|selected| selected == privilege);
+                            let effective = object.effective.iter().any(|effective| effective == privilege);
+
+                            ui.horizontal(|ui| {
+                                if ui.checkbox(&mut checked, "").changed() {
+                                    if checked {
+                                        object.selected.push(privilege.to_string());
+                                    } else {
+                                        object.selected.retain(|selected| selected != privilege);
+                                    }
+                                }
+                                if effective && !checked {
+                                    ui.label(RichText::new("✔").color(Color32::GRAY))
+                                        .on_hover_text(self.trans.effective_privilege());
+                                }
+                            });
+                        }
+                        ui.end_row();
+                    }
+                });
+        });
+
+        if review {
+            let server_id = privileges_page.server_id;
+            let database_name = privileges_page.database_name.clone();
+
+            self.open_script_window(
+                self.trans.review(),
+                server_id,
+                &database_name,
+                script,
+                None,
+                structs::AfterScript::ReloadPrivileges(page_index),
+            );
+        }
+    }
+}
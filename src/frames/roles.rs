@@ -0,0 +1,378 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+use crate::utils::{self, quote_ident, quote_literal};
+
+use egui::{
+    Align, CollapsingHeader, Color32, DragValue, Grid, Id, Layout, Modal, RichText, ScrollArea,
+    Spinner, TextEdit, Ui,
+};
+use log::error;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+fn flag(enabled: bool, name: &str) -> String {
+    if enabled {
+        name.to_string()
+    } else {
+        format!("NO{}", name)
+    }
+}
+
+/// Role options that differ from `original`, or all of them for a new role.
+/// `password` is the SCRAM verifier to set, empty to keep the current one.
+fn role_options(
+    original: Option<&structs::RoleInfo>,
+    role: &structs::RoleInfo,
+    password: &str,
+    mask_password: bool,
+) -> Vec<String> {
+    let mut options = Vec::new();
+
+    let flags = [
+        (role.login, original.map(|original| original.login), "LOGIN"),
+        (role.superuser, original.map(|original| original.superuser), "SUPERUSER"),
+        (role.create_db, original.map(|original| original.create_db), "CREATEDB"),
+        (role.create_role, original.map(|original| original.create_role), "CREATEROLE"),
+        (role.inherit, original.map(|original| original.inherit), "INHERIT"),
+        (role.replication, original.map(|original| original.replication), "REPLICATION"),
+    ];
+    for (value, original, name) in flags {
+        if original != Some(value) {
+            options.push(flag(value, name));
+        }
+    }
+
+    if original.map(|original| original.connection_limit) != Some(role.connection_limit) {
+        options.push(format!("CONNECTION LIMIT {}", role.connection_limit));
+    }
+
+    if !password.is_empty() {
+        options.push(format!(
+            "PASSWORD {}",
+            if mask_password { String::from("'********'") } else { quote_literal(password) }
+        ));
+    }
+
+    let valid_until = role.valid_until.as_deref().unwrap_or_default().trim();
+    if original.map(|original| original.valid_until.as_deref().unwrap_or_default()) != Some(valid_until)
+        && !(original.is_none() && valid_until.is_empty())
+    {
+        options.push(format!(
+            "VALID UNTIL {}",
+            quote_literal(if valid_until.is_empty() { "infinity" } else { valid_until })
+        ));
+    }
+
+    options
+}
+
+/// Builds the statements that create `role`, or turn `original` into it.
+fn role_script(
+    original: Option<&structs::RoleInfo>,
+    role: &structs::RoleInfo,
+    password: &str,
+    mask_password: bool,
+) -> String {
+    let mut statements = Vec::new();
+    let name = quote_ident(&role.name);
+    let options = role_options(original, role, password, mask_password);
+
+    match original {
+        None => statements.push(format!("CREATE ROLE {} WITH {};", name, options.join(" "))),
+        Some(original) => {
+            if original.name != role.name {
+                statements.push(format!(
+                    "ALTER ROLE {} RENAME TO {};",
+                    quote_ident(&original.name),
+                    name
+                ));
+            }
+            if !options.is_empty() {
+                statements.push(format!("ALTER ROLE {} WITH {};", name, options.join(" ")));
+            }
+        }
+    }
+
+    let member_of = original.map(|original| original.member_of.as_slice()).unwrap_or_default();
+
+    for group in role.member_of.iter().filter(|group| !member_of.contains(group)) {
+        statements.push(format!("GRANT {} TO {};", quote_ident(group), name));
+    }
+    for group in member_of.iter().filter(|group| !role.member_of.contains(group)) {
+        statements.push(format!("REVOKE {} FROM {};", quote_ident(group), name));
+    }
+
+    statements.join("\n")
+}
+
+impl Main<'_> {
+    async fn load_roles(
+        server: structs::Server,
+        roles: Arc<Mutex<HashMap<Uuid, structs::RolesState>>>,
+    ) {
+        let result = server.connect(&server.service_database).get_roles().await;
+
+        let state = match result {
+            Ok(loaded) => structs::RolesState::Loaded(loaded),
+            Err(e) => {
+                error!("Error loading roles for server {}: {}", server.ip, e);
+                structs::RolesState::Error(e)
+            }
+        };
+
+        roles.lock().unwrap().insert(server.id, state);
+    }
+
+    pub(super) fn show_roles(&mut self, ui: &mut Ui, server: &structs::Server) {
+        let header = CollapsingHeader::new(self.trans.roles())
+            .id_salt(format!("server:{}:roles", server.id))
+            .show(ui, |ui| {
+                let state = {
+                    let mut roles = self.db_manager.roles.lock().unwrap();
+                    if !roles.contains_key(&server.id) {
+                        roles.insert(server.id, structs::RolesState::Loading);
+
+                        let roles = self.db_manager.roles.clone();
+                        let server = server.clone();
+                        self.runtime.spawn(async move {
+                            Self::load_roles(server, roles).await;
+                        });
+                    }
+                    roles.get(&server.id).cloned().unwrap()
+                };
+
+                let roles = match state {
+                    structs::RolesState::Loading => {
+                        ui.add(Spinner::new());
+                        return;
+                    }
+                    structs::RolesState::Loaded(roles) => roles,
+                    structs::RolesState::Error(e) => {
+                        ui.label(RichText::new(e).color(Color32::RED));
+                        return;
+                    }
+                };
+
+                for role in roles.iter().filter(|role| !role.is_predefined()) {
+                    let mut attributes = Vec::new();
+                    if role.superuser {
+                        attributes.push("superuser");
+                    }
+                    if role.login {
+                        attributes.push("login");
+                    }
+
+                    let label = ui
+                        .label(if attributes.is_empty() {
+                            role.name.clone()
+                        } else {
+                            format!("{} ({})", role.name, attributes.join(", "))
+                        })
+                        .on_hover_text(if role.member_of.is_empty() {
+                            String::new()
+                        } else {
+                            format!("{}: {}", self.trans.member_of(), role.member_of.join(", "))
+                        });
+
+                    label.context_menu(|ui| {
+                        if ui.button(self.trans.edit()).clicked() {
+                            self.open_role_window(server.id, Some(role.clone()), &roles);
+                            ui.close_menu();
+                        }
+                        if ui.button(self.trans.delete()).clicked() {
+                            self.open_script_window(
+                                self.trans.delete_role(),
+                                server.id,
+                                &server.service_database,
+                                format!("DROP ROLE {};", quote_ident(&role.name)),
+                                None,
+                                structs::AfterScript::ReloadRoles(server.id),
+                            );
+                            ui.close_menu();
+                        }
+                    });
+                }
+
+                if ui.button(self.trans.create_role()).clicked() {
+                    self.open_role_window(server.id, None, &roles);
+                }
+            })
+            .header_response;
+
+        header.context_menu(|ui| {
+            if ui.button(self.trans.reload()).clicked() {
+                self.db_manager.roles.lock().unwrap().remove(&server.id);
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn open_role_window(
+        &mut self,
+        server_id: Uuid,
+        role: Option<structs::RoleInfo>,
+        roles: &[structs::RoleInfo],
+    ) {
+        let name = role.as_ref().map(|role| role.name.clone()).unwrap_or_default();
+
+        self.role_window = structs::RoleWindow {
+            show: true,
+            server_id,
+            role: role.clone().unwrap_or(structs::RoleInfo {
+                inherit: true,
+                connection_limit: -1,
+                ..Default::default()
+            }),
+            original: role,
+            password: String::new(),
+            available_roles: roles
+                .iter()
+                .map(|role| role.name.clone())
+                .filter(|role| *role != name)
+                .collect(),
+        };
+    }
+
+    pub(super) fn update_role_window(&mut self, ctx: &egui::Context) {
+        if !self.role_window.show {
+            return;
+        }
+
+        Modal::new(Id::new("role_modal")).show(ctx, |ui| {
+            ui.set_width(420.0);
+
+            widgets::modal_label(
+                ui,
+                if self.role_window.original.is_some() {
+                    self.trans.edit_role()
+                } else {
+                    self.trans.create_role()
+                },
+            );
+
+            let input_color = self.config.settings.theme.text_input_color();
+            let window = &mut self.role_window;
+
+            Grid::new("role_form")
+                .num_columns(2)
+                .spacing([40.0, 4.0])
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label(self.trans.name());
+                    TextEdit::singleline(&mut window.role.name)
+                        .background_color(input_color)
+                        .show(ui);
+                    ui.end_row();
+
+                    ui.label(self.trans.password());
+                    TextEdit::singleline(&mut window.password)
+                        .background_color(input_color)
+                        .password(true)
+                        .hint_text(if window.original.is_some() {
+                            self.trans.unchanged()
+                        } else {
+                            String::new()
+                        })
+                        .show(ui);
+                    ui.end_row();
+
+                    ui.label(self.trans.valid_until());
+                    let mut valid_until = window.role.valid_until.clone().unwrap_or_default();
+                    if TextEdit::singleline(&mut valid_until)
+                        .background_color(input_color)
+                        .hint_text("2030-01-01")
+                        .show(ui)
+                        .response
+                        .changed()
+                    {
+                        window.role.valid_until = Some(valid_until).filter(|value| !value.is_empty());
+                    }
+                    ui.end_row();
+
+                    ui.label(self.trans.connection_limit());
+                    ui.add(DragValue::new(&mut window.role.connection_limit).range(-1..=i32::MAX));
+                    ui.end_row();
+                });
+
+            ui.add_space(4.0);
+
+            ui.horizontal_wrapped(|ui| {
+                ui.checkbox(&mut window.role.login, "LOGIN");
+                ui.checkbox(&mut window.role.superuser, "SUPERUSER");
+                ui.checkbox(&mut window.role.create_db, "CREATEDB");
+                ui.checkbox(&mut window.role.create_role, "CREATEROLE");
+                ui.checkbox(&mut window.role.inherit, "INHERIT");
+                ui.checkbox(&mut window.role.replication, "REPLICATION");
+            });
+
+            ui.add_space(4.0);
+
+            CollapsingHeader::new(self.trans.member_of())
+                .id_salt("role_member_of")
+                .show(ui, |ui| {
+                    ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                        for group in window.available_roles.iter() {
+                            let mut is_member = window.role.member_of.contains(group);
+                            if ui.checkbox(&mut is_member, group).changed() {
+                                if is_member {
+                                    window.role.member_of.push(group.clone());
+                                } else {
+                                    window.role.member_of.retain(|member_of| member_of != group);
+                                }
+                            }
+                        }
+                    });
+                });
+
+            let can_review = !window.role.name.trim().is_empty()
+                && !role_script(window.original.as_ref(), &window.role, &window.password, true).is_empty();
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(can_review, egui::Button::new(self.trans.review())).clicked() {
+                        let window = &self.role_window;
+                        let server_id = window.server_id;
+                        let password = if window.password.is_empty() {
+                            Ok(String::new())
+                        } else {
+                            utils::scram_sha_256(&window.password)
+                        };
+                        let password = match password {
+                            Ok(password) => password,
+                            Err(e) => {
+                                self.show_error(e);
+                                return;
+                            }
+                        };
+                        let script = role_script(window.original.as_ref(), &window.role, &password, false);
+                        let masked_script = (!window.password.is_empty())
+                            .then(|| role_script(window.original.as_ref(), &window.role, &window.password, true));
+                        let service_database = self
+                            .config
+                            .servers
+                            .iter()
+                            .find(|server| server.id == server_id)
+                            .map(|server| server.service_database.clone())
+                            .unwrap_or_default();
+
+                        self.open_script_window(
+                            self.trans.review(),
+                            server_id,
+                            &service_database,
+                            script,
+                            masked_script,
+                            structs::AfterScript::ReloadRoles(server_id),
+                        );
+                    }
+                    if ui.button(self.trans.close()).clicked() {
+                        self.role_window = structs::RoleWindow::default();
+                    }
+                });
+            });
+        });
+    }
+}
@@ -0,0 +1,709 @@
+use super::Main;
+
+use crate::data::*;
+use crate::database;
+
+use egui::{Button, Color32, ComboBox, Grid, RichText, ScrollArea, Spinner, TextEdit, Ui};
+use log::error;
+use indexmap::IndexMap;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+fn is_foreign_key(object: &structs::SchemaObject) -> bool {
+    object.kind == structs::SchemaObjectKind::Constraint && object.definition.starts_with("FOREIGN KEY")
+}
+
+/// Whether `object` is a primary key or unique constraint, which foreign
+/// keys may depend on.
+fn is_referenced_key(object: &structs::SchemaObject) -> bool {
+    object.kind == structs::SchemaObjectKind::Constraint
+        && (object.definition.starts_with("PRIMARY KEY") || object.definition.starts_with("UNIQUE"))
+}
+
+/// Pairs up the objects of both databases by kind and name. Columns of
+/// added tables go into their `CREATE TABLE`, and columns, constraints and
+/// indexes of removed tables go away with them, so neither is listed. Foreign
+/// keys of removed tables are, to drop them before the tables they reference.
+/// Unchanged foreign keys are listed as changed when a key of the table they
+/// reference is dropped, so they are dropped first and added back after.
+fn compare_schemas(
+    source: Vec<structs::SchemaObject>,
+    target: Vec<structs::SchemaObject>,
+) -> Vec<structs::SchemaDifference> {
+    let key = |object: &structs::SchemaObject| (object.kind, object.table.clone(), object.name.clone());
+    let source_keys: HashSet<_> = source.iter().map(key).collect();
+    let mut target: IndexMap<_, structs::SchemaObject> = target.into_iter().map(|object| (key(&object), object)).collect();
+
+    let tables = |objects: &mut dyn Iterator<Item = &structs::SchemaObject>| -> HashSet<String> {
+        objects
+            .filter(|object| object.kind == structs::SchemaObjectKind::Table)
+            .map(|object| object.name.clone())
+            .collect()
+    };
+    let source_tables = tables(&mut source.iter());
+    let target_tables = tables(&mut target.values());
+
+    let mut differences = Vec::new();
+    let mut unchanged_foreign_keys = Vec::new();
+
+    for object in source {
+        let difference = match target.shift_remove(&key(&object)) {
+            None => {
+                if object.kind == structs::SchemaObjectKind::Column
+                    && object.table.as_ref().is_some_and(|table| !target_tables.contains(table))
+                {
+                    continue;
+                }
+                (structs::DiffStatus::Added, Some(object), None)
+            }
+            // Changes of a table show up in its columns.
+            Some(target) if target.definition != object.definition && object.kind != structs::SchemaObjectKind::Table => {
+                (structs::DiffStatus::Changed, Some(object), Some(target))
+            }
+            Some(target) if is_foreign_key(&target) => {
+                unchanged_foreign_keys.push((object, target));
+                continue;
+            }
+            Some(_) => continue,
+        };
+        differences.push(difference);
+    }
+
+    differences.extend(
+        target
+            .into_values()
+            .filter(|object| !source_keys.contains(&key(object)))
+            .filter(|object| {
+                object.table.as_ref().is_none_or(|table| source_tables.contains(table)) || is_foreign_key(object)
+            })
+            .map(|object| (structs::DiffStatus::Removed, None, Some(object))),
+    );
+
+    let dropped_keys: HashSet<String> = differences
+        .iter()
+        .filter(|(status, ..)| *status != structs::DiffStatus::Added)
+        .filter_map(|(_, _, target)| target.as_ref())
+        .filter(|target| is_referenced_key(target))
+        .filter_map(|target| target.table.clone())
+        .collect();
+    differences.extend(
+        unchanged_foreign_keys
+            .into_iter()
+            .filter(|(_, target)| target.references.as_ref().is_some_and(|table| dropped_keys.contains(table)))
+            .map(|(source, target)| (structs::DiffStatus::Changed, Some(source), Some(target))),
+    );
+
+    let mut differences: Vec<structs::SchemaDifference> = differences
+        .into_iter()
+        .map(|(status, source, target)| {
+            let object = source.as_ref().or(target.as_ref()).unwrap();
+            structs::SchemaDifference {
+                kind: object.kind,
+                table: object.table.clone(),
+                name: object.name.clone(),
+                status,
+                source: source.clone(),
+                target,
+                selected: true,
+            }
+        })
+        .collect();
+    differences.sort_by_key(|difference| difference.kind);
+
+    differences
+}
+
+/// Steps of the migration, in the order they run: dependants are dropped
+/// before what they depend on, and created after it.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Step {
+    DropView,
+    DropForeignKey,
+    DropConstraint,
+    DropIndex,
+    DropColumn,
+    DropTable,
+    DropFunction,
+    DropSequence,
+    DropSchema,
+    CreateSchema,
+    CreateSequence,
+    CreateFunction,
+    CreateTable,
+    AlterColumn,
+    AddConstraint,
+    CreateIndex,
+    AddForeignKey,
+    CreateView,
+}
+
+fn alter_column_statements(table: &str, name: &str, old: &structs::SchemaColumn, new: &structs::SchemaColumn) -> Vec<String> {
+    let alter = |action: String| format!("ALTER TABLE {} ALTER COLUMN {} {};", table, name, action);
+
+    // Generation expressions can't be altered, the column is added again.
+    if old.generated != new.generated {
+        return vec![
+            format!("ALTER TABLE {} DROP COLUMN {};", table, name),
+            format!("ALTER TABLE {} ADD COLUMN {} {};", table, name, new.definition()),
+        ];
+    }
+
+    let mut statements = Vec::new();
+    if new.not_null && !old.not_null {
+        statements.push(alter(String::from("SET NOT NULL")));
+    }
+    if old.identity.is_some() && new.identity.is_none() {
+        statements.push(alter(String::from("DROP IDENTITY")));
+    }
+    if old.data_type != new.data_type {
+        statements.push(alter(format!("TYPE {0} USING {1}::{0}", new.data_type, name)));
+    }
+    if old.default.is_some() && new.default.is_none() {
+        statements.push(alter(String::from("DROP DEFAULT")));
+    }
+    match (&old.identity, &new.identity) {
+        (None, Some(identity)) => statements.push(alter(format!("ADD GENERATED {} AS IDENTITY", identity))),
+        (Some(old_identity), Some(identity)) if old_identity != identity => {
+            statements.push(alter(format!("SET GENERATED {}", identity)))
+        }
+        _ => {}
+    }
+    if let Some(default) = &new.default
+        && old.default.as_ref() != Some(default)
+    {
+        statements.push(alter(format!("SET DEFAULT {}", default)));
+    }
+    if old.not_null && !new.not_null {
+        statements.push(alter(String::from("DROP NOT NULL")));
+    }
+
+    statements
+}
+
+fn difference_statements(difference: &structs::SchemaDifference) -> Vec<(Step, String)> {
+    use structs::{DiffStatus, SchemaObjectKind};
+
+    let table = difference.table.as_deref().unwrap_or_default();
+    let name = &difference.name;
+    let definition = difference
+        .source
+        .as_ref()
+        .map(|source| source.definition.trim().trim_end_matches(';'))
+        .unwrap_or_default();
+    let foreign_key = difference.source.as_ref().or(difference.target.as_ref()).is_some_and(is_foreign_key);
+
+    let drop = match difference.kind {
+        SchemaObjectKind::Schema => (Step::DropSchema, format!("DROP SCHEMA {};", name)),
+        // Sequences owned by a column go away with its table.
+        SchemaObjectKind::Sequence => (Step::DropSequence, format!("DROP SEQUENCE IF EXISTS {};", name)),
+        SchemaObjectKind::Table => (Step::DropTable, format!("DROP TABLE {};", name)),
+        SchemaObjectKind::Column => (Step::DropColumn, format!("ALTER TABLE {} DROP COLUMN {};", table, name)),
+        SchemaObjectKind::Constraint => (
+            if foreign_key { Step::DropForeignKey } else { Step::DropConstraint },
+            format!("ALTER TABLE {} DROP CONSTRAINT {};", table, name),
+        ),
+        SchemaObjectKind::Index => (Step::DropIndex, format!("DROP INDEX {};", name)),
+        SchemaObjectKind::Function => (Step::DropFunction, format!("DROP ROUTINE {};", name)),
+        SchemaObjectKind::View => (Step::DropView, format!("DROP VIEW {};", name)),
+    };
+
+    let create = match difference.kind {
+        SchemaObjectKind::Schema => (Step::CreateSchema, format!("CREATE SCHEMA {};", name)),
+        SchemaObjectKind::Sequence => (Step::CreateSequence, format!("CREATE SEQUENCE {} {};", name, definition)),
+        SchemaObjectKind::Table => (Step::CreateTable, format!("CREATE TABLE {} {};", name, definition)),
+        SchemaObjectKind::Column => (
+            Step::AlterColumn,
+            format!("ALTER TABLE {} ADD COLUMN {} {};", table, name, definition),
+        ),
+        SchemaObjectKind::Constraint => (
+            if foreign_key { Step::AddForeignKey } else { Step::AddConstraint },
+            format!("ALTER TABLE {} ADD CONSTRAINT {} {};", table, name, definition),
+        ),
+        SchemaObjectKind::Index => (Step::CreateIndex, format!("{};", definition)),
+        SchemaObjectKind::Function => (Step::CreateFunction, format!("{};", definition)),
+        SchemaObjectKind::View => (Step::CreateView, format!("CREATE VIEW {} AS\n{};", name, definition)),
+    };
+
+    match difference.status {
+        DiffStatus::Added => vec![create],
+        DiffStatus::Removed => vec![drop],
+        DiffStatus::Changed => match difference.kind {
+            SchemaObjectKind::Sequence => vec![(Step::CreateSequence, format!("ALTER SEQUENCE {} {};", name, definition))],
+            SchemaObjectKind::Function => vec![create],
+            SchemaObjectKind::Column => {
+                let (Some(old), Some(new)) = (
+                    difference.target.as_ref().and_then(|target| target.column.as_ref()),
+                    difference.source.as_ref().and_then(|source| source.column.as_ref()),
+                ) else {
+                    return Vec::new();
+                };
+                alter_column_statements(table, name, old, new)
+                    .into_iter()
+                    .map(|statement| (Step::AlterColumn, statement))
+                    .collect()
+            }
+            _ => vec![drop, create],
+        },
+    }
+}
+
+/// Builds the statements that bring the target in line with the source
+/// for the selected differences. Objects come in creation order, so drops
+/// of the same step run backwards to drop dependants first.
+fn migration_script(differences: &[structs::SchemaDifference]) -> String {
+    let mut statements: Vec<(usize, Step, String)> = differences
+        .iter()
+        .filter(|difference| difference.selected)
+        .enumerate()
+        .flat_map(|(index, difference)| {
+            difference_statements(difference)
+                .into_iter()
+                .map(move |(step, statement)| (index, step, statement))
+        })
+        .collect();
+    statements.sort_by_key(|(index, step, _)| {
+        (*step, if *step < Step::CreateSchema { usize::MAX - index } else { *index })
+    });
+    let statements: Vec<(Step, String)> = statements.into_iter().map(|(_, step, statement)| (step, statement)).collect();
+
+    let mut script: Vec<String> = Vec::new();
+    // Function bodies may refer to tables created further down.
+    if statements.iter().any(|(step, _)| *step == Step::CreateFunction) {
+        script.push(String::from("SET check_function_bodies = false;"));
+    }
+    script.extend(statements.into_iter().map(|(_, statement)| statement));
+
+    script.join("\n")
+}
+
+/// Picks one of `databases`, as listed by `Main::loaded_databases`.
+pub(super) fn database_picker(
+    ui: &mut Ui,
+    id_salt: impl std::hash::Hash,
+    databases: &[(Uuid, String, String)],
+    selection: &mut Option<(Uuid, String)>,
+) {
+    let selected_text = databases
+        .iter()
+        .find(|(server_id, database_name, _)| selection.as_ref() == Some(&(*server_id, database_name.clone())))
+        .map(|(_, _, label)| label.clone())
+        .unwrap_or_default();
+
+    ComboBox::from_id_salt(id_salt)
+        .selected_text(selected_text)
+        .width(220.0)
+        .show_ui(ui, |ui| {
+            for (server_id, database_name, label) in databases.iter() {
+                let database = Some((*server_id, database_name.clone()));
+                if ui.selectable_label(*selection == database, label).clicked() {
+                    *selection = database;
+                }
+            }
+        });
+}
+
+fn object_label(difference: &structs::SchemaDifference) -> String {
+    match (&difference.table, difference.kind) {
+        (Some(table), structs::SchemaObjectKind::Column | structs::SchemaObjectKind::Constraint) => {
+            format!("{}.{}", table, difference.name)
+        }
+        _ => difference.name.clone(),
+    }
+}
+
+impl Main<'_> {
+    pub(super) fn open_schema_diff(&mut self, server_id: Uuid, database_name: &str) {
+        self.pages.pages.push(structs::Page {
+            title: self.trans.schema_compare(),
+            page_type: structs::PageType::SchemaDiff(structs::SchemaDiffPage {
+                source: Some((server_id, database_name.to_string())),
+                target: None,
+                snapshot: Arc::new(Mutex::new(structs::SchemaDiffSnapshot::default())),
+                differences: Vec::new(),
+                compared: false,
+                selected: None,
+                filter: String::new(),
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    /// Server, database name and label of every loaded database.
+    pub(super) fn loaded_databases(&self) -> Vec<(Uuid, String, String)> {
+        let dbs = self.db_manager.dbs.lock().unwrap();
+
+        self.config
+            .servers
+            .iter()
+            .flat_map(|server| match dbs.get(&server.id) {
+                Some(structs::DbState::Loaded(databases)) => databases
+                    .iter()
+                    .map(|database| {
+                        (
+                            server.id,
+                            database.name.clone(),
+                            format!("{} / {}", server.alias, database.name),
+                        )
+                    })
+                    .collect(),
+                _ => Vec::new(),
+            })
+            .collect()
+    }
+
+    async fn fetch_schema_diff(
+        source: database::Database,
+        target: database::Database,
+        snapshot: Arc<Mutex<structs::SchemaDiffSnapshot>>,
+    ) {
+        let result = async {
+            let source = source.get_schema_objects().await?;
+            let target = target.get_schema_objects().await?;
+
+            Ok::<_, String>(compare_schemas(source, target))
+        }
+        .await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok(differences) => {
+                snapshot.differences = Some(differences);
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error comparing schemas: {}", e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+    }
+
+    pub(super) fn refresh_schema_diff(&self, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::SchemaDiff(schema_diff_page),
+            ..
+        }) = self.pages.pages.get(page_index)
+        else {
+            return;
+        };
+
+        let database = |database: &Option<(Uuid, String)>| {
+            database
+                .as_ref()
+                .and_then(|(server_id, database_name)| self.loaded_database(*server_id, database_name))
+                .map(|database| database.database)
+        };
+        let (Some(source), Some(target)) = (database(&schema_diff_page.source), database(&schema_diff_page.target)) else {
+            schema_diff_page.snapshot.lock().unwrap().error = Some(self.trans.database_not_loaded());
+            return;
+        };
+
+        schema_diff_page.snapshot.lock().unwrap().loading = true;
+
+        let snapshot = schema_diff_page.snapshot.clone();
+        self.runtime.spawn(async move {
+            Self::fetch_schema_diff(source, target, snapshot).await;
+        });
+    }
+
+    pub(super) fn update_schema_diff_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let databases = self.loaded_databases();
+
+        let Some(structs::Page {
+            page_type: structs::PageType::SchemaDiff(schema_diff_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = {
+            let mut snapshot = schema_diff_page.snapshot.lock().unwrap();
+            if let Some(differences) = snapshot.differences.take() {
+                schema_diff_page.differences = differences;
+                schema_diff_page.compared = true;
+                schema_diff_page.selected = None;
+            }
+            snapshot.clone()
+        };
+        let mut compare = false;
+
+        ui.horizontal(|ui| {
+            ui.label(self.trans.source());
+            database_picker(ui, ("schema_diff_source", page_index), &databases, &mut schema_diff_page.source);
+            ui.label(self.trans.target());
+            database_picker(ui, ("schema_diff_target", page_index), &databases, &mut schema_diff_page.target);
+
+            if ui.button("⇄").clicked() {
+                std::mem::swap(&mut schema_diff_page.source, &mut schema_diff_page.target);
+                compare = schema_diff_page.compared;
+            }
+
+            let can_compare = schema_diff_page.source.is_some() && schema_diff_page.target.is_some() && !snapshot.loading;
+            if ui.add_enabled(can_compare, Button::new(self.trans.compare())).clicked() {
+                compare = true;
+            }
+            if snapshot.loading {
+                ui.add(Spinner::new());
+            }
+        });
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        if !schema_diff_page.compared {
+            if compare {
+                self.refresh_schema_diff(page_index);
+            }
+            return;
+        }
+
+        let script = migration_script(&schema_diff_page.differences);
+        let mut review = false;
+
+        ui.horizontal(|ui| {
+            ui.add(
+                TextEdit::singleline(&mut schema_diff_page.filter)
+                    .hint_text(self.trans.search())
+                    .background_color(self.config.settings.theme.text_input_color()),
+            );
+
+            let count = |status: structs::DiffStatus| {
+                schema_diff_page
+                    .differences
+                    .iter()
+                    .filter(|difference| difference.status == status)
+                    .count()
+            };
+            ui.label(format!(
+                "{}: {}, {}: {}, {}: {}",
+                self.trans.added(),
+                count(structs::DiffStatus::Added),
+                self.trans.removed(),
+                count(structs::DiffStatus::Removed),
+                self.trans.changed(),
+                count(structs::DiffStatus::Changed)
+            ));
+
+            ui.separator();
+
+            if ui.button(self.trans.select_all()).clicked() {
+                schema_diff_page.differences.iter_mut().for_each(|difference| difference.selected = true);
+            }
+            if ui.button(self.trans.select_none()).clicked() {
+                schema_diff_page.differences.iter_mut().for_each(|difference| difference.selected = false);
+            }
+            if ui.add_enabled(!script.is_empty(), Button::new(self.trans.migration_script())).clicked() {
+                review = true;
+            }
+        });
+
+        ui.separator();
+
+        if schema_diff_page.differences.is_empty() {
+            ui.label(self.trans.no_differences());
+        }
+
+        let filter = schema_diff_page.filter.trim().to_lowercase();
+
+        ScrollArea::vertical()
+            .id_salt(("schema_diff_list", page_index))
+            .max_height(ui.available_height() / 2.0)
+            .auto_shrink([false, true])
+            .show(ui, |ui| {
+                Grid::new(("schema_diff_grid", page_index))
+                    .num_columns(4)
+                    .striped(true)
+                    .show(ui, |ui| {
+                        for (index, difference) in schema_diff_page.differences.iter_mut().enumerate() {
+                            let label = object_label(difference);
+                            if !filter.is_empty() && !label.to_lowercase().contains(&filter) {
+                                continue;
+                            }
+
+                            ui.checkbox(&mut difference.selected, "");
+                            let (status, color) = match difference.status {
+                                structs::DiffStatus::Added => (self.trans.added(), Color32::GREEN),
+                                structs::DiffStatus::Removed => (self.trans.removed(), Color32::RED),
+                                structs::DiffStatus::Changed => (self.trans.changed(), Color32::YELLOW),
+                            };
+                            ui.label(RichText::new(status).color(color));
+                            ui.label(difference.kind.keyword());
+                            if ui
+                                .selectable_label(schema_diff_page.selected == Some(index), label)
+                                .clicked()
+                            {
+                                schema_diff_page.selected = Some(index);
+                            }
+                            ui.end_row();
+                        }
+                    });
+            });
+
+        if let Some(difference) = schema_diff_page
+            .selected
+            .and_then(|index| schema_diff_page.differences.get(index))
+        {
+            ui.separator();
+
+            ui.columns(2, |columns| {
+                for (ui, (label, object)) in columns.iter_mut().zip([
+                    (self.trans.source(), &difference.source),
+                    (self.trans.target(), &difference.target),
+                ]) {
+                    ui.label(RichText::new(&label).strong());
+                    ScrollArea::both()
+                        .id_salt((label.as_str(), page_index))
+                        .auto_shrink([false, true])
+                        .show(ui, |ui| {
+                            let mut definition = object
+                                .as_ref()
+                                .map(|object| object.definition.as_str())
+                                .unwrap_or_default();
+                            ui.add(TextEdit::multiline(&mut definition).code_editor().desired_width(f32::INFINITY));
+                        });
+                }
+            });
+        }
+
+        if compare {
+            self.refresh_schema_diff(page_index);
+        }
+
+        if review
+            && let Some(structs::Page {
+                page_type: structs::PageType::SchemaDiff(structs::SchemaDiffPage {
+                    target: Some((server_id, database_name)),
+                    ..
+                }),
+                ..
+            }) = self.pages.pages.get(page_index)
+        {
+            let server_id = *server_id;
+            let database_name = database_name.clone();
+
+            self.open_script_window(
+                self.trans.migration_script(),
+                server_id,
+                &database_name,
+                script,
+                None,
+                structs::AfterScript::ReloadSchemaDiff(page_index),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use structs::SchemaObjectKind::{Column, Constraint, Index, Table, View};
+
+    fn object(kind: structs::SchemaObjectKind, table: Option<&str>, name: &str, definition: &str) -> structs::SchemaObject {
+        structs::SchemaObject {
+            kind,
+            table: table.map(str::to_string),
+            name: name.to_string(),
+            definition: definition.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn foreign_key(table: &str, name: &str, references: &str) -> structs::SchemaObject {
+        structs::SchemaObject {
+            references: Some(references.to_string()),
+            ..object(Constraint, Some(table), name, "FOREIGN KEY (a_id) REFERENCES a(id)")
+        }
+    }
+
+    fn table(name: &str) -> Vec<structs::SchemaObject> {
+        vec![
+            object(Table, None, name, "(\n    id integer\n)"),
+            object(Column, Some(name), "id", "integer"),
+            object(Constraint, Some(name), &format!("{}_pkey", &name[name.find('.').unwrap() + 1..]), "PRIMARY KEY (id)"),
+        ]
+    }
+
+    #[test]
+    fn orders_migration_steps() {
+        let mut source = table("public.a");
+        source.extend(table("public.b"));
+        source.extend([
+            foreign_key("public.b", "b_a_fkey", "public.a"),
+            object(Index, Some("public.b"), "public.b_idx", "CREATE INDEX b_idx ON public.b USING btree (id)"),
+            object(View, None, "public.v1", "SELECT 1"),
+            object(View, None, "public.v2", "SELECT * FROM v1"),
+        ]);
+
+        let mut target = table("public.a");
+        target.extend(table("public.old"));
+        target.extend([
+            foreign_key("public.old", "old_a_fkey", "public.a"),
+            object(View, None, "public.w1", "SELECT 1"),
+            object(View, None, "public.w2", "SELECT * FROM w1"),
+        ]);
+
+        let differences = compare_schemas(source, target);
+
+        // Columns and keys of added and removed tables come with the table.
+        let listed: Vec<(structs::DiffStatus, &str)> = differences
+            .iter()
+            .map(|difference| (difference.status, difference.name.as_str()))
+            .collect();
+        assert_eq!(
+            listed,
+            [
+                (structs::DiffStatus::Added, "public.b"),
+                (structs::DiffStatus::Removed, "public.old"),
+                (structs::DiffStatus::Added, "b_pkey"),
+                (structs::DiffStatus::Added, "b_a_fkey"),
+                (structs::DiffStatus::Removed, "old_a_fkey"),
+                (structs::DiffStatus::Added, "public.b_idx"),
+                (structs::DiffStatus::Added, "public.v1"),
+                (structs::DiffStatus::Added, "public.v2"),
+                (structs::DiffStatus::Removed, "public.w1"),
+                (structs::DiffStatus::Removed, "public.w2"),
+            ]
+        );
+
+        assert_eq!(
+            migration_script(&differences),
+            "DROP VIEW public.w2;\n\
+            DROP VIEW public.w1;\n\
+            ALTER TABLE public.old DROP CONSTRAINT old_a_fkey;\n\
+            DROP TABLE public.old;\n\
+            CREATE TABLE public.b (\n    id integer\n);\n\
+            ALTER TABLE public.b ADD CONSTRAINT b_pkey PRIMARY KEY (id);\n\
+            CREATE INDEX b_idx ON public.b USING btree (id);\n\
+            ALTER TABLE public.b ADD CONSTRAINT b_a_fkey FOREIGN KEY (a_id) REFERENCES a(id);\n\
+            CREATE VIEW public.v1 AS\nSELECT 1;\n\
+            CREATE VIEW public.v2 AS\nSELECT * FROM v1;"
+        );
+    }
+
+    #[test]
+    fn recreates_foreign_keys_of_changed_keys() {
+        let mut source = table("public.a");
+        source[2].definition = String::from("PRIMARY KEY (id, code)");
+        source.extend(table("public.c"));
+        source.push(foreign_key("public.c", "c_a_fkey", "public.a"));
+        source.push(foreign_key("public.c", "c_c_fkey", "public.c"));
+
+        let mut target = table("public.a");
+        target.extend(table("public.c"));
+        target.push(foreign_key("public.c", "c_a_fkey", "public.a"));
+        target.push(foreign_key("public.c", "c_c_fkey", "public.c"));
+
+        assert_eq!(
+            migration_script(&compare_schemas(source, target)),
+            "ALTER TABLE public.c DROP CONSTRAINT c_a_fkey;\n\
+            ALTER TABLE public.a DROP CONSTRAINT a_pkey;\n\
+            ALTER TABLE public.a ADD CONSTRAINT a_pkey PRIMARY KEY (id, code);\n\
+            ALTER TABLE public.c ADD CONSTRAINT c_a_fkey FOREIGN KEY (a_id) REFERENCES a(id);"
+        );
+    }
+}
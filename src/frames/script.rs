@@ -0,0 +1,240 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+use crate::database;
+
+use egui::{Align, Button, Color32, Id, Layout, Modal, RichText, ScrollArea, Spinner, TextEdit};
+use log::error;
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+impl Main<'_> {
+    /// Shows `sql` for review before it is run against `database_name`.
+    pub(super) fn open_script_window(
+        &mut self,
+        title: String,
+        server_id: Uuid,
+        database_name: &str,
+        sql: String,
+        masked_sql: Option<String>,
+        after: structs::AfterScript,
+    ) {
+        let database = self
+            .loaded_database(server_id, database_name)
+            .map(|database| database.database)
+            .or_else(|| {
+                self.config
+                    .servers
+                    .iter()
+                    .find(|server| server.id == server_id)
+                    .map(|server| server.connect(database_name))
+            });
+
+        self.script_window = structs::ScriptWindow {
+            show: true,
+            title,
+            server_id,
+            database_name: database_name.to_string(),
+            database,
+            sql,
+            masked_sql,
+            status: None,
+            after,
+            disconnect: None,
+        };
+    }
+
+    async fn run_script(
+        database: database::Database,
+        sql: String,
+        disconnect: Option<database::Database>,
+        status: Arc<Mutex<structs::CommandStatus>>,
+    ) {
+        if let Some(disconnect) = disconnect {
+            disconnect.close().await;
+        }
+
+        let result = database.execute(&sql).await;
+
+        *status.lock().unwrap() = match result {
+            Ok(()) => structs::CommandStatus::Success,
+            Err(e) => {
+                error!("Error running script: {}", e);
+                structs::CommandStatus::Error(e)
+            }
+        };
+    }
+
+    fn finish_script(&mut self, after: structs::AfterScript) {
+        match after {
+            structs::AfterScript::Nothing => {}
+            structs::AfterScript::ReloadRoles(server_id) => {
+                self.db_manager.roles.lock().unwrap().remove(&server_id);
+                self.role_window = structs::RoleWindow::default();
+            }
+            structs::AfterScript::ReloadPrivileges(page_index) => {
+                if let Some(structs::Page {
+                    page_type: structs::PageType::Privileges(privileges_page),
+                    ..
+                }) = self.pages.pages.get(page_index)
+                {
+                    privileges_page.snapshot.lock().unwrap().loaded = false;
+                }
+            }
+            structs::AfterScript::ReloadDatabases(server_id) => self.reconnect_databases(server_id),
+            structs::AfterScript::RenameDatabase(server_id, old_name, new_name) => {
+                self.rename_database_pages(server_id, &old_name, &new_name);
+                self.reconnect_databases(server_id);
+            }
+            structs::AfterScript::ReloadSchemaDiff(page_index) => self.refresh_schema_diff(page_index),
+            structs::AfterScript::ReloadDataCompare(page_index) => self.refresh_data_compare(page_index),
+            structs::AfterScript::ReloadIndexes(page_index) => {
+                if let Some(structs::Page {
+                    page_type: structs::PageType::Indexes(indexes_page),
+                    ..
+                }) = self.pages.pages.get(page_index)
+                {
+                    indexes_page.snapshot.lock().unwrap().loaded = false;
+                }
+            }
+            structs::AfterScript::ReloadTable(page_index) => {
+                let Some(structs::Page {
+                    page_type: structs::PageType::TableDesigner(table_designer_page),
+                    ..
+                }) = self.pages.pages.get(page_index)
+                else {
+                    return;
+                };
+
+                if let Some(database) =
+                    self.loaded_database(table_designer_page.server_id, &table_designer_page.database_name)
+                {
+                    *database.tables.lock().unwrap() = None;
+                }
+
+                let table = &table_designer_page.table;
+                Self::refresh_table_definition(
+                    &self.runtime,
+                    table_designer_page,
+                    Some(table.schema.trim()),
+                    table.name.trim(),
+                );
+            }
+        }
+    }
+
+    /// Refreshes the database list and rebinds pages, replacing
+    /// connections closed by `ScriptWindow::disconnect`.
+    fn reconnect_databases(&mut self, server_id: Uuid) {
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id).cloned() else {
+            return;
+        };
+
+        self.rebind_pages(&server);
+
+        let dbs = self.db_manager.dbs.clone();
+        self.runtime.spawn(async move {
+            Self::refresh_databases(server, dbs).await;
+        });
+    }
+
+    pub(super) fn update_script_window(&mut self, ctx: &egui::Context) {
+        if !self.script_window.show {
+            return;
+        }
+
+        let status = self
+            .script_window
+            .status
+            .as_ref()
+            .map(|status| status.lock().unwrap().clone());
+
+        if let Some(structs::CommandStatus::Success) = status {
+            let window = std::mem::take(&mut self.script_window);
+            self.finish_script(window.after);
+
+            return;
+        }
+        if let Some(structs::CommandStatus::Error(_)) = status
+            && self.script_window.disconnect.take().is_some()
+        {
+            self.reconnect_databases(self.script_window.server_id);
+        }
+
+        let running = matches!(status, Some(structs::CommandStatus::Running));
+
+        Modal::new(Id::new("script_modal")).show(ctx, |ui| {
+            ui.set_width(560.0);
+
+            widgets::modal_label(ui, &self.script_window.title);
+
+            let mut sql = self
+                .script_window
+                .masked_sql
+                .clone()
+                .unwrap_or_else(|| self.script_window.sql.clone());
+
+            ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                TextEdit::multiline(&mut sql)
+                    .font(egui::TextStyle::Monospace)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .background_color(self.config.settings.theme.text_input_color())
+                    .interactive(false)
+                    .show(ui);
+            });
+
+            if let Some(structs::CommandStatus::Error(e)) = &status {
+                ui.label(RichText::new(e).color(Color32::RED));
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if running {
+                        ui.add(Spinner::new());
+                    }
+
+                    let can_run = !running
+                        && !self.script_window.sql.is_empty()
+                        && self.script_window.database.is_some();
+
+                    if ui.add_enabled(can_run, Button::new(self.trans.apply())).clicked()
+                        && let Some(database) = self.script_window.database.clone()
+                    {
+                        let status = Arc::new(Mutex::new(structs::CommandStatus::Running));
+                        self.script_window.status = Some(status.clone());
+
+                        let sql = self.script_window.sql.clone();
+                        let disconnect = self.script_window.disconnect.clone();
+                        self.runtime.spawn(async move {
+                            Self::run_script(database, sql, disconnect, status).await;
+                        });
+                    }
+                    if ui
+                        .add_enabled(can_run, Button::new(self.trans.open_in_sql_editor()))
+                        .clicked()
+                        && let Some(database) = self.script_window.database.clone()
+                    {
+                        let window = std::mem::take(&mut self.script_window);
+
+                        self.open_sql_page(
+                            structs::SQLQueryPage::new(
+                                window.server_id,
+                                window.database_name,
+                                database,
+                                window.sql,
+                            ),
+                            false,
+                        );
+                        self.role_window = structs::RoleWindow::default();
+                    }
+                    if ui.add_enabled(!running, Button::new(self.trans.close())).clicked() {
+                        self.script_window = structs::ScriptWindow::default();
+                    }
+                });
+            });
+        });
+    }
+}
@@ -0,0 +1,165 @@
+use super::{Main, files};
+
+use crate::data::structs;
+use crate::utils;
+
+use log::{error, info};
+use std::fs as std_fs;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+const SESSION_SAVE_INTERVAL: Duration = Duration::from_secs(5);
+
+impl Main<'_> {
+    pub(super) fn collect_session(&self) -> structs::Session {
+        let mut session = structs::Session::default();
+
+        for (idx, page) in self.pages.pages.iter().enumerate() {
+            if let structs::PageType::SQLQuery(sqlquery_page) = &page.page_type {
+                if idx == self.pages.current_page_index as usize {
+                    session.current_page_index = session.pages.len() as u16;
+                }
+
+                session.pages.push(structs::SessionPage {
+                    server_id: sqlquery_page.server_id,
+                    database_name: sqlquery_page.database_name.clone(),
+                    code: sqlquery_page.code.clone(),
+                    code_file_path: sqlquery_page.code_file_path.clone(),
+                    cursor: sqlquery_page.cursor,
+                    read_only: sqlquery_page.read_only,
+                });
+            }
+        }
+
+        session
+    }
+
+    pub(super) fn autosave_session(&mut self) {
+        if !self.config.settings.restore_session || self.login_window.show {
+            return;
+        }
+        if self.session_saved_at.elapsed() < SESSION_SAVE_INTERVAL {
+            return;
+        }
+
+        self.session_saved_at = Instant::now();
+        self.save_session();
+    }
+
+    pub(super) fn save_session(&mut self) {
+        let session = self.collect_session();
+        if self.last_session.as_ref() == Some(&session) {
+            return;
+        }
+
+        let content = match serde_json::to_string_pretty(&session) {
+            Ok(content) => content,
+            Err(e) => {
+                error!("Error serializing session: {}", e);
+                return;
+            }
+        };
+
+        match utils::write_atomic(&self.config_store.session_path(), &content) {
+            Ok(()) => self.last_session = Some(session),
+            Err(e) => error!("Error saving session: {}", e),
+        }
+    }
+
+    pub(super) fn clear_session(&mut self) {
+        let session_path = self.config_store.session_path();
+        if session_path.exists()
+            && let Err(e) = std_fs::remove_file(&session_path)
+        {
+            error!("Error removing session file: {}", e);
+        }
+
+        self.last_session = None;
+    }
+
+    pub(super) fn restore_session(&mut self) {
+        let session_path = self.config_store.session_path();
+        if !session_path.exists() {
+            return;
+        }
+
+        let session = match std_fs::read_to_string(&session_path)
+            .map_err(|e| e.to_string())
+            .and_then(|content| {
+                serde_json::from_str::<structs::Session>(&content).map_err(|e| e.to_string())
+            }) {
+            Ok(session) => session,
+            Err(e) => {
+                error!("Error reading session: {}", e);
+                return;
+            }
+        };
+
+        self.apply_session(session);
+    }
+
+    /// Reopens the pages of `session` for servers that still exist.
+    pub(super) fn apply_session(&mut self, session: structs::Session) {
+        let mut pages = Vec::new();
+        let mut current_page_index = 0;
+
+        for (idx, session_page) in session.pages.iter().enumerate() {
+            let Some(server) = self
+                .config
+                .servers
+                .iter()
+                .find(|server| server.id == session_page.server_id)
+            else {
+                continue;
+            };
+
+            let database = server.connect(&session_page.database_name);
+
+            if idx == session.current_page_index as usize {
+                current_page_index = pages.len() as u16;
+            }
+
+            let (saved_code, file_modified) = match &session_page.code_file_path {
+                Some(code_file_path) => (
+                    std_fs::read_to_string(code_file_path).unwrap_or_default(),
+                    files::file_modified(code_file_path),
+                ),
+                None => (String::new(), None),
+            };
+
+            pages.push(structs::Page {
+                title: server.page_title(&session_page.database_name),
+                page_type: structs::PageType::SQLQuery(structs::SQLQueryPage {
+                    server_id: server.id,
+                    database_name: session_page.database_name.clone(),
+                    database,
+                    code: session_page.code.clone(),
+                    code_file_path: session_page.code_file_path.clone(),
+                    sql_query_execution_status: None,
+                    output_is_empty: true,
+                    update_page_index: None,
+                    cursor: session_page.cursor,
+                    restore_cursor: true,
+                    saved_code,
+                    file_modified,
+                    file_changed: false,
+                    notices: Arc::default(),
+                    show_messages: false,
+                    read_only: session_page.read_only,
+                }),
+                ..Default::default()
+            });
+        }
+
+        info!("Restored {} page(s) from previous session", pages.len());
+
+        if !pages.is_empty() {
+            self.pages = structs::Pages {
+                current_page_index,
+                pages,
+            };
+        }
+
+        self.last_session = Some(session);
+    }
+}
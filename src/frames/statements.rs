@@ -0,0 +1,656 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+use crate::database;
+
+use egui::{
+    Align, Button, Color32, Grid, Id, Label, Layout, Modal, RichText, ScrollArea, Spinner,
+    TextEdit, Ui,
+};
+use egui_extras::{Column, TableBuilder};
+use log::error;
+use std::cmp::Ordering;
+use std::sync::{Arc, Mutex};
+
+const QUERY_PREVIEW_LENGTH: usize = 160;
+
+/// If a string literal, quoted identifier, dollar-quoted string or comment
+/// starts at byte `start` of `query`, returns the byte index just past it.
+fn skip_quoted(query: &str, start: usize) -> Option<usize> {
+    let rest = &query[start..];
+
+    if rest.starts_with("--") {
+        return Some(rest.find('\n').map_or(query.len(), |end| start + end + 1));
+    }
+
+    if rest.starts_with("/*") {
+        // Block comments nest.
+        let mut depth = 0;
+        let mut i = start;
+        while i < query.len() {
+            if query[i..].starts_with("/*") {
+                depth += 1;
+                i += 2;
+            } else if query[i..].starts_with("*/") {
+                depth -= 1;
+                i += 2;
+                if depth == 0 {
+                    return Some(i);
+                }
+            } else {
+                i += query[i..].chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        return Some(query.len());
+    }
+
+    // `E'...'` strings escape quotes with backslashes too.
+    if rest.starts_with('\'')
+        && query[..start].ends_with(['E', 'e'])
+        && !query[..start - 1].ends_with(|c: char| c.is_alphanumeric() || c == '_')
+    {
+        let mut chars = rest.char_indices().skip(1);
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '\\' => {
+                    chars.next();
+                }
+                '\'' if rest[i + 1..].starts_with('\'') => {
+                    chars.next();
+                }
+                '\'' => return Some(start + i + 1),
+                _ => {}
+            }
+        }
+        return Some(query.len());
+    }
+
+    if let Some(quote) = rest.chars().next().filter(|c| *c == '\'' || *c == '"') {
+        // A doubled quote inside just reads as two adjacent literals.
+        return Some(rest[1..].find(quote).map_or(query.len(), |end| start + end + 2));
+    }
+
+    // `$tag$`, where the tag is empty or an identifier. A `$` inside an
+    // identifier or followed by a digit is not a quote.
+    let is_identifier_char = |c: char| c.is_alphanumeric() || c == '_';
+    if rest.starts_with('$')
+        && !query[..start].ends_with(is_identifier_char)
+        && !rest[1..].starts_with(|c: char| c.is_ascii_digit())
+    {
+        let tag_length = rest[1..].find(|c: char| !is_identifier_char(c))? + 2;
+        if rest[tag_length - 1..].starts_with('$') {
+            let tag = &rest[..tag_length];
+            return Some(
+                rest[tag_length..]
+                    .find(tag)
+                    .map_or(query.len(), |end| start + 2 * tag_length + end),
+            );
+        }
+    }
+
+    None
+}
+
+/// Calls `f` with the number of every `$n` placeholder outside literals,
+/// quoted identifiers, comments and identifiers such as `a$1`, and returns
+/// the text with each placeholder replaced by what `f` returned.
+fn map_parameters(query: &str, mut f: impl FnMut(usize) -> Option<String>) -> String {
+    let mut output = String::with_capacity(query.len());
+    let mut i = 0;
+
+    while let Some(c) = query[i..].chars().next() {
+        if let Some(end) = skip_quoted(query, i) {
+            output.push_str(&query[i..end]);
+            i = end;
+            continue;
+        }
+
+        if c == '$' && !query[..i].ends_with(|c: char| c.is_alphanumeric() || c == '_') {
+            let number: String = query[i + 1..].chars().take_while(|c| c.is_ascii_digit()).collect();
+            if !number.is_empty() {
+                match number.parse().ok().and_then(&mut f) {
+                    Some(value) => output.push_str(&value),
+                    None => output.push_str(&query[i..=i + number.len()]),
+                }
+                i += number.len() + 1;
+                continue;
+            }
+        }
+
+        output.push(c);
+        i += c.len_utf8();
+    }
+
+    output
+}
+
+/// Whether `query` looks like it doesn't change data. Any key word that may
+/// write, outside literals and comments, counts as a write. Functions such
+/// as `nextval` slip through, so this only decides what the UI warns about.
+fn is_read_only(query: &str) -> bool {
+    const WRITE_KEYWORDS: &[&str] = &[
+        "INSERT", "UPDATE", "DELETE", "MERGE", "INTO", "TRUNCATE", "COPY", "CALL", "DO",
+    ];
+
+    let mut code = String::with_capacity(query.len());
+    let mut i = 0;
+    while let Some(c) = query[i..].chars().next() {
+        match skip_quoted(query, i) {
+            Some(end) => {
+                code.push(' ');
+                i = end;
+            }
+            None => {
+                code.push(c);
+                i += c.len_utf8();
+            }
+        }
+    }
+
+    let words: Vec<String> = code
+        .split(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .filter(|word| !word.is_empty())
+        .map(str::to_uppercase)
+        .collect();
+
+    words
+        .first()
+        .is_some_and(|word| ["SELECT", "WITH", "VALUES", "TABLE"].contains(&word.as_str()))
+        && !words.iter().any(|word| WRITE_KEYWORDS.contains(&word.as_str()))
+}
+
+fn parameter_count(query: &str) -> usize {
+    let mut count = 0;
+    map_parameters(query, |n| {
+        count = count.max(n);
+        None
+    });
+
+    count
+}
+
+fn bind_parameters(query: &str, parameters: &[String]) -> String {
+    map_parameters(query, |n| parameters.get(n.checked_sub(1)?).cloned())
+}
+
+fn query_preview(query: &str) -> String {
+    let query = query.split_whitespace().collect::<Vec<&str>>().join(" ");
+
+    if query.chars().count() > QUERY_PREVIEW_LENGTH {
+        format!("{}...", query.chars().take(QUERY_PREVIEW_LENGTH).collect::<String>())
+    } else {
+        query
+    }
+}
+
+fn compare_statements(
+    a: &structs::StatementStats,
+    b: &structs::StatementStats,
+    column: structs::StatementsColumn,
+) -> Ordering {
+    let float = |a: f64, b: f64| a.partial_cmp(&b).unwrap_or(Ordering::Equal);
+
+    match column {
+        structs::StatementsColumn::Calls => a.calls.cmp(&b.calls),
+        structs::StatementsColumn::TotalTime => float(a.total_time, b.total_time),
+        structs::StatementsColumn::MeanTime => float(a.mean_time, b.mean_time),
+        structs::StatementsColumn::StddevTime => float(a.stddev_time, b.stddev_time),
+        structs::StatementsColumn::Rows => a.rows.cmp(&b.rows),
+        structs::StatementsColumn::SharedBlksHit => a.shared_blks_hit.cmp(&b.shared_blks_hit),
+        structs::StatementsColumn::SharedBlksRead => a.shared_blks_read.cmp(&b.shared_blks_read),
+        structs::StatementsColumn::TempBlks => a.temp_blks.cmp(&b.temp_blks),
+        structs::StatementsColumn::Query => a.query.to_lowercase().cmp(&b.query.to_lowercase()),
+    }
+}
+
+impl Main<'_> {
+    pub(super) fn open_statements_page(&mut self, server_id: uuid::Uuid, database_name: &str) {
+        if let Some(idx) = self.pages.pages.iter().position(|page| {
+            matches!(&page.page_type, structs::PageType::Statements(statements_page)
+                if statements_page.server_id == server_id && statements_page.database_name == database_name)
+        }) {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id) else {
+            return;
+        };
+        let Some(database) = self.loaded_database(server_id, database_name) else {
+            return;
+        };
+
+        self.pages.pages.push(structs::Page {
+            title: format!("{}: {}", self.trans.query_statistics(), server.page_title(database_name)),
+            page_type: structs::PageType::Statements(structs::StatementsPage {
+                server_id,
+                database_name: database_name.to_string(),
+                database: database.database,
+                snapshot: Arc::new(Mutex::new(structs::StatementsSnapshot::default())),
+                filter: String::new(),
+                all_databases: false,
+                sort_column: structs::StatementsColumn::default(),
+                sort_descending: true,
+                confirm_reset: false,
+            }),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    /// Runs `command` first if given, then reloads the statistics.
+    async fn fetch_statements(
+        database: database::Database,
+        command: Option<&str>,
+        all_databases: bool,
+        snapshot: Arc<Mutex<structs::StatementsSnapshot>>,
+    ) {
+        let result = async {
+            if let Some(command) = command {
+                database.execute(command).await?;
+            }
+
+            if !database.has_extension("pg_stat_statements").await? {
+                return Ok(None);
+            }
+
+            database.get_statements(all_databases).await.map(Some)
+        }
+        .await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok(Some(statements)) => {
+                snapshot.extension_installed = true;
+                snapshot.statements = statements;
+                snapshot.error = None;
+            }
+            Ok(None) => {
+                snapshot.extension_installed = false;
+                snapshot.statements = Vec::new();
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error loading statement statistics: {}", e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+        snapshot.loaded = true;
+    }
+
+    fn refresh_statements(
+        runtime: &tokio::runtime::Runtime,
+        statements_page: &structs::StatementsPage,
+        command: Option<&'static str>,
+    ) {
+        statements_page.snapshot.lock().unwrap().loading = true;
+
+        let database = statements_page.database.clone();
+        let all_databases = statements_page.all_databases;
+        let snapshot = statements_page.snapshot.clone();
+
+        runtime.spawn(async move {
+            Self::fetch_statements(database, command, all_databases, snapshot).await;
+        });
+    }
+
+    pub(super) fn update_statements_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::Statements(statements_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = statements_page.snapshot.lock().unwrap().clone();
+        let mut refresh = !snapshot.loaded && !snapshot.loading;
+        let mut command = None;
+
+        ui.horizontal(|ui| {
+            if ui.add_enabled(!snapshot.loading, Button::new(self.trans.refresh())).clicked() {
+                refresh = true;
+            }
+            if snapshot.loading {
+                ui.add(Spinner::new());
+            }
+
+            ui.separator();
+
+            if ui.checkbox(&mut statements_page.all_databases, self.trans.all_databases()).changed() {
+                refresh = true;
+            }
+
+            ui.separator();
+
+            if statements_page.confirm_reset {
+                ui.label(self.trans.reset_statistics_confirmation());
+                if ui.button(self.trans.yes()).clicked() {
+                    statements_page.confirm_reset = false;
+                    refresh = true;
+                    command = Some("SELECT pg_stat_statements_reset()");
+                }
+                if ui.button(self.trans.no()).clicked() {
+                    statements_page.confirm_reset = false;
+                }
+            } else if ui
+                .add_enabled(snapshot.extension_installed, Button::new(self.trans.reset_statistics()))
+                .clicked()
+            {
+                statements_page.confirm_reset = true;
+            }
+        });
+
+        ui.add(
+            TextEdit::singleline(&mut statements_page.filter)
+                .hint_text(self.trans.search())
+                .background_color(self.config.settings.theme.text_input_color()),
+        );
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        ui.separator();
+
+        if snapshot.loaded && !snapshot.extension_installed {
+            ui.label(self.trans.pg_stat_statements_missing());
+            if ui.add_enabled(!snapshot.loading, Button::new(self.trans.create_extension())).clicked() {
+                refresh = true;
+                command = Some("CREATE EXTENSION IF NOT EXISTS pg_stat_statements");
+            }
+        }
+
+        if refresh && !snapshot.loading {
+            Self::refresh_statements(&self.runtime, statements_page, command);
+        }
+
+        let filter = statements_page.filter.trim().to_lowercase();
+        let mut statements: Vec<&structs::StatementStats> = snapshot
+            .statements
+            .iter()
+            .filter(|statement| {
+                filter.is_empty()
+                    || statement.query.to_lowercase().contains(&filter)
+                    || statement.user.to_lowercase().contains(&filter)
+                    || statement.database.to_lowercase().contains(&filter)
+            })
+            .collect();
+
+        statements.sort_by(|a, b| {
+            let ordering = compare_statements(a, b, statements_page.sort_column);
+            if statements_page.sort_descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        });
+
+        let columns = [
+            (structs::StatementsColumn::Calls, self.trans.calls()),
+            (structs::StatementsColumn::TotalTime, self.trans.total_time()),
+            (structs::StatementsColumn::MeanTime, self.trans.mean_time()),
+            (structs::StatementsColumn::StddevTime, self.trans.stddev_time()),
+            (structs::StatementsColumn::Rows, self.trans.rows_column()),
+            (structs::StatementsColumn::SharedBlksHit, self.trans.shared_hit()),
+            (structs::StatementsColumn::SharedBlksRead, self.trans.shared_read()),
+            (structs::StatementsColumn::TempBlks, self.trans.temp_blocks()),
+        ];
+
+        let server_id = statements_page.server_id;
+        let mut open: Option<(&structs::StatementStats, bool)> = None;
+
+        let available_height = ui.available_height();
+        let available_width = ui.available_width();
+
+        ScrollArea::horizontal()
+            .auto_shrink([false, false])
+            .max_width(available_width)
+            .max_height(available_height)
+            .show(ui, |ui| {
+                TableBuilder::new(ui)
+                    .id_salt(("statements_table", page_index))
+                    .striped(true)
+                    .auto_shrink([false, false])
+                    .column(Column::auto())
+                    .columns(Column::auto().resizable(true), columns.len() + usize::from(statements_page.all_databases))
+                    .column(Column::remainder().resizable(true))
+                    .header(16.0, |mut header| {
+                        header.col(|_| {});
+
+                        let mut sort_header = |ui: &mut Ui, column: structs::StatementsColumn, title: String| {
+                            let title = if statements_page.sort_column == column {
+                                format!("{} {}", title, if statements_page.sort_descending { "⏷" } else { "⏶" })
+                            } else {
+                                title
+                            };
+
+                            if ui.add(Button::new(RichText::new(title).strong()).frame(false)).clicked() {
+                                if statements_page.sort_column == column {
+                                    statements_page.sort_descending = !statements_page.sort_descending;
+                                } else {
+                                    statements_page.sort_column = column;
+                                    statements_page.sort_descending = column != structs::StatementsColumn::Query;
+                                }
+                            }
+                        };
+
+                        if statements_page.all_databases {
+                            header.col(|ui| {
+                                ui.label(RichText::new(self.trans.database()).strong());
+                            });
+                        }
+                        for (column, title) in columns.iter() {
+                            header.col(|ui| sort_header(ui, *column, title.clone()));
+                        }
+                        header.col(|ui| sort_header(ui, structs::StatementsColumn::Query, self.trans.query()));
+                    })
+                    .body(|mut body| {
+                        for statement in statements.iter() {
+                            body.row(18.0, |mut row| {
+                                row.col(|ui| {
+                                    if ui.small_button(self.trans.open()).clicked() {
+                                        open = Some((statement, false));
+                                    }
+                                    if ui.small_button(self.trans.explain()).clicked() {
+                                        open = Some((statement, true));
+                                    }
+                                });
+                                if statements_page.all_databases {
+                                    row.col(|ui| {
+                                        ui.label(&statement.database);
+                                    });
+                                }
+                                for value in [
+                                    statement.calls.to_string(),
+                                    format!("{:.2}", statement.total_time),
+                                    format!("{:.2}", statement.mean_time),
+                                    format!("{:.2}", statement.stddev_time),
+                                    statement.rows.to_string(),
+                                    statement.shared_blks_hit.to_string(),
+                                    statement.shared_blks_read.to_string(),
+                                    statement.temp_blks.to_string(),
+                                ] {
+                                    row.col(|ui| {
+                                        ui.label(value);
+                                    });
+                                }
+                                row.col(|ui| {
+                                    let label = ui.add(
+                                        Label::new(RichText::new(query_preview(&statement.query)).monospace())
+                                            .wrap_mode(egui::TextWrapMode::Truncate)
+                                            .sense(egui::Sense::click()),
+                                    );
+                                    if label.clicked() {
+                                        self.sql_response_copy_window.show = true;
+                                        self.sql_response_copy_window.response = Some(statement.query.clone());
+                                    } else if label.hovered() {
+                                        egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), Id::new("statements_query_tooltip"), |ui| {
+                                            ui.label(self.trans.click_to_copy());
+                                        });
+                                    }
+                                });
+                            });
+                        }
+                    });
+            });
+
+        let Some((statement, explain)) = open else {
+            return;
+        };
+
+        // Statements of other databases run there, if it is loaded.
+        let database_name = if statement.database.is_empty() {
+            statements_page.database_name.clone()
+        } else {
+            statement.database.clone()
+        };
+        let database = self
+            .loaded_database(server_id, &database_name)
+            .map(|database| (database_name, database.database))
+            .or_else(|| {
+                let Some(structs::Page {
+                    page_type: structs::PageType::Statements(statements_page),
+                    ..
+                }) = self.pages.pages.get(page_index)
+                else {
+                    return None;
+                };
+                Some((statements_page.database_name.clone(), statements_page.database.clone()))
+            });
+        let Some((database_name, database)) = database else {
+            return;
+        };
+
+        if explain {
+            self.explain_window = structs::ExplainWindow {
+                show: true,
+                server_id,
+                database_name,
+                database: Some(database),
+                parameters: vec![String::new(); parameter_count(&statement.query)],
+                read_only: is_read_only(&statement.query),
+                query: statement.query.clone(),
+                analyze: false,
+            };
+        } else {
+            self.open_sql_page(
+                structs::SQLQueryPage::new(server_id, database_name, database, &statement.query),
+                false,
+            );
+        }
+    }
+
+    pub(super) fn update_statements_windows(&mut self, ctx: &egui::Context) {
+        if !self.explain_window.show {
+            return;
+        }
+
+        Modal::new(Id::new("explain_modal")).show(ctx, |ui| {
+            ui.set_max_width(600.0);
+
+            widgets::modal_label(ui, self.trans.explain());
+
+            ScrollArea::vertical().max_height(160.0).show(ui, |ui| {
+                ui.label(RichText::new(&self.explain_window.query).monospace());
+            });
+
+            ui.separator();
+
+            if !self.explain_window.parameters.is_empty() {
+                ui.label(self.trans.parameters_description());
+
+                Grid::new("explain_parameters")
+                    .num_columns(2)
+                    .spacing([40.0, 4.0])
+                    .show(ui, |ui| {
+                        for (idx, parameter) in self.explain_window.parameters.iter_mut().enumerate() {
+                            ui.label(format!("${}", idx + 1));
+                            TextEdit::singleline(parameter)
+                                .background_color(self.config.settings.theme.text_input_color())
+                                .hint_text("NULL")
+                                .show(ui);
+                            ui.end_row();
+                        }
+                    });
+            }
+
+            ui.checkbox(&mut self.explain_window.analyze, self.trans.explain_analyze())
+                .on_hover_text(self.trans.read_only_rolled_back());
+            if self.explain_window.analyze && !self.explain_window.read_only {
+                ui.label(RichText::new(self.trans.explain_analyze_writes()).color(Color32::YELLOW));
+            }
+
+            ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                ui.separator();
+
+                ui.horizontal(|ui| {
+                    if ui.button(self.trans.explain()).clicked() {
+                        let window = std::mem::take(&mut self.explain_window);
+
+                        let parameters: Vec<String> = window
+                            .parameters
+                            .iter()
+                            .map(|value| if value.trim().is_empty() { String::from("NULL") } else { value.clone() })
+                            .collect();
+                        let code = format!(
+                            "EXPLAIN {}{}",
+                            if window.analyze { "(ANALYZE, BUFFERS) " } else { "" },
+                            bind_parameters(&window.query, &parameters)
+                        );
+
+                        if let Some(database) = window.database {
+                            // ANALYZE runs the statement, parameters included, so
+                            // nothing it does may be kept.
+                            self.open_sql_page(
+                                structs::SQLQueryPage {
+                                    read_only: window.analyze,
+                                    ..structs::SQLQueryPage::new(window.server_id, window.database_name, database, code)
+                                },
+                                true,
+                            );
+                        }
+                    }
+                    if ui.button(self.trans.close()).clicked() {
+                        self.explain_window = structs::ExplainWindow::default();
+                    }
+                });
+            });
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn binds_parameters_outside_quotes_and_comments() {
+        let query = "SELECT $1, '$2', \"$3\", $$ $4 $$, $fn$ $5 $fn$, a$6 -- $7\n/* $8 /* $9 */ */ $10";
+        let parameters: Vec<String> = (1..=10).map(|n| format!("p{}", n)).collect();
+
+        assert_eq!(
+            bind_parameters(query, &parameters),
+            "SELECT p1, '$2', \"$3\", $$ $4 $$, $fn$ $5 $fn$, a$6 -- $7\n/* $8 /* $9 */ */ p10"
+        );
+        assert_eq!(parameter_count(query), 10);
+        assert_eq!(parameter_count("SELECT $$ $3 $$ -- $2"), 0);
+        assert_eq!(bind_parameters("SELECT $2", &[]), "SELECT $2");
+        assert_eq!(
+            bind_parameters("SELECT E'\\' $1', e'it''s $1', type'\\', $1", &parameters),
+            "SELECT E'\\' $1', e'it''s $1', type'\\', p1"
+        );
+    }
+
+    #[test]
+    fn detects_writing_statements() {
+        assert!(is_read_only("SELECT * FROM t WHERE a = $1"));
+        assert!(is_read_only("/* DELETE */ WITH x AS (SELECT 'update') SELECT * FROM x"));
+        assert!(is_read_only("select 1 -- insert"));
+        assert!(!is_read_only("UPDATE t SET a = $1"));
+        assert!(!is_read_only("WITH x AS (DELETE FROM t RETURNING *) SELECT * FROM x"));
+        assert!(!is_read_only("SELECT * INTO copy FROM t"));
+        assert!(!is_read_only("INSERT INTO t VALUES ($1)"));
+    }
+}
@@ -0,0 +1,1000 @@
+use super::Main;
+
+use crate::data::*;
+use crate::database;
+use crate::utils::quote_ident;
+
+use egui::{Button, CollapsingHeader, Color32, ComboBox, Grid, RichText, ScrollArea, Spinner, TextEdit, Ui};
+use log::error;
+use std::sync::{Arc, Mutex};
+
+const DATA_TYPES: [&str; 22] = [
+    "integer",
+    "bigint",
+    "smallint",
+    "serial",
+    "bigserial",
+    "numeric",
+    "real",
+    "double precision",
+    "boolean",
+    "text",
+    "character varying(255)",
+    "character(1)",
+    "uuid",
+    "date",
+    "time without time zone",
+    "timestamp without time zone",
+    "timestamp with time zone",
+    "interval",
+    "json",
+    "jsonb",
+    "bytea",
+    "inet",
+];
+
+const REFERENTIAL_ACTIONS: [&str; 5] = ["NO ACTION", "RESTRICT", "CASCADE", "SET NULL", "SET DEFAULT"];
+
+pub(super) const INDEX_METHODS: [&str; 6] = ["btree", "hash", "gist", "spgist", "gin", "brin"];
+
+fn qualified_name(schema: &str, name: &str) -> String {
+    format!("{}.{}", quote_ident(schema), quote_ident(name))
+}
+
+/// Quotes a table typed as `table` or `schema.table`. Parts already in
+/// double quotes, as `regclass` prints them, are taken literally.
+fn table_reference(name: &str) -> String {
+    let mut parts = Vec::new();
+    let mut part = String::new();
+    let mut quoted = false;
+    let mut chars = name.trim().chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                part.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            '.' if !quoted => parts.push(std::mem::take(&mut part)),
+            c => part.push(c),
+        }
+    }
+    parts.push(part);
+
+    parts.iter().map(|part| quote_ident(part.trim())).collect::<Vec<String>>().join(".")
+}
+
+/// Quotes each name of a comma-separated list.
+fn identifier_list(names: &str) -> String {
+    names
+        .split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty())
+        .map(quote_ident)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Replaces renamed columns in a comma-separated list, so constraints and
+/// indexes created after the renames can still refer to the old names.
+fn rename_columns(list: &str, renames: &[(String, String)], quoted: bool) -> String {
+    list.split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(|item| {
+            renames
+                .iter()
+                .find(|(old, _)| if quoted { quote_ident(old) == item } else { old == item })
+                .map(|(_, new)| if quoted { quote_ident(new) } else { new.clone() })
+                .unwrap_or_else(|| item.to_string())
+        })
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+fn column_definition(column: &structs::TableColumn) -> String {
+    let mut definition = format!("{} {}", quote_ident(column.name.trim()), column.data_type.trim());
+
+    if column.not_null {
+        definition.push_str(" NOT NULL");
+    }
+    if !column.default.trim().is_empty() {
+        definition.push_str(&format!(" DEFAULT {}", column.default.trim()));
+    }
+
+    definition
+}
+
+/// The constraint without its name, used both to create it and to tell
+/// whether it changed.
+fn constraint_body(constraint: &structs::TableConstraint) -> String {
+    match constraint.kind {
+        structs::TableConstraintKind::PrimaryKey | structs::TableConstraintKind::Unique => {
+            format!("{} ({})", constraint.kind.keyword(), identifier_list(&constraint.columns))
+        }
+        structs::TableConstraintKind::Check => format!("CHECK ({})", constraint.expression.trim()),
+        structs::TableConstraintKind::ForeignKey => {
+            let mut body = format!(
+                "FOREIGN KEY ({}) REFERENCES {}",
+                identifier_list(&constraint.columns),
+                table_reference(&constraint.ref_table)
+            );
+            if !constraint.ref_columns.trim().is_empty() {
+                body.push_str(&format!(" ({})", identifier_list(&constraint.ref_columns)));
+            }
+            for (action, clause) in [(&constraint.on_update, "ON UPDATE"), (&constraint.on_delete, "ON DELETE")] {
+                if !action.is_empty() && action != "NO ACTION" {
+                    body.push_str(&format!(" {} {}", clause, action));
+                }
+            }
+            body
+        }
+    }
+}
+
+fn constraint_definition(constraint: &structs::TableConstraint) -> String {
+    if constraint.name.trim().is_empty() {
+        constraint_body(constraint)
+    } else {
+        format!("CONSTRAINT {} {}", quote_ident(constraint.name.trim()), constraint_body(constraint))
+    }
+}
+
+/// The index without its name, used to tell whether it changed.
+fn index_body(index: &structs::TableIndex) -> String {
+    let mut body = format!(
+        "{}USING {} ({})",
+        if index.unique { "UNIQUE " } else { "" },
+        if index.method.is_empty() { "btree" } else { &index.method },
+        index.columns.trim()
+    );
+    if !index.include.trim().is_empty() {
+        body.push_str(&format!(" INCLUDE ({})", index.include.trim()));
+    }
+    if !index.predicate.trim().is_empty() {
+        body.push_str(&format!(" WHERE {}", index.predicate.trim()));
+    }
+
+    body
+}
+
+fn create_index_statement(table: &str, schema: &str, index: &structs::TableIndex) -> String {
+    let mut statement = String::from("CREATE ");
+    if index.unique {
+        statement.push_str("UNIQUE ");
+    }
+    statement.push_str("INDEX ");
+    if !index.name.trim().is_empty() {
+        statement.push_str(&format!("{} ", quote_ident(index.name.trim())));
+    }
+    statement.push_str(&format!(
+        "ON {} USING {} ({})",
+        qualified_name(schema, table),
+        if index.method.is_empty() { "btree" } else { &index.method },
+        index.columns.trim()
+    ));
+    if !index.include.trim().is_empty() {
+        statement.push_str(&format!(" INCLUDE ({})", index.include.trim()));
+    }
+    if !index.predicate.trim().is_empty() {
+        statement.push_str(&format!(" WHERE {}", index.predicate.trim()));
+    }
+    statement.push(';');
+
+    statement
+}
+
+fn create_table_script(table: &structs::TableDefinition) -> String {
+    let definitions: Vec<String> = table
+        .columns
+        .iter()
+        .map(column_definition)
+        .chain(table.constraints.iter().map(constraint_definition))
+        .map(|definition| format!("    {}", definition))
+        .collect();
+
+    let mut statements = vec![format!(
+        "CREATE TABLE {} (\n{}\n);",
+        qualified_name(&table.schema, &table.name),
+        definitions.join(",\n")
+    )];
+    for index in table.indexes.iter() {
+        statements.push(create_index_statement(&table.name, &table.schema, index));
+    }
+
+    statements.join("\n")
+}
+
+/// Builds the statements that turn `original` into `table`, leaving
+/// everything that did not change alone.
+fn alter_table_script(original: &structs::TableDefinition, table: &structs::TableDefinition) -> String {
+    let mut statements = Vec::new();
+    let name = qualified_name(&original.schema, &original.name);
+    let alter = |action: String| format!("ALTER TABLE {} {};", name, action);
+
+    let find_constraint = |name: &str| {
+        table
+            .constraints
+            .iter()
+            .find(|constraint| constraint.original_name.as_deref() == Some(name))
+    };
+    for original_constraint in original.constraints.iter() {
+        let keep = find_constraint(&original_constraint.name)
+            .is_some_and(|constraint| constraint_body(constraint) == constraint_body(original_constraint));
+        if !keep {
+            statements.push(alter(format!("DROP CONSTRAINT {}", quote_ident(&original_constraint.name))));
+        }
+    }
+
+    let find_index = |name: &str| table.indexes.iter().find(|index| index.original_name.as_deref() == Some(name));
+    for original_index in original.indexes.iter() {
+        let keep = find_index(&original_index.name).is_some_and(|index| index_body(index) == index_body(original_index));
+        if !keep {
+            statements.push(format!(
+                "DROP INDEX {};",
+                qualified_name(&original.schema, &original_index.name)
+            ));
+        }
+    }
+
+    for original_column in original.columns.iter() {
+        if !table
+            .columns
+            .iter()
+            .any(|column| column.original_name.as_deref() == Some(original_column.name.as_str()))
+        {
+            statements.push(alter(format!("DROP COLUMN {}", quote_ident(&original_column.name))));
+        }
+    }
+
+    let renames: Vec<(String, String)> = table
+        .columns
+        .iter()
+        .filter_map(|column| Some((column.original_name.clone()?, column.name.trim().to_string())))
+        .filter(|(old, new)| old != new)
+        .collect();
+
+    for column in table.columns.iter() {
+        let Some(original_column) = column
+            .original_name
+            .as_ref()
+            .and_then(|name| original.columns.iter().find(|original_column| original_column.name == *name))
+        else {
+            statements.push(alter(format!("ADD COLUMN {}", column_definition(column))));
+            continue;
+        };
+
+        let column_name = quote_ident(column.name.trim());
+        if column.name.trim() != original_column.name {
+            statements.push(alter(format!(
+                "RENAME COLUMN {} TO {}",
+                quote_ident(&original_column.name),
+                column_name
+            )));
+        }
+        if column.data_type.trim() != original_column.data_type {
+            statements.push(alter(format!(
+                "ALTER COLUMN {0} TYPE {1} USING {0}::{1}",
+                column_name,
+                column.data_type.trim()
+            )));
+        }
+        if column.not_null != original_column.not_null {
+            statements.push(alter(format!(
+                "ALTER COLUMN {} {} NOT NULL",
+                column_name,
+                if column.not_null { "SET" } else { "DROP" }
+            )));
+        }
+        if column.default.trim() != original_column.default {
+            statements.push(alter(if column.default.trim().is_empty() {
+                format!("ALTER COLUMN {} DROP DEFAULT", column_name)
+            } else {
+                format!("ALTER COLUMN {} SET DEFAULT {}", column_name, column.default.trim())
+            }));
+        }
+    }
+
+    for constraint in table.constraints.iter() {
+        let original_constraint = constraint
+            .original_name
+            .as_ref()
+            .and_then(|name| original.constraints.iter().find(|original_constraint| original_constraint.name == *name));
+
+        match original_constraint {
+            Some(original_constraint) if constraint_body(constraint) == constraint_body(original_constraint) => {
+                if constraint.name.trim() != original_constraint.name {
+                    statements.push(alter(format!(
+                        "RENAME CONSTRAINT {} TO {}",
+                        quote_ident(&original_constraint.name),
+                        quote_ident(constraint.name.trim())
+                    )));
+                }
+            }
+            _ => {
+                let constraint = structs::TableConstraint {
+                    columns: rename_columns(&constraint.columns, &renames, false),
+                    ..constraint.clone()
+                };
+                statements.push(alter(format!("ADD {}", constraint_definition(&constraint))));
+            }
+        }
+    }
+
+    for index in table.indexes.iter() {
+        let original_index = index
+            .original_name
+            .as_ref()
+            .and_then(|name| original.indexes.iter().find(|original_index| original_index.name == *name));
+
+        match original_index {
+            Some(original_index) if index_body(index) == index_body(original_index) => {
+                if index.name.trim() != original_index.name {
+                    statements.push(format!(
+                        "ALTER INDEX {} RENAME TO {};",
+                        qualified_name(&original.schema, &original_index.name),
+                        quote_ident(index.name.trim())
+                    ));
+                }
+            }
+            _ => {
+                let index = structs::TableIndex {
+                    columns: rename_columns(&index.columns, &renames, true),
+                    include: rename_columns(&index.include, &renames, true),
+                    ..index.clone()
+                };
+                statements.push(create_index_statement(&original.name, &original.schema, &index));
+            }
+        }
+    }
+
+    if table.name.trim() != original.name {
+        statements.push(alter(format!("RENAME TO {}", quote_ident(table.name.trim()))));
+    }
+
+    statements.join("\n")
+}
+
+fn table_script(table_designer_page: &structs::TableDesignerPage) -> String {
+    match &table_designer_page.original {
+        Some(original) => alter_table_script(original, &table_designer_page.table),
+        None if table_designer_page.table.name.trim().is_empty() || table_designer_page.table.columns.is_empty() => {
+            String::new()
+        }
+        None => create_table_script(&table_designer_page.table),
+    }
+}
+
+/// Up, down and remove buttons for row `idx`. Rows only swap with each
+/// other when both are `movable`.
+fn row_buttons(
+    ui: &mut Ui,
+    idx: usize,
+    movable: &[bool],
+    moved: &mut Option<(usize, usize)>,
+    removed: &mut Option<usize>,
+) {
+    let can_swap = |other: usize| movable[idx] && movable.get(other).copied().unwrap_or(false);
+
+    ui.horizontal(|ui| {
+        if ui.add_enabled(idx > 0 && can_swap(idx - 1), Button::new("⏶").small()).clicked() {
+            *moved = Some((idx, idx - 1));
+        }
+        if ui.add_enabled(can_swap(idx + 1), Button::new("⏷").small()).clicked() {
+            *moved = Some((idx, idx + 1));
+        }
+        if ui.small_button("✖").clicked() {
+            *removed = Some(idx);
+        }
+    });
+}
+
+fn apply_row_changes<T>(rows: &mut Vec<T>, moved: Option<(usize, usize)>, removed: Option<usize>) {
+    if let Some((from, to)) = moved {
+        rows.swap(from, to);
+    }
+    if let Some(idx) = removed {
+        rows.remove(idx);
+    }
+}
+
+/// A menu that appends one of `columns` to a comma-separated list.
+pub(super) fn column_picker(ui: &mut Ui, list: &mut String, columns: &[String], quote: bool) {
+    ui.menu_button("+", |ui| {
+        for column in columns {
+            if ui.button(column).clicked() {
+                let column = if quote { quote_ident(column) } else { column.clone() };
+                if list.trim().is_empty() {
+                    *list = column;
+                } else {
+                    list.push_str(&format!(", {}", column));
+                }
+                ui.close_menu();
+            }
+        }
+    });
+}
+
+impl Main<'_> {
+    pub(super) fn open_table_designer(&mut self, server_id: uuid::Uuid, database_name: &str, table: Option<String>) {
+        if let Some(table) = &table
+            && let Some(idx) = self.pages.pages.iter().position(|page| {
+                matches!(&page.page_type, structs::PageType::TableDesigner(table_designer_page)
+                    if table_designer_page.server_id == server_id
+                        && table_designer_page.database_name == database_name
+                        && table_designer_page.original.as_ref().is_some_and(|original| original.name == *table))
+            })
+        {
+            self.pages.current_page_index = idx as u16;
+            return;
+        }
+
+        let Some(server) = self.config.servers.iter().find(|server| server.id == server_id) else {
+            return;
+        };
+        let Some(database) = self.loaded_database(server_id, database_name) else {
+            return;
+        };
+
+        let table_designer_page = structs::TableDesignerPage {
+            server_id,
+            database_name: database_name.to_string(),
+            database: database.database,
+            original: None,
+            table: structs::TableDefinition {
+                schema: String::from("public"),
+                name: table.clone().unwrap_or_default(),
+                ..Default::default()
+            },
+            snapshot: Arc::new(Mutex::new(structs::TableDesignerSnapshot::default())),
+        };
+
+        if let Some(table) = &table {
+            Self::refresh_table_definition(&self.runtime, &table_designer_page, None, table);
+        }
+
+        self.pages.pages.push(structs::Page {
+            title: format!(
+                "{}: {}",
+                table.unwrap_or_else(|| self.trans.new_table()),
+                server.page_title(database_name)
+            ),
+            page_type: structs::PageType::TableDesigner(table_designer_page),
+            ..Default::default()
+        });
+        self.pages.current_page_index = (self.pages.pages.len() - 1) as u16;
+    }
+
+    async fn fetch_table_definition(
+        database: database::Database,
+        schema: Option<String>,
+        table: String,
+        snapshot: Arc<Mutex<structs::TableDesignerSnapshot>>,
+    ) {
+        let result = database.get_table_definition(schema.as_deref(), &table).await;
+
+        let mut snapshot = snapshot.lock().unwrap();
+        match result {
+            Ok(definition) => {
+                snapshot.definition = Some(definition);
+                snapshot.error = None;
+            }
+            Err(e) => {
+                error!("Error loading table {}: {}", table, e);
+                snapshot.error = Some(e);
+            }
+        }
+        snapshot.loading = false;
+    }
+
+    pub(super) fn refresh_table_definition(
+        runtime: &tokio::runtime::Runtime,
+        table_designer_page: &structs::TableDesignerPage,
+        schema: Option<&str>,
+        table: &str,
+    ) {
+        table_designer_page.snapshot.lock().unwrap().loading = true;
+
+        let database = table_designer_page.database.clone();
+        let schema = schema.map(str::to_string);
+        let table = table.to_string();
+        let snapshot = table_designer_page.snapshot.clone();
+
+        runtime.spawn(async move {
+            Self::fetch_table_definition(database, schema, table, snapshot).await;
+        });
+    }
+
+    pub(super) fn update_table_designer_page(&mut self, ui: &mut Ui, page_index: usize) {
+        let Some(structs::Page {
+            page_type: structs::PageType::TableDesigner(table_designer_page),
+            ..
+        }) = self.pages.pages.get_mut(page_index)
+        else {
+            return;
+        };
+
+        let snapshot = {
+            let mut snapshot = table_designer_page.snapshot.lock().unwrap();
+            if let Some(definition) = snapshot.definition.take() {
+                table_designer_page.original = Some(definition.clone());
+                table_designer_page.table = definition;
+            }
+            snapshot.clone()
+        };
+
+        if snapshot.loading {
+            ui.add(Spinner::new());
+            return;
+        }
+
+        let input_color = self.config.settings.theme.text_input_color();
+        let is_new = table_designer_page.original.is_none();
+        let mut review = false;
+
+        ui.horizontal(|ui| {
+            ui.label(self.trans.schema());
+            ui.add_enabled(
+                is_new,
+                TextEdit::singleline(&mut table_designer_page.table.schema)
+                    .background_color(input_color)
+                    .desired_width(120.0),
+            );
+            ui.label(self.trans.name());
+            ui.add(
+                TextEdit::singleline(&mut table_designer_page.table.name)
+                    .background_color(input_color)
+                    .desired_width(200.0),
+            );
+
+            ui.separator();
+
+            if let Some(original) = &table_designer_page.original
+                && ui.button(self.trans.refresh()).clicked()
+            {
+                Self::refresh_table_definition(
+                    &self.runtime,
+                    table_designer_page,
+                    Some(&original.schema),
+                    &original.name,
+                );
+            }
+        });
+
+        if let Some(error) = &snapshot.error {
+            ui.label(RichText::new(error).color(Color32::RED));
+        }
+
+        ui.separator();
+
+        let column_names: Vec<String> = table_designer_page
+            .table
+            .columns
+            .iter()
+            .map(|column| column.name.clone())
+            .filter(|name| !name.is_empty())
+            .collect();
+        let script = table_script(table_designer_page);
+
+        ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+            let table = &mut table_designer_page.table;
+
+            CollapsingHeader::new(self.trans.columns())
+                .id_salt(("table_designer_columns", page_index))
+                .default_open(true)
+                .show(ui, |ui| {
+                    let (mut moved, mut removed) = (None, None);
+                    // ALTER TABLE can't reorder columns, so existing ones stay put.
+                    let movable: Vec<bool> = table
+                        .columns
+                        .iter()
+                        .map(|column| is_new || column.original_name.is_none())
+                        .collect();
+
+                    Grid::new(("table_designer_columns_grid", page_index))
+                        .num_columns(5)
+                        .striped(true)
+                        .show(ui, |ui| {
+                            ui.label("");
+                            ui.label(RichText::new(self.trans.name()).strong());
+                            ui.label(RichText::new(self.trans.data_type()).strong());
+                            ui.label(RichText::new("NOT NULL").strong());
+                            ui.label(RichText::new(self.trans.default_value()).strong());
+                            ui.end_row();
+
+                            for (idx, column) in table.columns.iter_mut().enumerate() {
+                                row_buttons(ui, idx, &movable, &mut moved, &mut removed);
+                                ui.add(TextEdit::singleline(&mut column.name).background_color(input_color).desired_width(160.0));
+                                ui.horizontal(|ui| {
+                                    ui.add(
+                                        TextEdit::singleline(&mut column.data_type)
+                                            .background_color(input_color)
+                                            .desired_width(180.0),
+                                    );
+                                    ui.menu_button("⏷", |ui| {
+                                        for data_type in DATA_TYPES {
+                                            if ui.button(data_type).clicked() {
+                                                column.data_type = data_type.to_string();
+                                                ui.close_menu();
+                                            }
+                                        }
+                                    });
+                                });
+                                ui.checkbox(&mut column.not_null, "");
+                                ui.add(TextEdit::singleline(&mut column.default).background_color(input_color).desired_width(180.0));
+                                ui.end_row();
+                            }
+                        });
+
+                    apply_row_changes(&mut table.columns, moved, removed);
+
+                    if !is_new {
+                        ui.label(RichText::new(self.trans.column_order_note()).color(Color32::GRAY));
+                    }
+                    if ui.button(self.trans.add_column()).clicked() {
+                        table.columns.push(structs::TableColumn {
+                            data_type: String::from("text"),
+                            ..Default::default()
+                        });
+                    }
+                });
+
+            CollapsingHeader::new(self.trans.constraints())
+                .id_salt(("table_designer_constraints", page_index))
+                .default_open(true)
+                .show(ui, |ui| {
+                    let (mut moved, mut removed) = (None, None);
+                    let movable = vec![true; table.constraints.len()];
+
+                    for (idx, constraint) in table.constraints.iter_mut().enumerate() {
+                        ui.horizontal_wrapped(|ui| {
+                            row_buttons(ui, idx, &movable, &mut moved, &mut removed);
+
+                            ComboBox::from_id_salt(("table_designer_constraint_kind", page_index, idx))
+                                .selected_text(constraint.kind.keyword())
+                                .show_ui(ui, |ui| {
+                                    for kind in structs::TableConstraintKind::ALL {
+                                        ui.selectable_value(&mut constraint.kind, kind, kind.keyword());
+                                    }
+                                });
+                            ui.add(
+                                TextEdit::singleline(&mut constraint.name)
+                                    .background_color(input_color)
+                                    .hint_text(self.trans.name())
+                                    .desired_width(140.0),
+                            );
+
+                            if constraint.kind == structs::TableConstraintKind::Check {
+                                ui.add(
+                                    TextEdit::singleline(&mut constraint.expression)
+                                        .background_color(input_color)
+                                        .hint_text(self.trans.expression())
+                                        .desired_width(260.0),
+                                );
+                                return;
+                            }
+
+                            ui.add(
+                                TextEdit::singleline(&mut constraint.columns)
+                                    .background_color(input_color)
+                                    .hint_text(self.trans.columns())
+                                    .desired_width(160.0),
+                            );
+                            column_picker(ui, &mut constraint.columns, &column_names, false);
+
+                            if constraint.kind == structs::TableConstraintKind::ForeignKey {
+                                ui.label("→");
+                                ui.add(
+                                    TextEdit::singleline(&mut constraint.ref_table)
+                                        .background_color(input_color)
+                                        .hint_text(self.trans.table())
+                                        .desired_width(140.0),
+                                );
+                                ui.add(
+                                    TextEdit::singleline(&mut constraint.ref_columns)
+                                        .background_color(input_color)
+                                        .hint_text(self.trans.columns())
+                                        .desired_width(120.0),
+                                );
+                                for (action, label, salt) in [
+                                    (&mut constraint.on_update, "ON UPDATE", "update"),
+                                    (&mut constraint.on_delete, "ON DELETE", "delete"),
+                                ] {
+                                    ui.label(label);
+                                    ComboBox::from_id_salt(("table_designer_constraint_action", page_index, idx, salt))
+                                        .selected_text(if action.is_empty() { "NO ACTION" } else { action.as_str() })
+                                        .show_ui(ui, |ui| {
+                                            for referential_action in REFERENTIAL_ACTIONS {
+                                                ui.selectable_value(action, referential_action.to_string(), referential_action);
+                                            }
+                                        });
+                                }
+                            }
+                        });
+                    }
+
+                    apply_row_changes(&mut table.constraints, moved, removed);
+
+                    if ui.button(self.trans.add_constraint()).clicked() {
+                        table.constraints.push(structs::TableConstraint::default());
+                    }
+                });
+
+            CollapsingHeader::new(self.trans.indexes())
+                .id_salt(("table_designer_indexes", page_index))
+                .default_open(true)
+                .show(ui, |ui| {
+                    let (mut moved, mut removed) = (None, None);
+                    let movable = vec![true; table.indexes.len()];
+
+                    for (idx, index) in table.indexes.iter_mut().enumerate() {
+                        ui.horizontal_wrapped(|ui| {
+                            row_buttons(ui, idx, &movable, &mut moved, &mut removed);
+
+                            ui.add(
+                                TextEdit::singleline(&mut index.name)
+                                    .background_color(input_color)
+                                    .hint_text(self.trans.name())
+                                    .desired_width(160.0),
+                            );
+                            ui.checkbox(&mut index.unique, "UNIQUE");
+                            ComboBox::from_id_salt(("table_designer_index_method", page_index, idx))
+                                .selected_text(if index.method.is_empty() { "btree" } else { index.method.as_str() })
+                                .show_ui(ui, |ui| {
+                                    for method in INDEX_METHODS {
+                                        ui.selectable_value(&mut index.method, method.to_string(), method);
+                                    }
+                                });
+                            ui.add(
+                                TextEdit::singleline(&mut index.columns)
+                                    .background_color(input_color)
+                                    .hint_text(self.trans.columns())
+                                    .desired_width(180.0),
+                            );
+                            column_picker(ui, &mut index.columns, &column_names, true);
+                            ui.add(
+                                TextEdit::singleline(&mut index.include)
+                                    .background_color(input_color)
+                                    .hint_text("INCLUDE")
+                                    .desired_width(120.0),
+                            );
+                            column_picker(ui, &mut index.include, &column_names, true);
+                            ui.add(
+                                TextEdit::singleline(&mut index.predicate)
+                                    .background_color(input_color)
+                                    .hint_text("WHERE")
+                                    .desired_width(160.0),
+                            );
+                        });
+                    }
+
+                    apply_row_changes(&mut table.indexes, moved, removed);
+
+                    if ui.button(self.trans.add_index()).clicked() {
+                        table.indexes.push(structs::TableIndex {
+                            method: String::from("btree"),
+                            ..Default::default()
+                        });
+                    }
+                });
+
+            ui.separator();
+
+            ui.horizontal(|ui| {
+                if ui.add_enabled(!script.is_empty(), Button::new(self.trans.review())).clicked() {
+                    review = true;
+                }
+                if let Some(original) = &table_designer_page.original
+                    && ui.add_enabled(!script.is_empty(), Button::new(self.trans.discard())).clicked()
+                {
+                    table_designer_page.table = original.clone();
+                }
+            });
+
+            if !script.is_empty() {
+                let mut preview = script.clone();
+                TextEdit::multiline(&mut preview)
+                    .font(egui::TextStyle::Monospace)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .background_color(input_color)
+                    .interactive(false)
+                    .show(ui);
+            }
+        });
+
+        if review {
+            let server_id = table_designer_page.server_id;
+            let database_name = table_designer_page.database_name.clone();
+
+            self.open_script_window(
+                self.trans.review(),
+                server_id,
+                &database_name,
+                script,
+                None,
+                structs::AfterScript::ReloadTable(page_index),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn column(name: &str, data_type: &str) -> structs::TableColumn {
+        structs::TableColumn {
+            name: name.to_string(),
+            data_type: data_type.to_string(),
+            original_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn constraint(name: &str, kind: structs::TableConstraintKind, columns: &str) -> structs::TableConstraint {
+        structs::TableConstraint {
+            name: name.to_string(),
+            kind,
+            columns: columns.to_string(),
+            original_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn index(name: &str, columns: &str, include: &str) -> structs::TableIndex {
+        structs::TableIndex {
+            name: name.to_string(),
+            columns: columns.to_string(),
+            include: include.to_string(),
+            method: String::from("btree"),
+            original_name: Some(name.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// `users (id integer NOT NULL, email text, age integer)` with a
+    /// primary key, a unique constraint and an index.
+    fn users() -> structs::TableDefinition {
+        structs::TableDefinition {
+            schema: String::from("public"),
+            name: String::from("users"),
+            columns: vec![
+                structs::TableColumn {
+                    not_null: true,
+                    ..column("id", "integer")
+                },
+                column("email", "text"),
+                column("age", "integer"),
+            ],
+            constraints: vec![
+                constraint("users_pkey", structs::TableConstraintKind::PrimaryKey, "id"),
+                constraint("users_email_key", structs::TableConstraintKind::Unique, "email"),
+            ],
+            indexes: vec![index("users_age_idx", "age DESC", "email")],
+        }
+    }
+
+    #[test]
+    fn creates_table() {
+        let mut table = users();
+        table.schema = String::from("Sales");
+        table.columns[2].default = String::from("18");
+        table.constraints.push(structs::TableConstraint {
+            ref_table: String::from("public.accounts"),
+            ref_columns: String::from("id"),
+            on_delete: String::from("CASCADE"),
+            ..constraint("", structs::TableConstraintKind::ForeignKey, "id")
+        });
+        table.indexes[0].name = String::new();
+
+        assert_eq!(
+            create_table_script(&table),
+            "CREATE TABLE \"Sales\".users (\n    \
+                id integer NOT NULL,\n    \
+                email text,\n    \
+                age integer DEFAULT 18,\n    \
+                CONSTRAINT users_pkey PRIMARY KEY (id),\n    \
+                CONSTRAINT users_email_key UNIQUE (email),\n    \
+                FOREIGN KEY (id) REFERENCES public.accounts (id) ON DELETE CASCADE\n\
+            );\n\
+            CREATE INDEX ON \"Sales\".users USING btree (age DESC) INCLUDE (email);"
+        );
+    }
+
+    #[test]
+    fn leaves_unchanged_table_alone() {
+        assert_eq!(alter_table_script(&users(), &users()), "");
+    }
+
+    #[test]
+    fn renames_column_used_by_constraint_and_index() {
+        let mut table = users();
+        table.columns[1].name = String::from("mail");
+
+        // Postgres follows the rename in constraints and indexes by itself.
+        assert_eq!(
+            alter_table_script(&users(), &table),
+            "ALTER TABLE public.users RENAME COLUMN email TO mail;"
+        );
+
+        // Recreated ones are created after the rename, under the new name.
+        table.constraints[1].columns = String::from("email, age");
+        table.indexes[0].method = String::from("hash");
+        assert_eq!(
+            alter_table_script(&users(), &table),
+            "ALTER TABLE public.users DROP CONSTRAINT users_email_key;\n\
+            DROP INDEX public.users_age_idx;\n\
+            ALTER TABLE public.users RENAME COLUMN email TO mail;\n\
+            ALTER TABLE public.users ADD CONSTRAINT users_email_key UNIQUE (mail, age);\n\
+            CREATE INDEX users_age_idx ON public.users USING hash (age DESC) INCLUDE (mail);"
+        );
+    }
+
+    #[test]
+    fn changes_column_type_default_and_not_null() {
+        let mut table = users();
+        table.columns[0].not_null = false;
+        table.columns[2].data_type = String::from("bigint");
+        table.columns[2].not_null = true;
+        table.columns[2].default = String::from("0");
+
+        assert_eq!(
+            alter_table_script(&users(), &table),
+            "ALTER TABLE public.users ALTER COLUMN id DROP NOT NULL;\n\
+            ALTER TABLE public.users ALTER COLUMN age TYPE bigint USING age::bigint;\n\
+            ALTER TABLE public.users ALTER COLUMN age SET NOT NULL;\n\
+            ALTER TABLE public.users ALTER COLUMN age SET DEFAULT 0;"
+        );
+
+        let mut original = table.clone();
+        table.columns[2].default = String::new();
+        original.columns[2].default = String::from("0");
+        assert_eq!(
+            alter_table_script(&original, &table),
+            "ALTER TABLE public.users ALTER COLUMN age DROP DEFAULT;"
+        );
+    }
+
+    #[test]
+    fn renames_and_changes_constraints_and_indexes() {
+        let mut table = users();
+        table.constraints[0].name = String::from("users_id_pk");
+        table.indexes[0].name = String::from("users_by_age");
+
+        assert_eq!(
+            alter_table_script(&users(), &table),
+            "ALTER TABLE public.users RENAME CONSTRAINT users_pkey TO users_id_pk;\n\
+            ALTER INDEX public.users_age_idx RENAME TO users_by_age;"
+        );
+
+        let mut table = users();
+        table.constraints[1].columns = String::from("email, age");
+        table.indexes[0].include = String::new();
+        table.columns.remove(0);
+        table.constraints.remove(0);
+
+        assert_eq!(
+            alter_table_script(&users(), &table),
+            "ALTER TABLE public.users DROP CONSTRAINT users_pkey;\n\
+            ALTER TABLE public.users DROP CONSTRAINT users_email_key;\n\
+            DROP INDEX public.users_age_idx;\n\
+            ALTER TABLE public.users DROP COLUMN id;\n\
+            ALTER TABLE public.users ADD CONSTRAINT users_email_key UNIQUE (email, age);\n\
+            CREATE INDEX users_age_idx ON public.users USING btree (age DESC);"
+        );
+    }
+
+    #[test]
+    fn quotes_referenced_table() {
+        assert_eq!(table_reference("users"), "users");
+        assert_eq!(table_reference(" sales . Orders "), "sales.\"Orders\"");
+        assert_eq!(table_reference("\"My \"\"Table\"\""), "\"My \"\"Table\"\"\"");
+        assert_eq!(table_reference("\"a.b\".c"), "\"a.b\".c");
+        assert_eq!(
+            table_reference("users(id); DROP TABLE users; --"),
+            "\"users(id); DROP TABLE users; --\""
+        );
+    }
+}
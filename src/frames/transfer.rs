@@ -0,0 +1,428 @@
+use super::{Main, widgets};
+
+use crate::data::structs;
+use crate::utils;
+use crate::vault;
+
+use egui::{Align, Button, Color32, ComboBox, Grid, Id, Layout, Modal, RichText, ScrollArea, TextEdit};
+use egui_file_dialog::DialogState;
+use std::fs as std_fs;
+use std::path::Path;
+use uuid::Uuid;
+
+impl Main<'_> {
+    pub(super) fn servers_file_dialog_is_open(&self) -> bool {
+        matches!(self.servers_file_dialog.state(), DialogState::Open)
+    }
+
+    fn write_servers_export(path: &Path, export: &structs::ServersExport) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(export).map_err(|e| e.to_string())?;
+
+        utils::write_atomic(path, &content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    /// Writes server definitions without passwords, so they survive
+    /// clearing the storage when the master password is lost.
+    pub(super) fn export_servers_without_passwords(&self, path: &Path) -> Result<(), String> {
+        let export = structs::ServersExport {
+            servers: self
+                .config
+                .servers
+                .iter()
+                .map(|server| structs::Server {
+                    password: String::new(),
+                    ..server.clone()
+                })
+                .collect(),
+            folders: self.config.folders.clone(),
+            ..Default::default()
+        };
+
+        Self::write_servers_export(path, &export)
+    }
+
+    fn export_servers(&self, path: &Path) -> Result<(), String> {
+        let window = &self.export_servers_window;
+        let include_passwords = window.encrypt && window.include_passwords;
+
+        let servers: Vec<structs::Server> = self
+            .config
+            .servers
+            .iter()
+            .filter(|server| window.selected.contains(&server.id))
+            .map(|server| structs::Server {
+                password: if include_passwords {
+                    server.password.clone()
+                } else {
+                    String::new()
+                },
+                ..server.clone()
+            })
+            .collect();
+
+        let folders: Vec<String> = self
+            .config
+            .folders
+            .iter()
+            .filter(|path| {
+                servers.iter().any(|server| {
+                    server
+                        .folder
+                        .as_ref()
+                        .is_some_and(|folder| folder == *path || folder.starts_with(&format!("{}/", path)))
+                })
+            })
+            .cloned()
+            .collect();
+
+        let export = structs::ServersExport {
+            servers,
+            folders,
+            ..Default::default()
+        };
+
+        let export = if window.encrypt {
+            let (vault, vault_key) = vault::Vault::create(&window.passphrase)?;
+            let data = serde_json::to_string(&export).map_err(|e| e.to_string())?;
+
+            structs::ServersExport {
+                vault: Some(vault),
+                data: Some(vault_key.encrypt(&data)?),
+                ..Default::default()
+            }
+        } else {
+            export
+        };
+
+        Self::write_servers_export(path, &export)
+    }
+
+    fn read_servers_export(&mut self, path: &Path) -> Result<(), String> {
+        let content =
+            std_fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+        let export: structs::ServersExport =
+            serde_json::from_str(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+
+        self.import_servers_window = structs::ImportServersWindow {
+            show: true,
+            ..Default::default()
+        };
+
+        if export.vault.is_some() {
+            self.import_servers_window.encrypted = Some(export);
+        } else {
+            self.prepare_import(export);
+        }
+
+        Ok(())
+    }
+
+    fn decrypt_servers_export(&mut self) -> Result<(), String> {
+        let window = &self.import_servers_window;
+        let Some(structs::ServersExport {
+            vault: Some(vault),
+            data: Some(data),
+            ..
+        }) = &window.encrypted
+        else {
+            return Err(self.trans.incorrect_passphrase());
+        };
+
+        let data = vault
+            .unlock(&window.passphrase)
+            .and_then(|vault_key| vault_key.decrypt(data))
+            .map_err(|_| self.trans.incorrect_passphrase())?;
+        let export: structs::ServersExport =
+            serde_json::from_str(&data).map_err(|e| e.to_string())?;
+
+        self.import_servers_window.encrypted = None;
+        self.prepare_import(export);
+
+        Ok(())
+    }
+
+    fn prepare_import(&mut self, export: structs::ServersExport) {
+        self.import_servers_window.servers = export
+            .servers
+            .into_iter()
+            .map(|server| {
+                let conflict = self
+                    .config
+                    .servers
+                    .iter()
+                    .find(|existing| existing.id == server.id || existing.alias == server.alias)
+                    .map(|existing| existing.id);
+
+                structs::ImportServer {
+                    server,
+                    selected: true,
+                    conflict,
+                    resolution: structs::ImportResolution::default(),
+                }
+            })
+            .collect();
+        self.import_servers_window.folders = export.folders;
+    }
+
+    fn unique_alias(&self, alias: &str) -> String {
+        (2..)
+            .map(|n| format!("{} ({})", alias, n))
+            .find(|candidate| !self.config.servers.iter().any(|server| &server.alias == candidate))
+            .unwrap()
+    }
+
+    fn import_servers(&mut self) {
+        let window = std::mem::take(&mut self.import_servers_window);
+
+        for import in window.servers.into_iter().filter(|import| import.selected) {
+            let mut server = import.server;
+
+            match (import.conflict, import.resolution) {
+                (None, _) => {
+                    if server.id.is_nil()
+                        || self.config.servers.iter().any(|existing| existing.id == server.id)
+                    {
+                        server.id = Uuid::new_v4();
+                    }
+                    self.config.servers.push(server);
+                }
+                (Some(_), structs::ImportResolution::Skip) => {}
+                (Some(existing_id), structs::ImportResolution::Overwrite) => {
+                    if let Some(existing) = self
+                        .config
+                        .servers
+                        .iter_mut()
+                        .find(|existing| existing.id == existing_id)
+                    {
+                        server.id = existing_id;
+                        if server.password.is_empty() {
+                            server.password = existing.password.clone();
+                        }
+                        *existing = server.clone();
+                    }
+                    self.db_manager.dbs.lock().unwrap().remove(&existing_id);
+                    self.rebind_pages(&server);
+                }
+                (Some(_), structs::ImportResolution::Rename) => {
+                    server.id = Uuid::new_v4();
+                    server.alias = self.unique_alias(&server.alias);
+                    self.config.servers.push(server);
+                }
+            }
+        }
+
+        for folder in window.folders {
+            if !self.config.folders.contains(&folder) {
+                self.config.folders.push(folder);
+            }
+        }
+
+        self.save_config();
+    }
+
+    pub(super) fn update_transfer_windows(&mut self, ctx: &egui::Context) {
+        self.servers_file_dialog.update(ctx);
+
+        if let Some(action) = self.servers_file_dialog_action.clone()
+            && let Some(path) = self.servers_file_dialog.take_picked()
+        {
+            self.servers_file_dialog_action = None;
+
+            let result = match action {
+                structs::ServersFileDialogAction::ExportWithoutPasswords => {
+                    self.export_servers_without_passwords(&path)
+                }
+                structs::ServersFileDialogAction::Export => {
+                    let result = self.export_servers(&path);
+                    self.export_servers_window = structs::ExportServersWindow::default();
+                    result
+                }
+                structs::ServersFileDialogAction::Import => self.read_servers_export(&path),
+            };
+
+            if let Err(e) = result {
+                self.show_error(e);
+            }
+        }
+
+        if self.servers_file_dialog_is_open() {
+            return;
+        }
+
+        if self.export_servers_window.show {
+            Modal::new(Id::new("export_servers_modal")).show(ctx, |ui| {
+                widgets::modal_label(ui, self.trans.export_servers());
+
+                ScrollArea::vertical().max_height(240.0).show(ui, |ui| {
+                    for server in self.config.servers.iter() {
+                        let mut selected = self.export_servers_window.selected.contains(&server.id);
+
+                        if ui
+                            .checkbox(
+                                &mut selected,
+                                format!("{} ({}:{})", server.alias, server.ip, server.port),
+                            )
+                            .changed()
+                        {
+                            if selected {
+                                self.export_servers_window.selected.push(server.id);
+                            } else {
+                                self.export_servers_window.selected.retain(|id| id != &server.id);
+                            }
+                        }
+                    }
+                });
+
+                ui.separator();
+
+                ui.checkbox(
+                    &mut self.export_servers_window.encrypt,
+                    self.trans.encrypt_with_passphrase(),
+                );
+
+                if self.export_servers_window.encrypt {
+                    ui.checkbox(
+                        &mut self.export_servers_window.include_passwords,
+                        self.trans.include_passwords(),
+                    );
+
+                    Grid::new("export_servers_form")
+                        .num_columns(2)
+                        .spacing([40.0, 4.0])
+                        .show(ui, |ui| {
+                            ui.label(self.trans.passphrase());
+                            TextEdit::singleline(&mut self.export_servers_window.passphrase)
+                                .background_color(self.config.settings.theme.text_input_color())
+                                .password(true)
+                                .show(ui);
+                            ui.end_row();
+
+                            ui.label(self.trans.confirm_password());
+                            TextEdit::singleline(&mut self.export_servers_window.confirm_passphrase)
+                                .background_color(self.config.settings.theme.text_input_color())
+                                .password(true)
+                                .show(ui);
+                            ui.end_row();
+                        });
+
+                    if self.export_servers_window.passphrase
+                        != self.export_servers_window.confirm_passphrase
+                    {
+                        ui.label(
+                            RichText::new(self.trans.passwords_do_not_match()).color(Color32::RED),
+                        );
+                    }
+                } else {
+                    ui.label(self.trans.plain_export_description());
+                }
+
+                let can_export = !self.export_servers_window.selected.is_empty()
+                    && (!self.export_servers_window.encrypt
+                        || (!self.export_servers_window.passphrase.is_empty()
+                            && self.export_servers_window.passphrase
+                                == self.export_servers_window.confirm_passphrase));
+
+                ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        if ui.add_enabled(can_export, Button::new(self.trans.export())).clicked() {
+                            self.servers_file_dialog_action =
+                                Some(structs::ServersFileDialogAction::Export);
+                            self.servers_file_dialog.save_file();
+                        }
+                        if ui.button(self.trans.close()).clicked() {
+                            self.export_servers_window = structs::ExportServersWindow::default();
+                        }
+                    });
+                });
+            });
+        }
+
+        if self.import_servers_window.show {
+            Modal::new(Id::new("import_servers_modal")).show(ctx, |ui| {
+                widgets::modal_label(ui, self.trans.import_servers());
+
+                if let Some(error) = &self.import_servers_window.error {
+                    ui.label(RichText::new(error).color(Color32::RED));
+                }
+
+                if self.import_servers_window.encrypted.is_some() {
+                    ui.horizontal(|ui| {
+                        ui.label(self.trans.passphrase());
+                        TextEdit::singleline(&mut self.import_servers_window.passphrase)
+                            .background_color(self.config.settings.theme.text_input_color())
+                            .password(true)
+                            .show(ui);
+                    });
+
+                    ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                        ui.separator();
+
+                        ui.horizontal(|ui| {
+                            if ui.button(self.trans.decrypt()).clicked() {
+                                self.import_servers_window.error =
+                                    self.decrypt_servers_export().err();
+                            }
+                            if ui.button(self.trans.close()).clicked() {
+                                self.import_servers_window = structs::ImportServersWindow::default();
+                            }
+                        });
+                    });
+
+                    return;
+                }
+
+                ScrollArea::vertical().max_height(320.0).show(ui, |ui| {
+                    Grid::new("import_servers_grid")
+                        .num_columns(2)
+                        .spacing([40.0, 4.0])
+                        .striped(true)
+                        .show(ui, |ui| {
+                            for (idx, import) in
+                                self.import_servers_window.servers.iter_mut().enumerate()
+                            {
+                                ui.checkbox(
+                                    &mut import.selected,
+                                    format!(
+                                        "{} ({}:{})",
+                                        import.server.alias, import.server.ip, import.server.port
+                                    ),
+                                );
+
+                                if import.conflict.is_some() {
+                                    ComboBox::from_id_salt(("import_resolution", idx))
+                                        .selected_text(self.trans.import_resolution(import.resolution))
+                                        .show_ui(ui, |ui| {
+                                            for resolution in structs::ImportResolution::ALL {
+                                                ui.selectable_value(
+                                                    &mut import.resolution,
+                                                    resolution,
+                                                    self.trans.import_resolution(resolution),
+                                                );
+                                            }
+                                        });
+                                } else {
+                                    ui.label(self.trans.new_server());
+                                }
+                                ui.end_row();
+                            }
+                        });
+                });
+
+                ui.with_layout(Layout::top_down(Align::RIGHT), |ui| {
+                    ui.separator();
+
+                    ui.horizontal(|ui| {
+                        if ui.button(self.trans.import()).clicked() {
+                            self.import_servers();
+                        }
+                        if ui.button(self.trans.close()).clicked() {
+                            self.import_servers_window = structs::ImportServersWindow::default();
+                        }
+                    });
+                });
+            });
+        }
+    }
+}
@@ -0,0 +1,603 @@
+use super::{Main, widgets};
+
+use crate::data::*;
+
+use egui::{CollapsingHeader, Color32, Id, Label, RichText, Sense, Spinner, TextEdit, Ui};
+use uuid::Uuid;
+
+fn matches(text: &str, filter: &str) -> bool {
+    filter.is_empty() || text.to_lowercase().contains(filter)
+}
+
+impl Main<'_> {
+    pub(super) fn update_tree(&mut self, ui: &mut Ui) {
+        ui.add(
+            TextEdit::singleline(&mut self.tree_filter)
+                .hint_text(self.trans.search())
+                .background_color(self.config.settings.theme.text_input_color())
+                .desired_width(f32::INFINITY),
+        );
+
+        ui.add_space(4.0);
+
+        let filter = self.tree_filter.trim().to_lowercase();
+
+        CollapsingHeader::new(self.trans.servers())
+            .default_open(true)
+            .show(ui, |ui| {
+                let favorites: Vec<usize> = (0..self.config.servers.len())
+                    .filter(|&idx| self.config.servers[idx].favorite)
+                    .collect();
+
+                for idx in favorites {
+                    if self.server_matches(idx, &filter) {
+                        self.show_server(ui, idx, &filter);
+                    }
+                }
+
+                self.show_folder_contents(ui, None, &filter);
+
+                ui.horizontal(|ui| {
+                    if ui.button(self.trans.add_server()).clicked() {
+                        self.add_server_window.show = true;
+                    }
+                    if ui.button(self.trans.add_folder()).clicked() {
+                        self.folder_window = structs::FolderWindow {
+                            show: true,
+                            ..Default::default()
+                        };
+                    }
+                });
+
+                ui.horizontal(|ui| {
+                    if ui.button(self.trans.export()).clicked() {
+                        self.export_servers_window = structs::ExportServersWindow {
+                            show: true,
+                            selected: self.config.servers.iter().map(|server| server.id).collect(),
+                            ..Default::default()
+                        };
+                    }
+                    if ui.button(self.trans.import()).clicked() {
+                        self.servers_file_dialog_action =
+                            Some(structs::ServersFileDialogAction::Import);
+                        self.servers_file_dialog.pick_file();
+                    }
+                });
+            });
+    }
+
+    fn show_folder_contents(&mut self, ui: &mut Ui, folder: Option<&str>, filter: &str) {
+        let subfolders: Vec<String> = self
+            .config
+            .folders
+            .iter()
+            .filter(|path| structs::folder_parent(path).as_deref() == folder)
+            .cloned()
+            .collect();
+
+        for path in subfolders {
+            if self.folder_matches(&path, filter) {
+                self.show_folder(ui, &path, filter);
+            }
+        }
+
+        let servers: Vec<usize> = (0..self.config.servers.len())
+            .filter(|&idx| {
+                let server = &self.config.servers[idx];
+                !server.favorite && server.folder.as_deref() == folder
+            })
+            .collect();
+
+        for idx in servers {
+            if self.server_matches(idx, filter) {
+                self.show_server(ui, idx, filter);
+            }
+        }
+    }
+
+    fn show_folder(&mut self, ui: &mut Ui, path: &str, filter: &str) {
+        let header = CollapsingHeader::new(format!("🗀 {}", structs::folder_name(path)))
+            .id_salt(format!("folder:{}", path))
+            .open((!filter.is_empty()).then_some(true))
+            .show(ui, |ui| {
+                self.show_folder_contents(ui, Some(path), filter);
+            })
+            .header_response;
+
+        if let Some(dragged) = header.dnd_release_payload::<Uuid>() {
+            self.actions.push(structs::Action::MoveServerToFolder(
+                *dragged,
+                Some(path.to_string()),
+            ));
+        }
+
+        header.context_menu(|ui| {
+            if ui.button(self.trans.add_folder()).clicked() {
+                self.folder_window = structs::FolderWindow {
+                    show: true,
+                    parent: Some(path.to_string()),
+                    ..Default::default()
+                };
+                ui.close_menu();
+            }
+            if ui.button(self.trans.rename()).clicked() {
+                self.folder_window = structs::FolderWindow {
+                    show: true,
+                    name_field: structs::folder_name(path).to_string(),
+                    parent: structs::folder_parent(path),
+                    original_path: Some(path.to_string()),
+                };
+                ui.close_menu();
+            }
+            if ui.button(self.trans.delete()).clicked() {
+                self.delete_folder(path);
+                ui.close_menu();
+            }
+        });
+    }
+
+    fn folder_matches(&self, path: &str, filter: &str) -> bool {
+        if matches(structs::folder_name(path), filter) {
+            return true;
+        }
+
+        let prefix = format!("{}/", path);
+
+        self.config.folders.iter().any(|other| {
+            other.starts_with(&prefix) && matches(structs::folder_name(other), filter)
+        }) || (0..self.config.servers.len()).any(|idx| {
+            let server = &self.config.servers[idx];
+            !server.favorite
+                && server
+                    .folder
+                    .as_ref()
+                    .is_some_and(|folder| folder == path || folder.starts_with(&prefix))
+                && self.server_matches(idx, filter)
+        })
+    }
+
+    fn server_matches(&self, idx: usize, filter: &str) -> bool {
+        let server = &self.config.servers[idx];
+
+        if matches(&server.alias, filter) || matches(&server.ip, filter) {
+            return true;
+        }
+
+        let dbs = self.db_manager.dbs.lock().expect("Failed to lock dbs mutex");
+
+        match dbs.get(&server.id) {
+            Some(structs::DbState::Loaded(databases)) => databases
+                .iter()
+                .any(|database| Self::database_matches(database, filter)),
+            _ => false,
+        }
+    }
+
+    fn database_matches(database: &structs::LoadedDatabase, filter: &str) -> bool {
+        matches(&database.name, filter) || Self::tables_match(database, filter)
+    }
+
+    /// Whether already loaded tables of `database` match `filter`. Tables
+    /// are never loaded for the filter, as that connects to the database.
+    fn tables_match(database: &structs::LoadedDatabase, filter: &str) -> bool {
+        match &*database.tables.lock().unwrap() {
+            Some(structs::TablesState::Loaded(tables)) => {
+                !filter.is_empty() && tables.iter().any(|table| matches(table, filter))
+            }
+            _ => false,
+        }
+    }
+
+    fn delete_folder(&mut self, path: &str) {
+        let parent = structs::folder_parent(path);
+        let prefix = format!("{}/", path);
+
+        let reparent = |folder: &str| -> String {
+            let rest = &folder[prefix.len()..];
+            match &parent {
+                Some(parent) => format!("{}/{}", parent, rest),
+                None => rest.to_string(),
+            }
+        };
+
+        self.config.folders.retain(|folder| folder != path);
+        for folder in self.config.folders.iter_mut() {
+            if folder.starts_with(&prefix) {
+                *folder = reparent(folder);
+            }
+        }
+        for server in self.config.servers.iter_mut() {
+            match server.folder.as_deref() {
+                Some(folder) if folder == path => server.folder = parent.clone(),
+                Some(folder) if folder.starts_with(&prefix) => {
+                    server.folder = Some(reparent(folder))
+                }
+                _ => {}
+            }
+        }
+
+        self.config.folders.sort();
+        self.config.folders.dedup();
+        self.save_config();
+    }
+
+    pub(super) fn rename_folder(&mut self, path: &str, new_path: &str) {
+        let prefix = format!("{}/", path);
+
+        for folder in self.config.folders.iter_mut() {
+            if folder == path {
+                *folder = new_path.to_string();
+            } else if folder.starts_with(&prefix) {
+                *folder = format!("{}/{}", new_path, &folder[prefix.len()..]);
+            }
+        }
+        for server in self.config.servers.iter_mut() {
+            match server.folder.as_deref() {
+                Some(folder) if folder == path => server.folder = Some(new_path.to_string()),
+                Some(folder) if folder.starts_with(&prefix) => {
+                    server.folder = Some(format!("{}/{}", new_path, &folder[prefix.len()..]))
+                }
+                _ => {}
+            }
+        }
+
+        self.config.folders.sort();
+        self.save_config();
+    }
+
+    fn show_server(&mut self, ui: &mut Ui, idx: usize, filter: &str) {
+        let server = self.config.servers[idx].clone();
+
+        ui.horizontal(|ui| {
+            let server_id = format!("server:{}", server.id);
+
+            ui.dnd_drag_source(Id::new(format!("{}:drag", server_id)), server.id, |ui| {
+                ui.add(Label::new(RichText::new("⠿").color(Color32::GRAY)).sense(Sense::hover()));
+            });
+
+            if let Some(color) = server.color {
+                ui.label(RichText::new("●").color(color.to_color32()));
+            }
+            if self.missing_passwords.contains(&server.id) {
+                ui.label(RichText::new("⚠").color(Color32::YELLOW))
+                    .on_hover_text(self.trans.password_missing());
+            }
+
+            let title = format!(
+                "{}{} ({}:{})",
+                if server.favorite { "★ " } else { "" },
+                server.alias,
+                server.ip,
+                server.port
+            );
+
+            let db_state = {
+                let dbs = self.db_manager.dbs.lock().expect("Failed to lock dbs mutex");
+                dbs.get(&server.id).cloned()
+            };
+
+            let id_string = format!("{}:warning", server_id);
+            let id = Id::new(&id_string);
+
+            let show_all = matches(&server.alias, filter) || matches(&server.ip, filter);
+
+            let server_button: Option<egui::Response> = match db_state {
+                Some(structs::DbState::Loading) => {
+                    ui.add(Spinner::new());
+
+                    Some(ui.label(title))
+                }
+                Some(structs::DbState::Loaded(databases)) => {
+                    let open_databases = !filter.is_empty()
+                        && databases.iter().any(|database| Self::database_matches(database, filter));
+
+                    Some(CollapsingHeader::new(title)
+                        .id_salt(&server_id)
+                        .open(open_databases.then_some(true))
+                        .show(ui, |ui| {
+                            CollapsingHeader::new(self.trans.databases())
+                                .id_salt(format!("{}:databases", server_id))
+                                .open(open_databases.then_some(true))
+                                .show(ui, |ui| {
+                                    for database in databases {
+                                        if show_all || Self::database_matches(&database, filter) {
+                                            self.show_database(ui, idx, &database, filter);
+                                        }
+                                    }
+                                })
+                                .header_response
+                                .context_menu(|ui| {
+                                    if ui.button(self.trans.create_database()).clicked() {
+                                        self.open_create_database_window(server.id);
+                                        ui.close_menu();
+                                    }
+                                    if ui.button(self.trans.refresh()).clicked() {
+                                        let dbs = self.db_manager.dbs.clone();
+                                        let server = server.clone();
+                                        self.runtime.spawn(async move {
+                                            Self::refresh_databases(server, dbs).await;
+                                        });
+                                        ui.close_menu();
+                                    }
+                                });
+
+                            if filter.is_empty() {
+                                self.show_roles(ui, &server);
+                            }
+                        }).header_response)
+                }
+                Some(structs::DbState::Error(e)) => {
+                    let warning_icon = match self.config.settings.theme {
+                        structs::Theme::Light => self.icons.warning_dark.clone(),
+                        _ => self.icons.warning_light.clone(),
+                    };
+                    let warning = ui.add(warning_icon);
+                    if warning.hovered() {
+                        egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), id, |ui| {
+                            ui.label(e);
+                        });
+                    }
+
+                    Some(ui.label(title))
+                }
+                None => {
+                    let dbs = self.db_manager.dbs.clone();
+                    let server_clone = server.clone();
+                    {
+                        let mut dbs = dbs.lock().expect("Failed to lock dbs mutex");
+                        dbs.insert(server.id, structs::DbState::Loading);
+                    }
+                    self.runtime.spawn(async move {
+                        Self::load_db(server_clone, dbs).await;
+                    });
+                    ui.add(Spinner::new());
+
+                    None
+                }
+            };
+
+            if let Some(server_button) = server_button {
+                if let Some(dragged) = server_button.dnd_release_payload::<Uuid>() {
+                    self.actions.push(structs::Action::MoveServer(*dragged, server.id));
+                }
+
+                if server_button.secondary_clicked() {
+                    ui.memory_mut(|mem| mem.open_popup(id));
+                }
+
+                if ui.memory(|mem| mem.is_popup_open(id)) {
+                    server_button.context_menu(|ui| {
+                        if ui.button(self.trans.delete()).clicked() {
+                            ui.memory_mut(|mem| mem.close_popup());
+                            self.delete_server_window.show = true;
+                            self.delete_server_window.server = Some(server.clone());
+                        } else if ui.button(self.trans.edit()).clicked() {
+                            self.edit_server_window.show = true;
+                            self.edit_server_window.server = Some(server.clone());
+                            self.edit_server_window.original_server = Some(server.clone());
+
+                            self.edit_server_window.name_field = server.alias.clone();
+                            self.edit_server_window.ip_field = server.ip.clone();
+                            self.edit_server_window.port_field = server.port.to_string();
+                            self.edit_server_window.user_field = server.user.clone();
+                            self.edit_server_window.password_field = server.password.clone();
+                            self.edit_server_window.service_database_field = server.service_database.clone();
+                            self.edit_server_window.pool_size_field = server.pool_size.to_string();
+                            self.edit_server_window.pool_idle_timeout_field = server.pool_idle_timeout.to_string();
+                        } else if ui.button(self.trans.activity()).clicked() {
+                            ui.memory_mut(|mem| mem.close_popup());
+                            self.open_activity_page(&server);
+                        } else if ui.button(self.trans.dashboard()).clicked() {
+                            ui.memory_mut(|mem| mem.close_popup());
+                            self.open_dashboard_page(ui.ctx(), &server);
+                        } else if ui.button(self.trans.reload()).clicked() {
+                            let dbs = self.db_manager.dbs.clone();
+                            let server = server.clone();
+
+                            ui.memory_mut(|mem| mem.close_popup());
+
+                            self.runtime.spawn(async move {
+                                Self::reload_server(server, dbs).await;
+                            });
+                        } else if ui
+                            .button(if server.favorite {
+                                self.trans.remove_from_favorites()
+                            } else {
+                                self.trans.add_to_favorites()
+                            })
+                            .clicked()
+                        {
+                            ui.memory_mut(|mem| mem.close_popup());
+                            self.config.servers[idx].favorite = !server.favorite;
+                            self.save_config();
+                        }
+
+                        ui.menu_button(self.trans.move_to_folder(), |ui| {
+                            let mut target: Option<Option<String>> = None;
+
+                            if ui.button(self.trans.root_folder()).clicked() {
+                                target = Some(None);
+                            }
+                            for folder in &self.config.folders {
+                                if ui.button(folder).clicked() {
+                                    target = Some(Some(folder.clone()));
+                                }
+                            }
+
+                            if let Some(target) = target {
+                                ui.memory_mut(|mem| mem.close_popup());
+                                self.actions.push(structs::Action::MoveServerToFolder(server.id, target));
+                            }
+                        });
+
+                        ui.menu_button(self.trans.color(), |ui| {
+                            let mut color: Option<Option<structs::ServerColor>> = None;
+
+                            if ui.button(self.trans.no_color()).clicked() {
+                                color = Some(None);
+                            }
+                            ui.horizontal(|ui| {
+                                for server_color in structs::ServerColor::ALL {
+                                    if ui.button(RichText::new("●").color(server_color.to_color32())).clicked() {
+                                        color = Some(Some(server_color));
+                                    }
+                                }
+                            });
+
+                            if let Some(color) = color {
+                                ui.memory_mut(|mem| mem.close_popup());
+                                self.config.servers[idx].color = color;
+                                self.save_config();
+                            }
+                        });
+                    });
+                }
+            }
+        });
+    }
+
+    fn show_database(&mut self, ui: &mut Ui, idx: usize, database: &structs::LoadedDatabase, filter: &str) {
+        let pages = &mut self.pages;
+        let server = &self.config.servers[idx];
+
+        let show_all_tables = matches(&database.name, filter);
+        let open_tables = Self::tables_match(database, filter);
+
+        let database_header = CollapsingHeader::new(&database.name)
+            .id_salt(format!("db_{}_{}", server.id, database.name))
+            .open(open_tables.then_some(true))
+            .show(ui, |ui| {
+                let tables_state = {
+                    let mut tables = database.tables.lock().unwrap();
+                    if tables.is_none() {
+                        *tables = Some(structs::TablesState::Loading);
+
+                        let database_clone = database.clone();
+                        self.runtime.spawn(async move {
+                            Self::load_tables(database_clone).await;
+                        });
+                    }
+                    tables.clone().unwrap()
+                };
+
+                CollapsingHeader::new(self.trans.tables())
+                    .id_salt(format!("tables_{}_{}", server.id, database.name))
+                    .open(open_tables.then_some(true))
+                    .show(ui, |ui| {
+                        let tables = match &tables_state {
+                            structs::TablesState::Loading => {
+                                ui.add(Spinner::new());
+                                return;
+                            }
+                            structs::TablesState::Loaded(tables) => tables,
+                            structs::TablesState::Error(e) => {
+                                ui.label(RichText::new(e).color(Color32::RED));
+                                return;
+                            }
+                        };
+
+                        for table in tables {
+                            if !show_all_tables && !matches(table, filter) {
+                                continue;
+                            }
+
+                            CollapsingHeader::new(table).id_salt(format!("table_{}_{}_{}", server.id, database.name, table)).show(ui, |ui| {
+                                CollapsingHeader::new(self.trans.scripts()).id_salt(format!("scripts_{}_{}_{}", server.id, database.name, table)).show(ui, |ui| {
+                                    widgets::script_preset(ui, pages, database, server, "Insert", scripts::INSERT.replace("{table_name}", table));
+                                    widgets::script_preset(ui, pages, database, server, "Update", scripts::UPDATE.replace("{table_name}", table));
+                                    widgets::script_preset(ui, pages, database, server, "Delete", scripts::DELETE.replace("{table_name}", table));
+                                    widgets::script_preset(ui, pages, database, server, "Select", scripts::SELECT.replace("{table_name}", table));
+                                    widgets::script_preset(ui, pages, database, server, "Select 100", scripts::SELECT_100.replace("{table_name}", table));
+                                    widgets::script_preset(ui, pages, database, server, self.trans.get_columns(), scripts::GET_TABLE_COLUMNS.replace("{table_name}", table));
+                                });
+                            }).header_response.context_menu(|ui| {
+                                if ui.button(self.trans.design_table()).clicked() {
+                                    self.actions.push(structs::Action::OpenTableDesigner(server.id, database.name.clone(), Some(table.clone())));
+                                    ui.close_menu();
+                                }
+                                if ui.button(self.trans.indexes()).clicked() {
+                                    self.actions.push(structs::Action::OpenIndexesPage(server.id, database.name.clone(), table.clone()));
+                                    ui.close_menu();
+                                }
+                                if ui.button(self.trans.compare_data()).clicked() {
+                                    self.actions.push(structs::Action::OpenDataCompare(server.id, database.name.clone(), table.clone()));
+                                    ui.close_menu();
+                                }
+                                if ui.button(self.trans.maintenance()).clicked() {
+                                    self.actions.push(structs::Action::OpenMaintenance(server.id, database.name.clone(), Some(table.clone())));
+                                    ui.close_menu();
+                                }
+                            });
+                        }
+                    });
+
+                CollapsingHeader::new(self.trans.scripts()).id_salt(format!("db_scripts_{}_{}", server.id, database.name)).show(ui, |ui| {
+                    widgets::script_preset(ui, pages, database, server, "Create table", scripts::CREATE_TABLE);
+                    widgets::script_preset(ui, pages, database, server, "Create index", scripts::CREATE_INDEX);
+                    widgets::script_preset(ui, pages, database, server, "Drop table", scripts::DROP_TABLE);
+                });
+
+                widgets::script_preset(ui, pages, database, server, "SQL Query", String::new());
+            })
+            .header_response;
+
+        let server_id = server.id;
+        database_header.context_menu(|ui| {
+            if ui.button(self.trans.query_statistics()).clicked() {
+                self.actions.push(structs::Action::OpenStatementsPage(server_id, database.name.clone()));
+                ui.close_menu();
+            }
+            if ui.button(self.trans.privileges()).clicked() {
+                self.actions.push(structs::Action::OpenPrivilegesPage(server_id, database.name.clone()));
+                ui.close_menu();
+            }
+            if ui.button(self.trans.er_diagram()).clicked() {
+                self.actions.push(structs::Action::OpenErDiagram(server_id, database.name.clone()));
+                ui.close_menu();
+            }
+            if ui.button(self.trans.compare_schema()).clicked() {
+                self.actions.push(structs::Action::OpenSchemaDiff(server_id, database.name.clone()));
+                ui.close_menu();
+            }
+
+            if ui.button(self.trans.create_table()).clicked() {
+                self.actions.push(structs::Action::OpenTableDesigner(server_id, database.name.clone(), None));
+                ui.close_menu();
+            }
+            if ui.button(self.trans.maintenance()).clicked() {
+                self.actions.push(structs::Action::OpenMaintenance(server_id, database.name.clone(), None));
+                ui.close_menu();
+            }
+
+            ui.separator();
+
+            if ui.button(self.trans.properties()).clicked() {
+                self.open_database_properties_window(server_id, &database.name);
+                ui.close_menu();
+            }
+            if ui.button(self.trans.alter_database()).clicked() {
+                self.open_alter_database_window(server_id, &database.name);
+                ui.close_menu();
+            }
+            if ui.button(self.trans.drop_database()).clicked() {
+                self.open_drop_database_window(server_id, &database.name);
+                ui.close_menu();
+            }
+
+            ui.separator();
+
+            if ui.button(self.trans.backup_database()).clicked() {
+                self.open_backup_window(structs::BackupTool::Dump, server_id, &database.name);
+                ui.close_menu();
+            }
+            if ui.button(self.trans.restore_database()).clicked() {
+                self.open_backup_window(structs::BackupTool::Restore, server_id, &database.name);
+                ui.close_menu();
+            }
+            if ui.button(self.trans.run_sql_file()).clicked() {
+                self.open_backup_window(structs::BackupTool::Psql, server_id, &database.name);
+                ui.close_menu();
+            }
+        });
+    }
+}
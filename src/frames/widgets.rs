@@ -1,5 +1,6 @@
 use egui::{
-    Context, ScrollArea, SidePanel, TopBottomPanel, Ui, RichText, Button,
+    Context, ScrollArea, SidePanel, TopBottomPanel, Ui, RichText, Button, Color32, Id, Pos2,
+    Sense, Shape, Stroke, Vec2, Align2, FontId,
 };
 
 use crate::data::structs;
@@ -13,7 +14,7 @@ pub fn modal_label(ui: &mut Ui, title: impl Into<RichText>) {
     });
 }
 
-pub fn top_panel(ctx: &Context, content: impl FnOnce(&mut Ui) -> ()) {
+pub fn top_panel(ctx: &Context, content: impl FnOnce(&mut Ui)) {
     TopBottomPanel::top("pages_panel").show(ctx, |ui| {
         ScrollArea::both().show(ui, |ui| {
             ui.add_space(2.0);
@@ -27,7 +28,7 @@ pub fn top_panel(ctx: &Context, content: impl FnOnce(&mut Ui) -> ()) {
     });
 }
 
-pub fn left_panel(ctx: &Context, content: impl FnOnce(&mut Ui) -> ()) {
+pub fn left_panel(ctx: &Context, content: impl FnOnce(&mut Ui)) {
     SidePanel::left("left_panel").show(ctx, |ui| {
         ScrollArea::vertical().show(ui, |ui| {
             content(ui);
@@ -40,18 +41,99 @@ pub fn script_preset(ui: &mut Ui, pages: &mut structs::Pages, database: &structs
 
     if button.clicked() {
         pages.pages.push(structs::Page {
-            title: String::from(format!("{} ({}:{})", database.name, server.ip, server.port)),
-            page_type: structs::PageType::SQLQuery(structs::SQLQueryPage {
-                database: database.database.clone(),
-                code: script.to_string(),
-                code_file_path: None,
-                sql_query_execution_status: None,
-                output_is_empty: true,
-                update_page_index: None,
-            }),
+            title: server.page_title(&database.name),
+            page_type: structs::PageType::SQLQuery(structs::SQLQueryPage::new(
+                server.id,
+                &database.name,
+                database.database.clone(),
+                script,
+            )),
             ..Default::default()
         });
 
         pages.current_page_index = (pages.pages.len() - 1) as u16;
     }
 }
+
+pub struct ChartSeries {
+    pub name: String,
+    pub color: Color32,
+    /// `[x, y]` pairs in increasing `x` order.
+    pub points: Vec<[f64; 2]>,
+}
+
+/// Line chart with the Y axis starting at zero. Hovering shows the values
+/// nearest to the pointer.
+pub fn line_chart(ui: &mut Ui, title: impl Into<RichText>, series: &[ChartSeries], format_value: impl Fn(f64) -> String) {
+    ui.vertical(|ui| {
+        ui.horizontal_wrapped(|ui| {
+            ui.label(title.into().strong());
+
+            for series in series {
+                if let Some([_, y]) = series.points.last() {
+                    ui.label(RichText::new(format!("{}: {}", series.name, format_value(*y))).color(series.color).small());
+                }
+            }
+        });
+
+        let (rect, response) = ui.allocate_exact_size(Vec2::new(ui.available_width(), 120.0), Sense::hover());
+        let painter = ui.painter_at(rect);
+        let visuals = ui.visuals();
+
+        painter.rect_filled(rect, 2.0, visuals.extreme_bg_color);
+        painter.rect_stroke(rect, 2.0, visuals.widgets.noninteractive.bg_stroke, egui::StrokeKind::Inside);
+
+        let all_points = || series.iter().flat_map(|series| series.points.iter());
+        let (Some(min_x), Some(max_x)) = (
+            all_points().map(|[x, _]| *x).reduce(f64::min),
+            all_points().map(|[x, _]| *x).reduce(f64::max),
+        ) else {
+            return;
+        };
+        let max_y = all_points().map(|[_, y]| *y).fold(0.0, f64::max);
+        let max_y = if max_y > 0.0 { max_y * 1.1 } else { 1.0 };
+        let span_x = (max_x - min_x).max(1.0);
+
+        let to_screen = |[x, y]: [f64; 2]| {
+            Pos2::new(
+                rect.left() + ((x - min_x) / span_x) as f32 * rect.width(),
+                rect.bottom() - (y / max_y) as f32 * rect.height(),
+            )
+        };
+
+        let font = FontId::proportional(10.0);
+        let text_color = visuals.weak_text_color();
+        for fraction in [0.5, 1.0] {
+            let y = rect.bottom() - fraction as f32 * rect.height();
+            painter.line_segment([Pos2::new(rect.left(), y), Pos2::new(rect.right(), y)], Stroke::new(0.5, text_color.gamma_multiply(0.3)));
+            painter.text(Pos2::new(rect.left() + 2.0, y), Align2::LEFT_TOP, format_value(max_y * fraction), font.clone(), text_color);
+        }
+
+        for series in series {
+            let points: Vec<Pos2> = series.points.iter().map(|point| to_screen(*point)).collect();
+            if points.len() > 1 {
+                painter.add(Shape::line(points, Stroke::new(1.5, series.color)));
+            } else if let Some(point) = points.first() {
+                painter.circle_filled(*point, 2.0, series.color);
+            }
+        }
+
+        if let Some(pointer) = response.hover_pos() {
+            let x = min_x + ((pointer.x - rect.left()) / rect.width()) as f64 * span_x;
+
+            painter.line_segment([Pos2::new(pointer.x, rect.top()), Pos2::new(pointer.x, rect.bottom())], Stroke::new(0.5, text_color));
+
+            egui::show_tooltip_at_pointer(ui.ctx(), ui.layer_id(), Id::new("line_chart_tooltip"), |ui| {
+                for series in series {
+                    let nearest = series.points.iter().min_by(|[a, _], [b, _]| {
+                        (a - x).abs().partial_cmp(&(b - x).abs()).unwrap_or(std::cmp::Ordering::Equal)
+                    });
+
+                    if let Some([_, y]) = nearest {
+                        ui.label(RichText::new(format!("{}: {}", series.name, format_value(*y))).color(series.color));
+                    }
+                }
+            });
+        }
+    });
+}
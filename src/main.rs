@@ -1,21 +1,35 @@
+mod cli;
+mod config;
 mod data;
 mod database;
+mod export;
 mod frames;
+mod secrets;
 mod utils;
+mod vault;
 
 use eframe::NativeOptions;
 use env_logger::Builder;
 use log::LevelFilter;
 use std::env;
+use std::path::PathBuf;
 
 fn main() {
     let mut builder = Builder::new();
 
     let args: Vec<String> = env::args().collect();
     let debug = args.contains(&String::from("--debug"));
+    let config_path = args
+        .iter()
+        .position(|arg| arg == "--config")
+        .and_then(|idx| args.get(idx + 1))
+        .map(PathBuf::from);
+    let command = cli::parse(&args);
 
     if debug {
         builder.filter_level(LevelFilter::Debug);
+    } else if command.is_some() {
+        builder.filter_level(LevelFilter::Warn);
     } else {
         builder.filter_level(LevelFilter::Info);
     }
@@ -24,14 +38,30 @@ fn main() {
     builder.filter_module("tracing", LevelFilter::Error);
     builder.filter_module("winit", LevelFilter::Error);
 
-    builder.init();
+    let logger = builder.build();
+    let max_level = logger.filter();
+    database::NoticeLogger::install(logger, max_level).unwrap();
 
-    let mut options = NativeOptions::default();
-    options.viewport = egui::ViewportBuilder::default()
-        .with_app_id("rs-postgres")
-        .with_inner_size([1024.0, 768.0])
-        .with_min_inner_size([1024.0, 768.0])
-        .with_icon(utils::load_icon());
+    if let Some(command) = command {
+        let code = match command {
+            Ok(command) => cli::run(command, config_path),
+            Err(e) => {
+                eprintln!("Error: {}\n\n{}", e, cli::USAGE);
+                2
+            }
+        };
+
+        std::process::exit(code);
+    }
+
+    let options = NativeOptions {
+        viewport: egui::ViewportBuilder::default()
+            .with_app_id("rs-postgres")
+            .with_inner_size([1024.0, 768.0])
+            .with_min_inner_size([1024.0, 768.0])
+            .with_icon(utils::load_icon()),
+        ..Default::default()
+    };
 
     eframe::run_native(
         format!(
@@ -41,7 +71,7 @@ fn main() {
         )
         .as_str(),
         options,
-        Box::new(|cc| Ok(Box::new(frames::Main::new(&cc.egui_ctx, debug)))),
+        Box::new(|cc| Ok(Box::new(frames::Main::new(&cc.egui_ctx, debug, config_path)))),
     )
     .unwrap();
 }
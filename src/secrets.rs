@@ -0,0 +1,142 @@
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::vault::VaultKey;
+
+const KEYRING_SERVICE: &str = "rs-postgres";
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretsBackend {
+    #[default]
+    Vault,
+    Keyring,
+}
+
+impl SecretsBackend {
+    pub const ALL: [SecretsBackend; 2] = [SecretsBackend::Vault, SecretsBackend::Keyring];
+}
+
+/// Storage for server passwords. `stored` is the value kept in the server's
+/// `password` field of the config, which backends may use or ignore.
+pub trait SecretStore {
+    /// Returns `None` when the backend has no password for the server.
+    fn load(&self, server_id: Uuid, stored: &str) -> Result<Option<String>, String>;
+
+    /// Saves the password and returns the value to keep in the config.
+    fn store(&self, server_id: Uuid, password: &str) -> Result<String, String>;
+
+    fn delete(&self, server_id: Uuid) -> Result<(), String>;
+}
+
+/// Opens the store of `backend`, with `keyring` being the one that talks to
+/// the platform keychain, normally a `KeyringStore`.
+pub fn open(backend: SecretsBackend, vault_key: &VaultKey, keyring: &Arc<dyn SecretStore>) -> Arc<dyn SecretStore> {
+    match backend {
+        SecretsBackend::Vault => Arc::new(VaultStore {
+            vault_key: vault_key.clone(),
+        }),
+        SecretsBackend::Keyring => keyring.clone(),
+    }
+}
+
+/// Keeps passwords in the config, encrypted with the master password key.
+pub struct VaultStore {
+    vault_key: VaultKey,
+}
+
+impl SecretStore for VaultStore {
+    fn load(&self, _server_id: Uuid, stored: &str) -> Result<Option<String>, String> {
+        self.vault_key.decrypt(stored).map(Some)
+    }
+
+    fn store(&self, _server_id: Uuid, password: &str) -> Result<String, String> {
+        self.vault_key.encrypt(password)
+    }
+
+    fn delete(&self, _server_id: Uuid) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+/// Keeps passwords in the platform keychain (Secret Service on Linux),
+/// one entry per server ID. Nothing is written to the config.
+pub struct KeyringStore;
+
+impl KeyringStore {
+    fn entry(server_id: Uuid) -> Result<keyring::Entry, String> {
+        keyring::Entry::new(KEYRING_SERVICE, &server_id.to_string())
+            .map_err(|e| format!("Keyring error: {}", e))
+    }
+}
+
+impl SecretStore for KeyringStore {
+    fn load(&self, server_id: Uuid, _stored: &str) -> Result<Option<String>, String> {
+        match Self::entry(server_id)?.get_password() {
+            Ok(password) => Ok(Some(password)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(format!("Keyring error: {}", e)),
+        }
+    }
+
+    fn store(&self, server_id: Uuid, password: &str) -> Result<String, String> {
+        Self::entry(server_id)?
+            .set_password(password)
+            .map_err(|e| format!("Keyring error: {}", e))?;
+
+        Ok(String::new())
+    }
+
+    fn delete(&self, server_id: Uuid) -> Result<(), String> {
+        match Self::entry(server_id)?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(format!("Keyring error: {}", e)),
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    use std::collections::HashMap;
+    use std::sync::Mutex;
+
+    /// In-memory keychain for tests.
+    #[derive(Default)]
+    pub struct MockStore {
+        passwords: Mutex<HashMap<Uuid, String>>,
+        writes: Mutex<usize>,
+    }
+
+    impl MockStore {
+        pub fn password(&self, server_id: Uuid) -> Option<String> {
+            self.passwords.lock().unwrap().get(&server_id).cloned()
+        }
+
+        /// Number of `store` calls made so far.
+        pub fn writes(&self) -> usize {
+            *self.writes.lock().unwrap()
+        }
+    }
+
+    impl SecretStore for MockStore {
+        fn load(&self, server_id: Uuid, _stored: &str) -> Result<Option<String>, String> {
+            Ok(self.password(server_id))
+        }
+
+        fn store(&self, server_id: Uuid, password: &str) -> Result<String, String> {
+            self.passwords.lock().unwrap().insert(server_id, password.to_string());
+            *self.writes.lock().unwrap() += 1;
+
+            Ok(String::new())
+        }
+
+        fn delete(&self, server_id: Uuid) -> Result<(), String> {
+            self.passwords.lock().unwrap().remove(&server_id);
+
+            Ok(())
+        }
+    }
+}
@@ -1,14 +1,17 @@
 use crate::data::icons;
-use ring::{aead, pbkdf2, rand};
-use ring::rand::SecureRandom;
+use ring::rand::{SecureRandom, SystemRandom};
+use ring::{aead, digest, hmac, pbkdf2};
 use sha2::Digest;
+use std::borrow::Cow;
+use std::fs::File;
+use std::io::Write;
 use std::num::NonZeroU32;
+use std::path::Path;
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 
-const SALT_LEN: usize = 16;
-const NONCE_LEN: usize = 12;
 const KEY_LEN: usize = 32;
 const ITERATION_COUNT: u32 = 100_000;
+const SCRAM_ITERATIONS: u32 = 4096;
 
 pub fn load_icon() -> egui::IconData {
 	let (icon_rgba, icon_width, icon_height) = {
@@ -34,47 +37,8 @@ pub struct EncryptedData {
 	ciphertext: Vec<u8>,
 }
 
-pub fn encrypt_string(plain_text: &str, password: impl ToString) -> Result<String, String> {
-	let rng = rand::SystemRandom::new();
-	let mut salt = vec![0u8; SALT_LEN];
-	rng.fill(&mut salt).map_err(|_| String::from("Error while generating salt"))?;
-
-	let mut nonce = vec![0u8; NONCE_LEN];
-	rng.fill(&mut nonce).map_err(|_| String::from("Error while generating nonce"))?;
-
-	let mut key = [0u8; KEY_LEN];
-	let iterations = NonZeroU32::new(ITERATION_COUNT).unwrap();
-	pbkdf2::derive(
-		pbkdf2::PBKDF2_HMAC_SHA256,
-		iterations,
-		&salt,
-		password.to_string().as_bytes(),
-		&mut key,
-	);
-
-	let unbound_key = aead::UnboundKey::new(&aead::AES_256_GCM, &key)
-		.map_err(|_| String::from("Error while creating key"))?;
-	let sealing_key = aead::LessSafeKey::new(unbound_key);
-
-	let nonce_sequence = aead::Nonce::assume_unique_for_key(
-		nonce.clone().try_into().map_err(|_| String::from("Invalid nonce format"))?
-	);
-
-	let mut in_out = plain_text.as_bytes().to_vec();
-	sealing_key
-		.seal_in_place_append_tag(nonce_sequence, aead::Aad::empty(), &mut in_out)
-		.map_err(|_| String::from("Error while encrypting"))?;
-
-	let encrypted_data = EncryptedData {
-		salt,
-		nonce,
-		ciphertext: in_out,
-	};
-
-	let serialized = serialize_encrypted_data(&encrypted_data)?;
-	Ok(serialized)
-}
-
+/// Decrypts secrets written before the vault format, where every value
+/// carried its own salt and PBKDF2-derived key.
 pub fn decrypt_string(encrypted_text: &str, password: impl ToString) -> Result<String, String> {
 	let encrypted_data = deserialize_encrypted_data(encrypted_text)?;
 
@@ -105,21 +69,6 @@ pub fn decrypt_string(encrypted_text: &str, password: impl ToString) -> Result<S
 		.map_err(|_| String::from("Error while decrypting"))
 }
 
-fn serialize_encrypted_data(data: &EncryptedData) -> Result<String, String> {
-	let mut serialized = Vec::new();
-
-	serialized.extend_from_slice(&(data.salt.len() as u32).to_be_bytes());
-	serialized.extend_from_slice(&data.salt);
-
-	serialized.extend_from_slice(&(data.nonce.len() as u32).to_be_bytes());
-	serialized.extend_from_slice(&data.nonce);
-
-	serialized.extend_from_slice(&(data.ciphertext.len() as u32).to_be_bytes());
-	serialized.extend_from_slice(&data.ciphertext);
-
-	Ok(BASE64.encode(serialized))
-}
-
 fn deserialize_encrypted_data(serialized: &str) -> Result<EncryptedData, String> {
 	let bytes = BASE64.decode(serialized)
 		.map_err(|_| String::from("Error while decoding base64"))?;
@@ -173,3 +122,91 @@ pub fn create_checksum(text: impl ToString) -> String {
 
     format!("{:x}", hasher.finalize())
 }
+
+pub fn write_atomic(path: &Path, contents: &str) -> std::io::Result<()> {
+	let file_name = path
+		.file_name()
+		.map(|name| name.to_string_lossy().to_string())
+		.unwrap_or_default();
+	let tmp_path = path.with_file_name(format!("{}.tmp", file_name));
+
+	{
+		let mut file = File::create(&tmp_path)?;
+		file.write_all(contents.as_bytes())?;
+		file.sync_all()?;
+	}
+
+	std::fs::rename(&tmp_path, path)
+}
+
+/// PostgreSQL reserved key words, which can't be used as bare identifiers.
+const RESERVED_WORDS: &[&str] = &[
+	"all", "analyse", "analyze", "and", "any", "array", "as", "asc", "asymmetric", "authorization",
+	"binary", "both", "case", "cast", "check", "collate", "collation", "column", "concurrently",
+	"constraint", "create", "cross", "current_catalog", "current_date", "current_role",
+	"current_schema", "current_time", "current_timestamp", "current_user", "default", "deferrable",
+	"desc", "distinct", "do", "else", "end", "except", "false", "fetch", "for", "foreign", "freeze",
+	"from", "full", "grant", "group", "having", "ilike", "in", "initially", "inner", "intersect",
+	"into", "is", "isnull", "join", "lateral", "leading", "left", "like", "limit", "localtime",
+	"localtimestamp", "natural", "not", "notnull", "null", "offset", "on", "only", "or", "order",
+	"outer", "overlaps", "placing", "primary", "references", "returning", "right", "select",
+	"session_user", "similar", "some", "symmetric", "system_user", "table", "tablesample", "then",
+	"to", "trailing", "true", "union", "unique", "user", "using", "variadic", "verbose", "when",
+	"where", "window", "with",
+];
+
+/// Quotes an identifier for use in generated SQL, leaving simple lower-case
+/// names bare.
+pub fn quote_ident(ident: &str) -> String {
+	let is_simple = ident
+		.chars()
+		.next()
+		.is_some_and(|c| c.is_ascii_lowercase() || c == '_')
+		&& ident
+			.chars()
+			.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '$')
+		&& !RESERVED_WORDS.contains(&ident);
+
+	if is_simple {
+		ident.to_string()
+	} else {
+		format!("\"{}\"", ident.replace('"', "\"\""))
+	}
+}
+
+pub fn quote_literal(value: &str) -> String {
+	format!("'{}'", value.replace('\'', "''"))
+}
+
+/// Hashes a role password into the SCRAM-SHA-256 verifier the server keeps,
+/// like psql's \password does, so the plain text never leaves the client.
+pub fn scram_sha_256(password: &str) -> Result<String, String> {
+	let mut salt = [0u8; 16];
+	SystemRandom::new()
+		.fill(&mut salt)
+		.map_err(|_| String::from("Error while generating salt"))?;
+
+	// The server uses the password as is when SASLprep rejects it.
+	let password = stringprep::saslprep(password).unwrap_or(Cow::Borrowed(password));
+
+	let mut salted_password = [0u8; 32];
+	pbkdf2::derive(
+		pbkdf2::PBKDF2_HMAC_SHA256,
+		NonZeroU32::new(SCRAM_ITERATIONS).unwrap(),
+		&salt,
+		password.as_bytes(),
+		&mut salted_password,
+	);
+
+	let key = hmac::Key::new(hmac::HMAC_SHA256, &salted_password);
+	let stored_key = digest::digest(&digest::SHA256, hmac::sign(&key, b"Client Key").as_ref());
+	let server_key = hmac::sign(&key, b"Server Key");
+
+	Ok(format!(
+		"SCRAM-SHA-256${}:{}${}:{}",
+		SCRAM_ITERATIONS,
+		BASE64.encode(salt),
+		BASE64.encode(stored_key),
+		BASE64.encode(server_key)
+	))
+}
@@ -0,0 +1,360 @@
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+use ring::aead;
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+pub const VAULT_VERSION: u32 = 2;
+
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const KEY_LEN: usize = 32;
+const KEY_CHECK: &str = "rs-postgres";
+const RECOVERY_KEY_LEN: usize = 16;
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(tag = "algorithm", rename_all = "lowercase")]
+pub enum Kdf {
+    Argon2id {
+        memory_kib: u32,
+        iterations: u32,
+        parallelism: u32,
+    },
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Self::Argon2id {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
+impl Kdf {
+    fn derive(&self, password: &str, salt: &[u8]) -> Result<VaultKey, String> {
+        let mut key = [0u8; KEY_LEN];
+
+        match self {
+            Self::Argon2id {
+                memory_kib,
+                iterations,
+                parallelism,
+            } => {
+                let params = Params::new(*memory_kib, *iterations, *parallelism, Some(KEY_LEN))
+                    .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+
+                Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+                    .hash_password_into(password.as_bytes(), salt, &mut key)
+                    .map_err(|e| format!("Error while deriving key: {}", e))?;
+            }
+        }
+
+        Ok(VaultKey(key))
+    }
+}
+
+/// Master password parameters stored in the config. Secrets are encrypted
+/// with a random key, which is stored wrapped by the key derived from the
+/// master password and, optionally, by one derived from a recovery key.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Vault {
+    pub version: u32,
+    pub kdf: Kdf,
+    pub salt: String,
+    /// Version 1 only: secrets were encrypted with the password-derived key
+    /// itself, and this known value sealed with it verified the password.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_check: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wrapped_key: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recovery: Option<KeySlot>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct KeySlot {
+    pub kdf: Kdf,
+    pub salt: String,
+    pub wrapped_key: String,
+}
+
+impl KeySlot {
+    fn create(secret: &str, vault_key: &VaultKey) -> Result<Self, String> {
+        let salt = random_bytes::<SALT_LEN>()?;
+        let kdf = Kdf::default();
+
+        Ok(Self {
+            wrapped_key: kdf.derive(secret, &salt)?.wrap(vault_key)?,
+            kdf,
+            salt: BASE64.encode(salt),
+        })
+    }
+
+    fn open(&self, secret: &str) -> Result<VaultKey, String> {
+        let salt = BASE64
+            .decode(&self.salt)
+            .map_err(|_| String::from("Error while decoding salt"))?;
+
+        self.kdf.derive(secret, &salt)?.unwrap(&self.wrapped_key)
+    }
+}
+
+impl Vault {
+    pub fn create(password: &str) -> Result<(Self, VaultKey), String> {
+        let vault_key = VaultKey(random_bytes::<KEY_LEN>()?);
+        let slot = KeySlot::create(password, &vault_key)?;
+
+        let vault = Self {
+            version: VAULT_VERSION,
+            kdf: slot.kdf,
+            salt: slot.salt,
+            key_check: None,
+            wrapped_key: Some(slot.wrapped_key),
+            recovery: None,
+        };
+
+        Ok((vault, vault_key))
+    }
+
+    pub fn needs_upgrade(&self) -> bool {
+        self.version < VAULT_VERSION
+    }
+
+    pub fn unlock(&self, password: &str) -> Result<VaultKey, String> {
+        match (self.version, &self.key_check, &self.wrapped_key) {
+            (1, Some(key_check), _) => {
+                let salt = BASE64
+                    .decode(&self.salt)
+                    .map_err(|_| String::from("Error while decoding salt"))?;
+                let key = self.kdf.derive(password, &salt)?;
+
+                match key.decrypt(key_check) {
+                    Ok(check) if check == KEY_CHECK => Ok(key),
+                    _ => Err(String::from("Incorrect password")),
+                }
+            }
+            (VAULT_VERSION, _, Some(wrapped_key)) => KeySlot {
+                kdf: self.kdf.clone(),
+                salt: self.salt.clone(),
+                wrapped_key: wrapped_key.clone(),
+            }
+            .open(password)
+            .map_err(|_| String::from("Incorrect password")),
+            _ => Err(format!("Unsupported vault version: {}", self.version)),
+        }
+    }
+
+    pub fn unlock_with_recovery_key(&self, recovery_key: &str) -> Result<VaultKey, String> {
+        let Some(recovery) = &self.recovery else {
+            return Err(String::from("No recovery key has been set up"));
+        };
+
+        recovery
+            .open(&normalize_recovery_key(recovery_key))
+            .map_err(|_| String::from("Incorrect recovery key"))
+    }
+
+    /// Replaces the recovery slot and returns the new recovery key, which is
+    /// not stored anywhere and has to be shown to the user.
+    pub fn add_recovery_key(&mut self, vault_key: &VaultKey) -> Result<String, String> {
+        let recovery_key = random_bytes::<RECOVERY_KEY_LEN>()?
+            .chunks(2)
+            .map(|chunk| chunk.iter().map(|byte| format!("{:02X}", byte)).collect::<String>())
+            .collect::<Vec<String>>()
+            .join("-");
+
+        self.recovery = Some(KeySlot::create(
+            &normalize_recovery_key(&recovery_key),
+            vault_key,
+        )?);
+
+        Ok(recovery_key)
+    }
+}
+
+fn normalize_recovery_key(recovery_key: &str) -> String {
+    recovery_key
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_uppercase())
+        .collect()
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N], String> {
+    let mut bytes = [0u8; N];
+    SystemRandom::new()
+        .fill(&mut bytes)
+        .map_err(|_| String::from("Error while generating random data"))?;
+
+    Ok(bytes)
+}
+
+/// Key shared by every secret in the config.
+#[derive(Clone)]
+pub struct VaultKey([u8; KEY_LEN]);
+
+impl Drop for VaultKey {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl VaultKey {
+    fn wrap(&self, vault_key: &VaultKey) -> Result<String, String> {
+        self.encrypt(&BASE64.encode(vault_key.0))
+    }
+
+    fn unwrap(&self, wrapped_key: &str) -> Result<VaultKey, String> {
+        let key = BASE64
+            .decode(self.decrypt(wrapped_key)?)
+            .map_err(|_| String::from("Error while decoding key"))?;
+
+        key.try_into()
+            .map(VaultKey)
+            .map_err(|_| String::from("Invalid key length"))
+    }
+
+    fn sealing_key(&self) -> Result<aead::LessSafeKey, String> {
+        let unbound_key = aead::UnboundKey::new(&aead::AES_256_GCM, &self.0)
+            .map_err(|_| String::from("Error while creating key"))?;
+
+        Ok(aead::LessSafeKey::new(unbound_key))
+    }
+
+    /// Returns base64 of `nonce || ciphertext || tag`.
+    pub fn encrypt(&self, plain_text: &str) -> Result<String, String> {
+        let nonce = random_bytes::<NONCE_LEN>()?;
+
+        let mut in_out = plain_text.as_bytes().to_vec();
+        self.sealing_key()?
+            .seal_in_place_append_tag(
+                aead::Nonce::assume_unique_for_key(nonce),
+                aead::Aad::empty(),
+                &mut in_out,
+            )
+            .map_err(|_| String::from("Error while encrypting"))?;
+
+        let mut serialized = nonce.to_vec();
+        serialized.extend_from_slice(&in_out);
+
+        Ok(BASE64.encode(serialized))
+    }
+
+    pub fn decrypt(&self, encrypted_text: &str) -> Result<String, String> {
+        let bytes = BASE64
+            .decode(encrypted_text)
+            .map_err(|_| String::from("Error while decoding base64"))?;
+
+        if bytes.len() < NONCE_LEN {
+            return Err(String::from("Not enough data for reading nonce"));
+        }
+
+        let (nonce, ciphertext) = bytes.split_at(NONCE_LEN);
+        let nonce = aead::Nonce::try_assume_unique_for_key(nonce)
+            .map_err(|_| String::from("Invalid nonce format"))?;
+
+        let mut ciphertext = ciphertext.to_vec();
+        let plaintext = self
+            .sealing_key()?
+            .open_in_place(nonce, aead::Aad::empty(), &mut ciphertext)
+            .map_err(|_| String::from("Error while decrypting"))?;
+
+        String::from_utf8(plaintext.to_vec()).map_err(|_| String::from("Error while decrypting"))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// Builds a version 1 vault, where secrets are encrypted with the
+    /// password-derived key itself.
+    pub(crate) fn key_check_vault(password: &str) -> (Vault, VaultKey) {
+        let kdf = Kdf::default();
+        let salt = random_bytes::<SALT_LEN>().unwrap();
+        let key = kdf.derive(password, &salt).unwrap();
+
+        let vault = Vault {
+            version: 1,
+            kdf,
+            salt: BASE64.encode(salt),
+            key_check: Some(key.encrypt(KEY_CHECK).unwrap()),
+            wrapped_key: None,
+            recovery: None,
+        };
+
+        (vault, key)
+    }
+
+    /// Flips one bit in the decoded bytes of a base64 string.
+    fn tamper(encoded: &str, index: usize) -> String {
+        let mut bytes = BASE64.decode(encoded).unwrap();
+        bytes[index] ^= 0x01;
+
+        BASE64.encode(bytes)
+    }
+
+    #[test]
+    fn unlocks_with_master_password() {
+        let (vault, vault_key) = Vault::create("master").unwrap();
+        let encrypted = vault_key.encrypt("secret").unwrap();
+
+        assert_eq!(vault.version, VAULT_VERSION);
+        assert!(!vault.needs_upgrade());
+        assert_eq!(vault.unlock("master").unwrap().decrypt(&encrypted).unwrap(), "secret");
+    }
+
+    #[test]
+    fn rejects_wrong_master_password() {
+        let (vault, _) = Vault::create("master").unwrap();
+
+        assert_eq!(vault.unlock("Master").err().unwrap(), "Incorrect password");
+    }
+
+    #[test]
+    fn rejects_tampered_wrapped_key() {
+        let (mut vault, _) = Vault::create("master").unwrap();
+        let wrapped_key = vault.wrapped_key.take().unwrap();
+        vault.wrapped_key = Some(tamper(&wrapped_key, NONCE_LEN + 4));
+
+        assert!(vault.unlock("master").is_err());
+    }
+
+    #[test]
+    fn rejects_tampered_ciphertext() {
+        let (_, vault_key) = Vault::create("master").unwrap();
+        let encrypted = vault_key.encrypt("secret").unwrap();
+
+        assert!(vault_key.decrypt(&tamper(&encrypted, NONCE_LEN)).is_err());
+        assert!(vault_key.decrypt(&tamper(&encrypted, 0)).is_err());
+    }
+
+    #[test]
+    fn unlocks_with_recovery_key() {
+        let (mut vault, vault_key) = Vault::create("master").unwrap();
+        let encrypted = vault_key.encrypt("secret").unwrap();
+
+        assert!(vault.unlock_with_recovery_key("0000").is_err());
+
+        let recovery_key = vault.add_recovery_key(&vault_key).unwrap();
+        let recovered = vault
+            .unlock_with_recovery_key(&recovery_key.to_lowercase().replace('-', " "))
+            .unwrap();
+
+        assert_eq!(recovered.decrypt(&encrypted).unwrap(), "secret");
+        assert!(vault.unlock_with_recovery_key("0000-0000").is_err());
+    }
+
+    #[test]
+    fn upgrades_key_check_vault() {
+        let (vault, key) = key_check_vault("master");
+        let encrypted = key.encrypt("secret").unwrap();
+
+        assert!(vault.needs_upgrade());
+        assert!(vault.unlock("Master").is_err());
+        assert_eq!(vault.unlock("master").unwrap().decrypt(&encrypted).unwrap(), "secret");
+    }
+}